@@ -0,0 +1,89 @@
+// Full against incremental evaluation of a generated graph of 10k nodes, run
+// with `cargo bench --bench graph_process`.
+//
+// The graph is 100 chains of 100 nodes: a head, then Map Range nodes each fed
+// by the one before. Heads of moving chains are Expression nodes following the
+// time, the others Constant Number nodes, so the share of moving chains is how
+// much of the graph changes every frame.
+use std::time::{Duration, Instant};
+
+use wovmoh_lib::core::graph::Graph;
+use wovmoh_lib::core::node::{NodeKind, NodeUIState};
+use wovmoh_lib::types::data_type::DataValue;
+
+const CHAINS: usize = 100;
+const LENGTH: usize = 100;
+const FRAMES: u32 = 200;
+const DELTA: f64 = 1.0 / 40.0;
+
+fn generate(moving: usize) -> Graph {
+    let mut graph = Graph::new(0);
+    let ui_state = NodeUIState {
+        position: (0.0, 0.0),
+    };
+
+    // Every node has one output, so output ids are node ids
+    let mut input = 0;
+    for chain in 0..CHAINS {
+        let head = chain * LENGTH;
+        if chain < moving {
+            graph.add_node(NodeKind::Expression, ui_state.clone());
+            graph.set_node_text(head, "sin(t) * 0.5 + 0.5");
+            input += 4;
+        } else {
+            graph.add_node(NodeKind::ConstantNumber, ui_state.clone());
+            graph.set_input_value(input, DataValue::Number(0.5));
+            input += 1;
+        }
+
+        for node in head + 1..head + LENGTH {
+            graph.add_node(NodeKind::MapRange, ui_state.clone());
+            // Previous node -> "Value"
+            graph.connect(node - 1, input);
+            input += NodeKind::MapRange.descriptor().inputs.len();
+        }
+    }
+    graph
+}
+
+// Average time of a frame and the nodes run in the last one
+fn run(graph: &mut Graph) -> (Duration, usize) {
+    // The first frame runs everything
+    graph.tick(DELTA);
+
+    let start = Instant::now();
+    for _ in 0..FRAMES {
+        graph.tick(DELTA);
+    }
+    (start.elapsed() / FRAMES, graph.processed())
+}
+
+fn main() {
+    println!("{} nodes, {} frames", CHAINS * LENGTH, FRAMES);
+
+    for moving in [0, 10, CHAINS] {
+        let mut full = generate(moving);
+        full.set_incremental(false);
+        let mut incremental = generate(moving);
+
+        let (full_time, full_run) = run(&mut full);
+        let (incremental_time, incremental_run) = run(&mut incremental);
+
+        for output in 0..CHAINS * LENGTH {
+            assert!(full.get_output_value(output) == incremental.get_output_value(output));
+        }
+
+        println!("{}% of the chains moving", moving * 100 / CHAINS);
+        println!(
+            "  full        {:>9.3} ms/frame, {:>5} nodes run",
+            full_time.as_secs_f64() * 1000.0,
+            full_run
+        );
+        println!(
+            "  incremental {:>9.3} ms/frame, {:>5} nodes run, {:.1}x",
+            incremental_time.as_secs_f64() * 1000.0,
+            incremental_run,
+            full_time.as_secs_f64() / incremental_time.as_secs_f64()
+        );
+    }
+}
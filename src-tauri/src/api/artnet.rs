@@ -0,0 +1,31 @@
+// Tauri Imports
+use tauri::async_runtime::Mutex;
+use tauri::State;
+
+use crate::dto::artnet_node_dto::ArtnetNodeDto;
+use crate::managers::inputs_manager::InputsManager;
+
+#[tauri::command]
+pub async fn get_artnet_nodes(
+    state: State<'_, Mutex<InputsManager>>,
+) -> Result<Vec<ArtnetNodeDto>, ()> {
+    let inputs = state.lock().await;
+
+    Ok(match inputs.artnet() {
+        Some(artnet) => artnet.nodes().iter().map(|x| x.to_dto()).collect(),
+        None => Vec::new(),
+    })
+}
+
+// Replies arrive through the "artnet-nodes" event
+#[tauri::command]
+pub async fn poll_artnet_nodes(state: State<'_, Mutex<InputsManager>>) -> Result<(), ()> {
+    let inputs = state.lock().await;
+
+    match inputs.artnet() {
+        Some(artnet) => artnet
+            .poll()
+            .map_err(|e| eprintln!("ArtPoll failed: {}", e)),
+        None => Err(()),
+    }
+}
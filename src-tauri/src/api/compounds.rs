@@ -0,0 +1,100 @@
+// Tauri Imports
+use tauri::async_runtime::Mutex;
+use tauri::State;
+
+use std::time::Instant;
+
+use crate::core::compound::CompoundId;
+use crate::core::fragment::Fragment;
+use crate::core::graph::GraphId;
+use crate::core::node::NodeId;
+use crate::dto::compound_dto::CompoundDto;
+use crate::dto::graph_delta_dto::GraphDeltaDto;
+use crate::managers::graph_manager::GraphManager;
+
+#[tauri::command]
+pub async fn get_compounds(graphs: State<'_, Mutex<GraphManager>>) -> Result<Vec<CompoundDto>, ()> {
+    Ok(graphs
+        .lock()
+        .await
+        .compounds()
+        .defs()
+        .map(|x| x.into())
+        .collect())
+}
+
+// fragment is clipboard JSON from copy_nodes, with GroupInput and GroupOutput
+// nodes for the pins
+#[tauri::command]
+pub async fn add_compound(
+    name: String,
+    fragment: String,
+    graphs: State<'_, Mutex<GraphManager>>,
+) -> Result<CompoundId, String> {
+    let fragment = Fragment::from_json(&fragment).map_err(|x| x.to_string())?;
+    graphs.lock().await.add_compound(&name, fragment)
+}
+
+// Updates every Compound node using it
+#[tauri::command]
+pub async fn set_compound(
+    id: CompoundId,
+    fragment: String,
+    graphs: State<'_, Mutex<GraphManager>>,
+) -> Result<(), String> {
+    let fragment = Fragment::from_json(&fragment).map_err(|x| x.to_string())?;
+    graphs.lock().await.set_compound(id, fragment)
+}
+
+#[tauri::command]
+pub async fn rename_compound(
+    id: CompoundId,
+    name: String,
+    graphs: State<'_, Mutex<GraphManager>>,
+) -> Result<(), String> {
+    match graphs.lock().await.rename_compound(id, &name) {
+        true => Ok(()),
+        false => Err(format!("Compound {} doesn't exist", id)),
+    }
+}
+
+#[tauri::command]
+pub async fn remove_compound(
+    id: CompoundId,
+    graphs: State<'_, Mutex<GraphManager>>,
+) -> Result<(), ()> {
+    graphs.lock().await.remove_compound(id);
+    Ok(())
+}
+
+// A new compound of the nodes, a Compound node using it takes their place
+#[tauri::command]
+pub async fn group_nodes(
+    graph_id: GraphId,
+    nodes: Vec<NodeId>,
+    name: String,
+    graphs: State<'_, Mutex<GraphManager>>,
+) -> Result<GraphDeltaDto, String> {
+    let mut graphs = graphs.lock().await;
+
+    let before = graphs.get_graph(graph_id).to_dto();
+    graphs.group_nodes(graph_id, &nodes, &name)?;
+
+    Ok(graphs.get_graph(graph_id).delta(&before))
+}
+
+// Back into plain nodes, the compound stays in the library
+#[tauri::command]
+pub async fn expand_compound(
+    graph_id: GraphId,
+    node_id: NodeId,
+    graphs: State<'_, Mutex<GraphManager>>,
+) -> Result<GraphDeltaDto, String> {
+    let mut graphs = graphs.lock().await;
+
+    let graph = graphs.get_graph_mut(graph_id);
+    let before = graph.to_dto();
+    graph.expand_compound(node_id, Instant::now())?;
+
+    Ok(graph.delta(&before))
+}
@@ -2,7 +2,13 @@
 use tauri::async_runtime::Mutex;
 use tauri::State;
 
-use crate::core::node::{NodeKind, NodeUIState};
+use std::time::Instant;
+
+use crate::core::fragment::Fragment;
+use crate::core::graph::GraphId;
+use crate::core::history::GraphEdit;
+use crate::core::node::{NodeId, NodeKind, NodeUIState};
+use crate::dto::graph_delta_dto::GraphDeltaDto;
 use crate::dto::graph_dto::GraphDto;
 use crate::managers::graph_manager::GraphManager;
 
@@ -37,3 +43,138 @@ pub async fn get_graph_dto(
 
     Ok(graph.to_dto())
 }
+
+#[tauri::command]
+pub async fn set_node_text(
+    graph_id: usize,
+    node_id: NodeId,
+    text: String,
+    state: State<'_, Mutex<GraphManager>>,
+) -> Result<GraphDto, ()> {
+    let mut graph_manager = state.lock().await;
+
+    let graph = graph_manager.get_graph_mut(graph_id);
+    // Recorded for undo, an unknown node changes nothing
+    let _ = graph.edit(
+        GraphEdit::SetNodeText {
+            node: node_id,
+            text,
+        },
+        Instant::now(),
+    );
+
+    Ok(graph.to_dto())
+}
+
+// Any change to the graph the user can undo
+#[tauri::command]
+pub async fn edit_graph(
+    graph_id: GraphId,
+    edit: GraphEdit,
+    state: State<'_, Mutex<GraphManager>>,
+) -> Result<GraphDeltaDto, String> {
+    let mut graph_manager = state.lock().await;
+
+    let graph = graph_manager.get_graph_mut(graph_id);
+    let before = graph.to_dto();
+    graph.edit(edit, Instant::now())?;
+
+    Ok(graph.delta(&before))
+}
+
+// Edits until end_graph_transaction undo as one step, e.g. moving a selection
+#[tauri::command]
+pub async fn begin_graph_transaction(
+    graph_id: GraphId,
+    state: State<'_, Mutex<GraphManager>>,
+) -> Result<(), ()> {
+    state
+        .lock()
+        .await
+        .get_graph_mut(graph_id)
+        .begin_transaction();
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn end_graph_transaction(
+    graph_id: GraphId,
+    state: State<'_, Mutex<GraphManager>>,
+) -> Result<(), ()> {
+    state.lock().await.get_graph_mut(graph_id).end_transaction();
+    Ok(())
+}
+
+// The selection as JSON for the clipboard
+#[tauri::command]
+pub async fn copy_nodes(
+    graph_id: GraphId,
+    nodes: Vec<NodeId>,
+    state: State<'_, Mutex<GraphManager>>,
+) -> Result<String, String> {
+    let graph_manager = state.lock().await;
+
+    let fragment = graph_manager.get_graph(graph_id).extract_subgraph(&nodes);
+    fragment.to_json().map_err(|x| x.to_string())
+}
+
+// Clipboard JSON from copy_nodes, in this graph or any other. The pasted
+// nodes are the new ones in the delta.
+#[tauri::command]
+pub async fn paste_nodes(
+    graph_id: GraphId,
+    fragment: String,
+    offset: (f64, f64),
+    state: State<'_, Mutex<GraphManager>>,
+) -> Result<GraphDeltaDto, String> {
+    let fragment = Fragment::from_json(&fragment).map_err(|x| x.to_string())?;
+    let mut graph_manager = state.lock().await;
+
+    let graph = graph_manager.get_graph_mut(graph_id);
+    let before = graph.to_dto();
+    graph.insert_fragment(&fragment, offset)?;
+
+    Ok(graph.delta(&before))
+}
+
+#[tauri::command]
+pub async fn duplicate_nodes(
+    graph_id: GraphId,
+    nodes: Vec<NodeId>,
+    offset: (f64, f64),
+    state: State<'_, Mutex<GraphManager>>,
+) -> Result<GraphDeltaDto, String> {
+    let mut graph_manager = state.lock().await;
+
+    let graph = graph_manager.get_graph_mut(graph_id);
+    let before = graph.to_dto();
+    let fragment = graph.extract_subgraph(&nodes);
+    graph.insert_fragment(&fragment, offset)?;
+
+    Ok(graph.delta(&before))
+}
+
+// None when there is nothing to undo
+#[tauri::command]
+pub async fn undo(
+    graph_id: GraphId,
+    state: State<'_, Mutex<GraphManager>>,
+) -> Result<Option<GraphDeltaDto>, ()> {
+    let mut graph_manager = state.lock().await;
+
+    let graph = graph_manager.get_graph_mut(graph_id);
+    let before = graph.to_dto();
+    Ok(graph.undo().then(|| graph.delta(&before)))
+}
+
+#[tauri::command]
+pub async fn redo(
+    graph_id: GraphId,
+    state: State<'_, Mutex<GraphManager>>,
+) -> Result<Option<GraphDeltaDto>, ()> {
+    let mut graph_manager = state.lock().await;
+
+    let graph = graph_manager.get_graph_mut(graph_id);
+    let before = graph.to_dto();
+    Ok(graph.redo().then(|| graph.delta(&before)))
+}
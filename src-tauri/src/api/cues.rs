@@ -0,0 +1,87 @@
+// Tauri Imports
+use tauri::async_runtime::Mutex;
+use tauri::State;
+
+use crate::cues::{Cue, CueListId};
+use crate::dto::cue_list_dto::CueListDto;
+use crate::managers::cues_manager::CuesManager;
+
+fn unknown_list(id: CueListId) -> String {
+    format!("Unknown cue list {}", id)
+}
+
+#[tauri::command]
+pub async fn get_cue_lists(cues: State<'_, Mutex<CuesManager>>) -> Result<Vec<CueListDto>, ()> {
+    Ok(cues.lock().await.to_dto())
+}
+
+#[tauri::command]
+pub async fn add_cue_list(
+    name: String,
+    cues: State<'_, Mutex<CuesManager>>,
+) -> Result<CueListId, ()> {
+    Ok(cues.lock().await.add_list(&name))
+}
+
+#[tauri::command]
+pub async fn remove_cue_list(id: CueListId, cues: State<'_, Mutex<CuesManager>>) -> Result<(), ()> {
+    cues.lock().await.remove_list(id);
+    Ok(())
+}
+
+// Adds the cue, or replaces the one with the same number
+#[tauri::command]
+pub async fn set_cue(
+    list: CueListId,
+    cue: Cue,
+    cues: State<'_, Mutex<CuesManager>>,
+) -> Result<(), String> {
+    match cues.lock().await.set_cue(list, cue) {
+        true => Ok(()),
+        false => Err(unknown_list(list)),
+    }
+}
+
+#[tauri::command]
+pub async fn remove_cue(
+    list: CueListId,
+    number: f64,
+    cues: State<'_, Mutex<CuesManager>>,
+) -> Result<bool, ()> {
+    Ok(cues.lock().await.remove_cue(list, number))
+}
+
+// False at the end of the list
+#[tauri::command]
+pub async fn cue_go(list: CueListId, cues: State<'_, Mutex<CuesManager>>) -> Result<bool, ()> {
+    Ok(cues.lock().await.go(list))
+}
+
+// False at the start of the list
+#[tauri::command]
+pub async fn cue_back(list: CueListId, cues: State<'_, Mutex<CuesManager>>) -> Result<bool, ()> {
+    Ok(cues.lock().await.back(list))
+}
+
+#[tauri::command]
+pub async fn cue_goto(
+    list: CueListId,
+    number: f64,
+    cues: State<'_, Mutex<CuesManager>>,
+) -> Result<(), String> {
+    match cues.lock().await.goto(list, number) {
+        true => Ok(()),
+        false => Err(format!("No cue {} in cue list {}", number, list)),
+    }
+}
+
+#[tauri::command]
+pub async fn cue_release(
+    list: CueListId,
+    cues: State<'_, Mutex<CuesManager>>,
+) -> Result<(), String> {
+    match cues.lock().await.release(list) {
+        true => Ok(()),
+        false => Err(unknown_list(list)),
+    }
+}
@@ -0,0 +1,147 @@
+use std::path::PathBuf;
+
+// Tauri Imports
+use tauri::async_runtime::Mutex;
+use tauri::State;
+
+use crate::dmx::PortAddress;
+use crate::dto::fixtures_dto::{FixtureImportDto, PatchDto};
+use crate::fixtures::group::GroupId;
+use crate::fixtures::ofl;
+use crate::fixtures::patch::FixtureId;
+use crate::fixtures::profile::FixtureProfile;
+use crate::managers::fixtures_manager::FixturesManager;
+use crate::managers::graph_manager::GraphManager;
+
+#[tauri::command]
+pub async fn get_fixture_profiles(
+    fixtures: State<'_, Mutex<FixturesManager>>,
+) -> Result<Vec<FixtureProfile>, ()> {
+    Ok(fixtures.lock().await.library().profiles().to_vec())
+}
+
+// Open Fixture Library JSON, the folder it is in names the manufacturer.
+// Replaces the profile with the same key, patched fixtures included. The
+// warnings also list fixtures that now overlap or lost their mode.
+#[tauri::command]
+pub async fn import_ofl_fixture(
+    path: PathBuf,
+    graphs: State<'_, Mutex<GraphManager>>,
+    fixtures: State<'_, Mutex<FixturesManager>>,
+) -> Result<FixtureImportDto, String> {
+    let import = ofl::load(&path).map_err(|e| e.to_string())?;
+    let profile = import.profile.key();
+    let mut warnings = import.warnings;
+
+    let mut graphs = graphs.lock().await;
+    let mut fixtures = fixtures.lock().await;
+    warnings.extend(fixtures.add_profile(import.profile));
+    graphs.set_fixtures(fixtures.fixture_map());
+
+    Ok(FixtureImportDto { profile, warnings })
+}
+
+#[tauri::command]
+pub async fn get_patch(fixtures: State<'_, Mutex<FixturesManager>>) -> Result<PatchDto, ()> {
+    Ok(fixtures.lock().await.to_dto())
+}
+
+// Profile is the manufacturer/name key, see FixtureProfile::key
+#[tauri::command]
+pub async fn patch_fixture(
+    name: String,
+    profile: String,
+    mode: String,
+    universe: PortAddress,
+    address: u16,
+    graphs: State<'_, Mutex<GraphManager>>,
+    fixtures: State<'_, Mutex<FixturesManager>>,
+) -> Result<FixtureId, String> {
+    let mut graphs = graphs.lock().await;
+    let mut fixtures = fixtures.lock().await;
+
+    let id = fixtures
+        .patch(&name, &profile, &mode, universe, address)
+        .map_err(|e| e.to_string())?;
+    graphs.set_fixtures(fixtures.fixture_map());
+    Ok(id)
+}
+
+#[tauri::command]
+pub async fn unpatch_fixture(
+    id: FixtureId,
+    graphs: State<'_, Mutex<GraphManager>>,
+    fixtures: State<'_, Mutex<FixturesManager>>,
+) -> Result<(), ()> {
+    let mut graphs = graphs.lock().await;
+    let mut fixtures = fixtures.lock().await;
+
+    fixtures.unpatch(id);
+    graphs.set_fixtures(fixtures.fixture_map());
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn move_fixture(
+    id: FixtureId,
+    universe: PortAddress,
+    address: u16,
+    graphs: State<'_, Mutex<GraphManager>>,
+    fixtures: State<'_, Mutex<FixturesManager>>,
+) -> Result<(), String> {
+    let mut graphs = graphs.lock().await;
+    let mut fixtures = fixtures.lock().await;
+
+    fixtures
+        .move_to(id, universe, address)
+        .map_err(|e| e.to_string())?;
+    graphs.set_fixtures(fixtures.fixture_map());
+    Ok(())
+}
+
+// Fixtures in the order values spread over them
+#[tauri::command]
+pub async fn add_fixture_group(
+    name: String,
+    fixtures: Vec<FixtureId>,
+    graphs: State<'_, Mutex<GraphManager>>,
+    manager: State<'_, Mutex<FixturesManager>>,
+) -> Result<GroupId, String> {
+    let mut graphs = graphs.lock().await;
+    let mut manager = manager.lock().await;
+
+    let id = manager
+        .add_group(&name, fixtures)
+        .map_err(|e| e.to_string())?;
+    graphs.set_fixtures(manager.fixture_map());
+    Ok(id)
+}
+
+#[tauri::command]
+pub async fn set_fixture_group(
+    id: GroupId,
+    fixtures: Vec<FixtureId>,
+    graphs: State<'_, Mutex<GraphManager>>,
+    manager: State<'_, Mutex<FixturesManager>>,
+) -> Result<(), String> {
+    let mut graphs = graphs.lock().await;
+    let mut manager = manager.lock().await;
+
+    manager.set_group(id, fixtures).map_err(|e| e.to_string())?;
+    graphs.set_fixtures(manager.fixture_map());
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn remove_fixture_group(
+    id: GroupId,
+    graphs: State<'_, Mutex<GraphManager>>,
+    fixtures: State<'_, Mutex<FixturesManager>>,
+) -> Result<(), ()> {
+    let mut graphs = graphs.lock().await;
+    let mut fixtures = fixtures.lock().await;
+
+    fixtures.remove_group(id);
+    graphs.set_fixtures(fixtures.fixture_map());
+    Ok(())
+}
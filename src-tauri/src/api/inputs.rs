@@ -0,0 +1,14 @@
+// Tauri Imports
+use tauri::async_runtime::Mutex;
+use tauri::State;
+
+use crate::dto::input_channel_dto::InputChannelDto;
+use crate::managers::inputs_manager::InputsManager;
+
+// Every input channel with its latest value, for the monitor view
+#[tauri::command]
+pub async fn get_input_channels(
+    state: State<'_, Mutex<InputsManager>>,
+) -> Result<Vec<InputChannelDto>, ()> {
+    Ok(state.lock().await.to_dto())
+}
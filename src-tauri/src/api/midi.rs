@@ -0,0 +1,34 @@
+// Tauri Imports
+use tauri::async_runtime::Mutex;
+use tauri::State;
+
+use crate::core::node::NodeId;
+use crate::managers::graph_manager::GraphManager;
+use crate::managers::inputs_manager::InputsManager;
+use crate::managers::midi_manager::LearnTarget;
+
+#[tauri::command]
+pub async fn get_midi_ports(state: State<'_, Mutex<InputsManager>>) -> Result<Vec<String>, ()> {
+    Ok(state.lock().await.midi().port_names())
+}
+
+// The next matching message received binds to the node
+#[tauri::command]
+pub async fn midi_learn(
+    graph_id: usize,
+    node_id: NodeId,
+    graphs: State<'_, Mutex<GraphManager>>,
+    state: State<'_, Mutex<InputsManager>>,
+) -> Result<(), ()> {
+    let target = LearnTarget::new(graphs.lock().await.get_graph(graph_id), graph_id, node_id);
+    match target {
+        Some(target) if state.lock().await.midi_mut().learn(target) => Ok(()),
+        _ => Err(()),
+    }
+}
+
+#[tauri::command]
+pub async fn cancel_midi_learn(state: State<'_, Mutex<InputsManager>>) -> Result<(), ()> {
+    state.lock().await.midi_mut().cancel_learn();
+    Ok(())
+}
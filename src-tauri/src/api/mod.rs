@@ -1 +1,12 @@
-pub mod core;
\ No newline at end of file
+pub mod artnet;
+pub mod compounds;
+pub mod core;
+pub mod cues;
+pub mod fixtures;
+pub mod inputs;
+pub mod midi;
+pub mod osc;
+pub mod outputs;
+pub mod project;
+pub mod snapshots;
+pub mod timeline;
@@ -0,0 +1,33 @@
+use std::net::{SocketAddr, ToSocketAddrs};
+
+// Tauri Imports
+use tauri::async_runtime::Mutex;
+use tauri::State;
+
+use crate::osc::server::OscClient;
+
+#[tauri::command]
+pub async fn get_osc_target(state: State<'_, Mutex<OscClient>>) -> Result<String, ()> {
+    Ok(state.lock().await.target().to_string())
+}
+
+// Where OscOut nodes send to, host may be a name or an IP
+#[tauri::command]
+pub async fn set_osc_target(
+    host: String,
+    port: u16,
+    state: State<'_, Mutex<OscClient>>,
+) -> Result<String, ()> {
+    let target: SocketAddr = (host.as_str(), port)
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut x| x.next())
+        .ok_or(())?;
+
+    let mut client = state.lock().await;
+    client
+        .set_target(target)
+        .map_err(|e| eprintln!("Can't send OSC to {}: {}", target, e))?;
+
+    Ok(target.to_string())
+}
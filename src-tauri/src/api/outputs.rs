@@ -0,0 +1,93 @@
+// Tauri Imports
+use tauri::async_runtime::Mutex;
+use tauri::State;
+
+use crate::dmx::merge::UniverseMerge;
+use crate::dmx::PortAddress;
+use crate::dto::channel_info_dto::ChannelInfoDto;
+use crate::dto::outputs_dto::OutputsDto;
+use crate::managers::outputs_manager::OutputsManager;
+use crate::outputs::{InterfaceId, InterfaceKind, Route};
+
+// Interfaces with their status, and the routes
+#[tauri::command]
+pub async fn get_outputs(state: State<'_, Mutex<OutputsManager>>) -> Result<OutputsDto, ()> {
+    Ok(state.lock().await.to_dto())
+}
+
+#[tauri::command]
+pub async fn add_output_interface(
+    name: String,
+    kind: InterfaceKind,
+    state: State<'_, Mutex<OutputsManager>>,
+) -> Result<InterfaceId, ()> {
+    Ok(state.lock().await.add_interface(&name, kind))
+}
+
+#[tauri::command]
+pub async fn remove_output_interface(
+    id: InterfaceId,
+    state: State<'_, Mutex<OutputsManager>>,
+) -> Result<(), ()> {
+    state.lock().await.remove_interface(id);
+    Ok(())
+}
+
+// Sends a universe through an interface, see Route for the destination
+#[tauri::command]
+pub async fn set_output_route(
+    universe: PortAddress,
+    interface: InterfaceId,
+    destination: u16,
+    state: State<'_, Mutex<OutputsManager>>,
+) -> Result<(), ()> {
+    let route = Route {
+        universe,
+        interface,
+        destination,
+    };
+    if state.lock().await.set_route(route) {
+        Ok(())
+    } else {
+        Err(())
+    }
+}
+
+#[tauri::command]
+pub async fn remove_output_route(
+    universe: PortAddress,
+    interface: InterfaceId,
+    state: State<'_, Mutex<OutputsManager>>,
+) -> Result<(), ()> {
+    state.lock().await.remove_route(universe, interface);
+    Ok(())
+}
+
+// Replaces how the sources of a universe are merged
+#[tauri::command]
+pub async fn set_universe_merge(
+    merge: UniverseMerge,
+    state: State<'_, Mutex<OutputsManager>>,
+) -> Result<(), ()> {
+    state.lock().await.set_merge(merge);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn remove_universe_merge(
+    universe: PortAddress,
+    state: State<'_, Mutex<OutputsManager>>,
+) -> Result<(), ()> {
+    state.lock().await.remove_merge(universe);
+    Ok(())
+}
+
+// Every source writing a channel and which of them decided its level
+#[tauri::command]
+pub async fn get_channel_info(
+    universe: PortAddress,
+    channel: u16,
+    state: State<'_, Mutex<OutputsManager>>,
+) -> Result<ChannelInfoDto, ()> {
+    Ok(state.lock().await.inspect(universe, channel))
+}
@@ -0,0 +1,54 @@
+use std::path::PathBuf;
+
+// Tauri Imports
+use tauri::async_runtime::Mutex;
+use tauri::State;
+
+use crate::managers::cues_manager::CuesManager;
+use crate::managers::fixtures_manager::FixturesManager;
+use crate::managers::graph_manager::GraphManager;
+use crate::managers::outputs_manager::OutputsManager;
+use crate::project::Project;
+
+#[tauri::command]
+pub async fn save_project(
+    path: PathBuf,
+    graphs: State<'_, Mutex<GraphManager>>,
+    outputs: State<'_, Mutex<OutputsManager>>,
+    fixtures: State<'_, Mutex<FixturesManager>>,
+    cues: State<'_, Mutex<CuesManager>>,
+) -> Result<(), String> {
+    let graphs = graphs.lock().await;
+    let project = Project {
+        outputs: outputs.lock().await.config(),
+        fixtures: fixtures.lock().await.config(),
+        cues: cues.lock().await.config(),
+        graphs: graphs.graphs_config(),
+        snapshots: graphs.snapshots(),
+        compounds: graphs.compounds().config(),
+    };
+
+    project.save(&path).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn load_project(
+    path: PathBuf,
+    graphs: State<'_, Mutex<GraphManager>>,
+    outputs: State<'_, Mutex<OutputsManager>>,
+    fixtures: State<'_, Mutex<FixturesManager>>,
+    cues: State<'_, Mutex<CuesManager>>,
+) -> Result<(), String> {
+    let project = Project::load(&path).map_err(|e| e.to_string())?;
+
+    let mut graphs = graphs.lock().await;
+    graphs.set_compounds(project.compounds);
+    graphs.set_graphs(project.graphs);
+    graphs.set_snapshots(project.snapshots);
+    outputs.lock().await.set_config(project.outputs);
+    let mut fixtures = fixtures.lock().await;
+    fixtures.set_config(project.fixtures);
+    graphs.set_fixtures(fixtures.fixture_map());
+    cues.lock().await.set_config(project.cues);
+    Ok(())
+}
@@ -0,0 +1,50 @@
+// Tauri Imports
+use tauri::async_runtime::Mutex;
+use tauri::State;
+
+use crate::core::graph::GraphId;
+use crate::core::node::NodeId;
+use crate::core::snapshot::{Snapshot, SnapshotId};
+use crate::managers::graph_manager::GraphManager;
+
+#[tauri::command]
+pub async fn get_snapshots(graphs: State<'_, Mutex<GraphManager>>) -> Result<Vec<Snapshot>, ()> {
+    Ok(graphs.lock().await.snapshots())
+}
+
+// Every node of the graph without a selection
+#[tauri::command]
+pub async fn capture_snapshot(
+    graph_id: GraphId,
+    name: String,
+    nodes: Option<Vec<NodeId>>,
+    graphs: State<'_, Mutex<GraphManager>>,
+) -> Result<SnapshotId, String> {
+    graphs
+        .lock()
+        .await
+        .capture_snapshot(graph_id, &name, nodes.as_deref())
+        .ok_or_else(|| format!("Unknown graph {}", graph_id))
+}
+
+// Fade in seconds, 0 for at once
+#[tauri::command]
+pub async fn recall_snapshot(
+    id: SnapshotId,
+    fade: f64,
+    graphs: State<'_, Mutex<GraphManager>>,
+) -> Result<(), String> {
+    match graphs.lock().await.recall_snapshot(id, fade) {
+        true => Ok(()),
+        false => Err(format!("Unknown snapshot {}", id)),
+    }
+}
+
+#[tauri::command]
+pub async fn remove_snapshot(
+    id: SnapshotId,
+    graphs: State<'_, Mutex<GraphManager>>,
+) -> Result<(), ()> {
+    graphs.lock().await.remove_snapshot(id);
+    Ok(())
+}
@@ -0,0 +1,107 @@
+// Tauri Imports
+use tauri::async_runtime::Mutex;
+use tauri::State;
+
+use crate::core::graph::GraphId;
+use crate::core::input_field::InputId;
+use crate::managers::graph_manager::GraphManager;
+use crate::timeline::transport::LoopRegion;
+use crate::timeline::{Keyframe, Timeline};
+
+#[tauri::command]
+pub async fn get_timeline(
+    graph_id: GraphId,
+    state: State<'_, Mutex<GraphManager>>,
+) -> Result<Timeline, ()> {
+    Ok(state.lock().await.get_graph(graph_id).timeline().clone())
+}
+
+// Adds the input's track when missing, replaces the keyframe at the same
+// time. Errors for an unknown input or a value of another type.
+#[tauri::command]
+pub async fn set_keyframe(
+    graph_id: GraphId,
+    input: InputId,
+    keyframe: Keyframe,
+    state: State<'_, Mutex<GraphManager>>,
+) -> Result<(), String> {
+    let mut graph_manager = state.lock().await;
+    graph_manager
+        .get_graph_mut(graph_id)
+        .set_keyframe(input, keyframe)
+}
+
+#[tauri::command]
+pub async fn remove_keyframe(
+    graph_id: GraphId,
+    input: InputId,
+    time: f64,
+    state: State<'_, Mutex<GraphManager>>,
+) -> Result<bool, ()> {
+    let mut graph_manager = state.lock().await;
+    Ok(graph_manager
+        .get_graph_mut(graph_id)
+        .remove_keyframe(input, time))
+}
+
+#[tauri::command]
+pub async fn remove_timeline_track(
+    graph_id: GraphId,
+    input: InputId,
+    state: State<'_, Mutex<GraphManager>>,
+) -> Result<(), ()> {
+    let mut graph_manager = state.lock().await;
+    graph_manager
+        .get_graph_mut(graph_id)
+        .timeline_mut()
+        .remove_track(input);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn timeline_play(
+    graph_id: GraphId,
+    state: State<'_, Mutex<GraphManager>>,
+) -> Result<(), ()> {
+    let mut graph_manager = state.lock().await;
+    let timeline = graph_manager.get_graph_mut(graph_id).timeline_mut();
+    timeline.transport.play();
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn timeline_pause(
+    graph_id: GraphId,
+    state: State<'_, Mutex<GraphManager>>,
+) -> Result<(), ()> {
+    let mut graph_manager = state.lock().await;
+    let timeline = graph_manager.get_graph_mut(graph_id).timeline_mut();
+    timeline.transport.pause();
+    Ok(())
+}
+
+// Position in seconds, applied on the next frame even while paused
+#[tauri::command]
+pub async fn timeline_seek(
+    graph_id: GraphId,
+    position: f64,
+    state: State<'_, Mutex<GraphManager>>,
+) -> Result<(), ()> {
+    let mut graph_manager = state.lock().await;
+    let timeline = graph_manager.get_graph_mut(graph_id).timeline_mut();
+    timeline.transport.seek(position);
+    Ok(())
+}
+
+// null plays on past the end
+#[tauri::command]
+pub async fn set_timeline_loop(
+    graph_id: GraphId,
+    region: Option<LoopRegion>,
+    state: State<'_, Mutex<GraphManager>>,
+) -> Result<(), ()> {
+    let mut graph_manager = state.lock().await;
+    let timeline = graph_manager.get_graph_mut(graph_id).timeline_mut();
+    timeline.transport.set_loop(region);
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+use std::time::{Duration, Instant};
+
+use crate::artnet::packet::ArtPollReply;
+use crate::dmx::PortAddress;
+use crate::dto::artnet_node_dto::ArtnetNodeDto;
+
+// Controllers should poll every 2.5 to 3 seconds
+pub const POLL_PERIOD: Duration = Duration::from_millis(2500);
+
+// Nodes that missed this many polls are dropped
+pub const NODE_TIMEOUT: Duration = Duration::from_secs(8);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ArtnetNode {
+    pub ip: Ipv4Addr,
+    pub short_name: String,
+    pub long_name: String,
+    pub mac: [u8; 6],
+    // Universes the node sends to the network
+    pub inputs: Vec<PortAddress>,
+    // Universes the node takes from the network
+    pub outputs: Vec<PortAddress>,
+    pub last_seen: Instant,
+}
+
+impl ArtnetNode {
+    pub fn to_dto(&self) -> ArtnetNodeDto {
+        ArtnetNodeDto {
+            ip: self.ip.to_string(),
+            short_name: self.short_name.clone(),
+            long_name: self.long_name.clone(),
+            mac: self
+                .mac
+                .iter()
+                .map(|x| format!("{:02x}", x))
+                .collect::<Vec<String>>()
+                .join(":"),
+            inputs: self.inputs.clone(),
+            outputs: self.outputs.clone(),
+            last_seen: self.last_seen.elapsed().as_millis() as u64,
+        }
+    }
+}
+
+// Live list of nodes built from ArtPollReplies
+#[derive(Default)]
+pub struct Discovery {
+    // Devices with more than 4 ports reply once per bind index, keep each reply
+    replies: HashMap<(Ipv4Addr, u8), (ArtPollReply, Instant)>,
+}
+
+impl Discovery {
+    pub fn new() -> Discovery {
+        Discovery::default()
+    }
+
+    // Returns true if the list of nodes changed, not just their last seen time
+    pub fn update(&mut self, reply: ArtPollReply, now: Instant) -> bool {
+        let key = (reply.ip, reply.bind_index);
+        let changed = self.replies.get(&key).map(|x| &x.0) != Some(&reply);
+        self.replies.insert(key, (reply, now));
+        changed
+    }
+
+    // Drops nodes not seen within timeout, returns true if any were
+    pub fn expire(&mut self, now: Instant, timeout: Duration) -> bool {
+        let len = self.replies.len();
+        self.replies
+            .retain(|_, (_, last_seen)| now.duration_since(*last_seen) <= timeout);
+        self.replies.len() != len
+    }
+
+    // One entry per IP with the ports of all its bind indexes, sorted by IP
+    pub fn nodes(&self) -> Vec<ArtnetNode> {
+        let mut keys: Vec<&(Ipv4Addr, u8)> = self.replies.keys().collect();
+        keys.sort();
+
+        let mut nodes: Vec<ArtnetNode> = Vec::new();
+        for key in keys {
+            let (reply, last_seen) = &self.replies[key];
+
+            match nodes.last_mut() {
+                Some(node) if node.ip == reply.ip => {
+                    node.inputs.extend(reply.inputs());
+                    node.outputs.extend(reply.outputs());
+                    node.last_seen = node.last_seen.max(*last_seen);
+                }
+                _ => nodes.push(ArtnetNode {
+                    ip: reply.ip,
+                    short_name: reply.short_name.clone(),
+                    long_name: reply.long_name.clone(),
+                    mac: reply.mac,
+                    inputs: reply.inputs(),
+                    outputs: reply.outputs(),
+                    last_seen: *last_seen,
+                }),
+            }
+        }
+
+        nodes
+    }
+}
@@ -0,0 +1,315 @@
+pub mod discovery;
+pub mod packet;
+pub mod receiver;
+
+#[cfg(test)]
+mod tests {
+    use std::net::{Ipv4Addr, UdpSocket};
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use super::discovery::*;
+    use super::packet::*;
+    use super::receiver::*;
+    use crate::dmx::port_address;
+
+    fn dmx(port_address: u16, data: Vec<u8>) -> ArtnetPacket {
+        ArtnetPacket::Dmx(ArtDmx {
+            sequence: 1,
+            physical: 0,
+            port_address,
+            data,
+        })
+    }
+
+    // Waits for the receive thread to pick up a packet
+    fn wait_for(receiver: &ArtnetReceiver, port_address: u16, sequence: u8) -> ReceivedFrame {
+        let start = Instant::now();
+        loop {
+            match receiver.latest(port_address) {
+                Some(frame) if frame.sequence == sequence => return frame,
+                _ if start.elapsed() > Duration::from_secs(2) => panic!("No frame received"),
+                _ => thread::sleep(Duration::from_millis(5)),
+            }
+        }
+    }
+
+    #[test]
+    fn art_dmx_encoding() {
+        let bytes = dmx(port_address(1, 2, 3), vec![10, 20, 30, 40]).encode();
+
+        assert_eq!(b"Art-Net\0", &bytes[0..8]);
+        assert_eq!([0x00, 0x50], bytes[8..10]);
+        assert_eq!([0, 14], bytes[10..12]);
+        // SubUni then Net
+        assert_eq!([0x23, 0x01], bytes[14..16]);
+        assert_eq!([0, 4], bytes[16..18]);
+        assert_eq!([10, 20, 30, 40], bytes[18..]);
+
+        assert_eq!(
+            Ok(dmx(port_address(1, 2, 3), vec![10, 20, 30, 40])),
+            ArtnetPacket::decode(&bytes)
+        );
+
+        // Odd lengths are padded
+        let bytes = dmx(0, vec![255]).encode();
+        assert_eq!([0, 2], bytes[16..18]);
+        assert_eq!(Ok(dmx(0, vec![255, 0])), ArtnetPacket::decode(&bytes));
+    }
+
+    #[test]
+    fn art_dmx_rejects_bad_packets() {
+        let bytes = dmx(0, vec![1, 2, 3, 4]).encode();
+
+        assert_eq!(Err(ArtnetError::NotArtnet), ArtnetPacket::decode(b"hello"));
+        assert_eq!(
+            Err(ArtnetError::Truncated),
+            ArtnetPacket::decode(&bytes[..20])
+        );
+
+        let mut unknown = bytes.clone();
+        unknown[9] = 0x60;
+        assert_eq!(
+            Err(ArtnetError::UnsupportedOpCode(0x6000)),
+            ArtnetPacket::decode(&unknown)
+        );
+
+        let mut too_long = bytes.clone();
+        too_long[16] = 0x02;
+        too_long[17] = 0x02;
+        assert_eq!(
+            Err(ArtnetError::InvalidLength(514)),
+            ArtnetPacket::decode(&too_long)
+        );
+    }
+
+    fn poll_reply(ip: [u8; 4], bind_index: u8, ports: Vec<ArtPort>) -> ArtPollReply {
+        ArtPollReply {
+            ip: Ipv4Addr::from(ip),
+            port: ARTNET_PORT,
+            version: 3,
+            oem: 0x1234,
+            esta: 0x4321,
+            short_name: "Node".to_string(),
+            long_name: "Some Art-Net node".to_string(),
+            node_report: "#0001 [0001] OK".to_string(),
+            ports,
+            style: 0,
+            mac: [1, 2, 3, 4, 5, 6],
+            bind_index,
+        }
+    }
+
+    #[test]
+    fn art_poll_encoding() {
+        let poll = ArtnetPacket::Poll(ArtPoll {
+            flags: 2,
+            diag_priority: 0x10,
+        });
+        let bytes = poll.encode();
+
+        assert_eq!(14, bytes.len());
+        assert_eq!([0x00, 0x20], bytes[8..10]);
+        assert_eq!(Ok(poll), ArtnetPacket::decode(&bytes));
+    }
+
+    #[test]
+    fn art_poll_reply_encoding() {
+        let reply = poll_reply(
+            [10, 0, 0, 5],
+            1,
+            vec![
+                ArtPort {
+                    input: None,
+                    output: Some(port_address(1, 2, 0)),
+                },
+                ArtPort {
+                    input: Some(port_address(1, 2, 5)),
+                    output: Some(port_address(1, 2, 1)),
+                },
+            ],
+        );
+        let bytes = ArtnetPacket::PollReply(reply.clone()).encode();
+
+        assert_eq!(239, bytes.len());
+        assert_eq!([0x00, 0x21], bytes[8..10]);
+        assert_eq!([10, 0, 0, 5], bytes[10..14]);
+        assert_eq!(b"Node\0", &bytes[26..31]);
+        // Net, subnet, port count and types
+        assert_eq!([1, 2], bytes[18..20]);
+        assert_eq!([0, 2], bytes[172..174]);
+        assert_eq!([0x80, 0xC0, 0, 0], bytes[174..178]);
+
+        assert_eq!(
+            Ok(ArtnetPacket::PollReply(reply.clone())),
+            ArtnetPacket::decode(&bytes)
+        );
+        assert_eq!(vec![port_address(1, 2, 5)], reply.inputs());
+        assert_eq!(
+            vec![port_address(1, 2, 0), port_address(1, 2, 1)],
+            reply.outputs()
+        );
+
+        // Names are cut to fit their field
+        let mut long = reply.clone();
+        long.short_name = "x".repeat(30);
+        let bytes = ArtnetPacket::PollReply(long).encode();
+        match ArtnetPacket::decode(&bytes) {
+            Ok(ArtnetPacket::PollReply(decoded)) => assert_eq!("x".repeat(17), decoded.short_name),
+            x => panic!("Unexpected {:?}", x),
+        }
+    }
+
+    #[test]
+    fn discovery_merges_and_expires() {
+        let start = Instant::now();
+        let output = |universe| ArtPort {
+            input: None,
+            output: Some(universe),
+        };
+        let mut discovery = Discovery::new();
+
+        assert!(discovery.update(poll_reply([10, 0, 0, 2], 1, vec![output(0)]), start));
+        assert!(discovery.update(poll_reply([10, 0, 0, 1], 1, vec![output(4)]), start));
+        // Same reply again only refreshes it
+        assert!(!discovery.update(poll_reply([10, 0, 0, 1], 1, vec![output(4)]), start));
+        // Second bind index of the same device
+        let later = start + Duration::from_secs(5);
+        assert!(discovery.update(poll_reply([10, 0, 0, 1], 2, vec![output(5)]), later));
+
+        let nodes = discovery.nodes();
+        assert_eq!(2, nodes.len());
+        assert_eq!(Ipv4Addr::new(10, 0, 0, 1), nodes[0].ip);
+        assert_eq!(vec![4, 5], nodes[0].outputs);
+        assert_eq!(later, nodes[0].last_seen);
+        assert_eq!("01:02:03:04:05:06", nodes[0].to_dto().mac);
+
+        assert!(!discovery.expire(later, NODE_TIMEOUT));
+        assert!(discovery.expire(start + NODE_TIMEOUT + Duration::from_secs(1), NODE_TIMEOUT));
+        let nodes = discovery.nodes();
+        assert_eq!(1, nodes.len());
+        assert_eq!(vec![5], nodes[0].outputs);
+    }
+
+    #[test]
+    fn discovery_loopback() {
+        let controller = ArtnetReceiver::bind("127.0.0.1:0").unwrap();
+        let node = ArtnetReceiver::bind("127.0.0.1:0").unwrap();
+
+        let mut reply = poll_reply([0, 0, 0, 0], 1, Vec::new());
+        reply.short_name = "Other".to_string();
+        node.set_poll_reply(reply);
+
+        let (sender, receiver) = mpsc::channel();
+        controller.set_nodes_listener(Box::new(move |nodes| {
+            let _ = sender.send(nodes);
+        }));
+        controller.start_discovery(node.local_addr()).unwrap();
+
+        let nodes = receiver.recv_timeout(Duration::from_secs(2)).unwrap();
+        assert_eq!(1, nodes.len());
+        assert_eq!("Other", nodes[0].short_name);
+        // Filled in by the node answering
+        assert_eq!(Ipv4Addr::LOCALHOST, nodes[0].ip);
+        assert_eq!(nodes, controller.nodes());
+    }
+
+    #[test]
+    fn discovery_ignores_own_polls() {
+        let controller = ArtnetReceiver::bind("127.0.0.1:0").unwrap();
+        let (sender, receiver) = mpsc::channel();
+        controller.set_nodes_listener(Box::new(move |nodes| {
+            let _ = sender.send(nodes);
+        }));
+
+        // As if our broadcast came back to us
+        controller.start_discovery(controller.local_addr()).unwrap();
+        assert!(receiver.recv_timeout(Duration::from_millis(300)).is_err());
+        assert!(controller.nodes().is_empty());
+    }
+
+    #[test]
+    fn receiver_answers_art_poll() {
+        let receiver = ArtnetReceiver::bind("127.0.0.1:0").unwrap();
+        let poller = UdpSocket::bind("127.0.0.1:0").unwrap();
+        poller
+            .set_read_timeout(Some(Duration::from_secs(2)))
+            .unwrap();
+
+        let poll = ArtnetPacket::Poll(ArtPoll::default());
+        poller
+            .send_to(&poll.encode(), receiver.local_addr())
+            .unwrap();
+
+        let mut buffer = [0u8; 1024];
+        let (len, _) = poller.recv_from(&mut buffer).unwrap();
+        match ArtnetPacket::decode(&buffer[..len]) {
+            Ok(ArtnetPacket::PollReply(reply)) => {
+                assert_eq!("wovmoh", reply.short_name);
+                assert_eq!(Ipv4Addr::LOCALHOST, reply.ip);
+                assert_eq!(1, reply.style);
+            }
+            x => panic!("Unexpected {:?}", x),
+        }
+    }
+
+    #[test]
+    fn receiver_loopback() {
+        let receiver = ArtnetReceiver::bind("127.0.0.1:0").unwrap();
+        let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let universe = port_address(0, 1, 2);
+
+        let mut packet = ArtDmx {
+            sequence: 1,
+            physical: 0,
+            port_address: universe,
+            data: vec![0, 128, 255, 64],
+        };
+        sender
+            .send_to(
+                &ArtnetPacket::Dmx(packet.clone()).encode(),
+                receiver.local_addr(),
+            )
+            .unwrap();
+
+        let frame = wait_for(&receiver, universe, 1);
+        assert_eq!([0, 128, 255, 64, 0], frame.data[..5]);
+        assert_eq!(sender.local_addr().unwrap(), frame.source);
+        assert!(receiver.latest(port_address(0, 0, 0)).is_none());
+
+        // Newer frames replace older ones, garbage is ignored
+        sender
+            .send_to(b"not art-net", receiver.local_addr())
+            .unwrap();
+        packet.sequence = 2;
+        packet.data = vec![1, 2];
+        sender
+            .send_to(&ArtnetPacket::Dmx(packet).encode(), receiver.local_addr())
+            .unwrap();
+
+        let frame = wait_for(&receiver, universe, 2);
+        assert_eq!([1, 2, 0, 0], frame.data[..4]);
+    }
+
+    #[test]
+    fn receiver_marks_stale_sources() {
+        let receiver = ArtnetReceiver::bind("127.0.0.1:0").unwrap();
+        let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+
+        sender
+            .send_to(&dmx(7, vec![9, 9]).encode(), receiver.local_addr())
+            .unwrap();
+        wait_for(&receiver, 7, 1);
+
+        let fresh = receiver.snapshot(STALE_TIMEOUT);
+        assert!(!fresh[&7].stale);
+        assert_eq!(9, fresh[&7].data[1]);
+
+        thread::sleep(Duration::from_millis(20));
+        let stale = receiver.snapshot(Duration::from_millis(10));
+        assert!(stale[&7].stale);
+        // The last levels are kept
+        assert_eq!(9, stale[&7].data[1]);
+    }
+}
@@ -0,0 +1,319 @@
+use std::fmt::{Display, Formatter};
+use std::net::Ipv4Addr;
+
+use crate::dmx::{PortAddress, UNIVERSE_SIZE};
+
+pub const ARTNET_PORT: u16 = 6454;
+pub const PROTOCOL_VERSION: u16 = 14;
+
+const ID: &[u8; 8] = b"Art-Net\0";
+const OP_POLL: u16 = 0x2000;
+const OP_POLL_REPLY: u16 = 0x2100;
+const OP_DMX: u16 = 0x5000;
+const POLL_SIZE: usize = 14;
+const POLL_REPLY_SIZE: usize = 239;
+const DMX_HEADER_SIZE: usize = 18;
+
+const SHORT_NAME_SIZE: usize = 18;
+const LONG_NAME_SIZE: usize = 64;
+// ArtPollReply describes at most 4 ports, bigger devices send one reply per 4 ports
+pub const MAX_REPLY_PORTS: usize = 4;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ArtnetPacket {
+    Poll(ArtPoll),
+    PollReply(ArtPollReply),
+    Dmx(ArtDmx),
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ArtPoll {
+    pub flags: u8,
+    pub diag_priority: u8,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ArtPollReply {
+    pub ip: Ipv4Addr,
+    pub port: u16,
+    pub version: u16,
+    pub oem: u16,
+    pub esta: u16,
+    pub short_name: String,
+    pub long_name: String,
+    pub node_report: String,
+    pub ports: Vec<ArtPort>,
+    // 0 = node, 1 = controller, see the Art-Net Style codes
+    pub style: u8,
+    pub mac: [u8; 6],
+    // Which reply this is for devices with more than 4 ports, from 1
+    pub bind_index: u8,
+}
+
+// A port can both input (to the network) and output (from the network)
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ArtPort {
+    pub input: Option<PortAddress>,
+    pub output: Option<PortAddress>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ArtDmx {
+    // 0 when the sender doesn't sequence its packets
+    pub sequence: u8,
+    pub physical: u8,
+    pub port_address: PortAddress,
+    // 2..512 channels
+    pub data: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ArtnetError {
+    NotArtnet,
+    Truncated,
+    UnsupportedOpCode(u16),
+    UnsupportedVersion(u16),
+    InvalidLength(usize),
+}
+
+impl Display for ArtnetError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            ArtnetError::NotArtnet => write!(f, "Not an Art-Net packet"),
+            ArtnetError::Truncated => write!(f, "Truncated Art-Net packet"),
+            ArtnetError::UnsupportedOpCode(op) => {
+                write!(f, "Unsupported Art-Net OpCode {:#06x}", op)
+            }
+            ArtnetError::UnsupportedVersion(v) => write!(f, "Unsupported Art-Net version {}", v),
+            ArtnetError::InvalidLength(len) => write!(f, "Invalid ArtDmx length {}", len),
+        }
+    }
+}
+
+impl ArtnetPacket {
+    pub fn decode(bytes: &[u8]) -> Result<ArtnetPacket, ArtnetError> {
+        if bytes.len() < 10 || &bytes[0..8] != ID {
+            return Err(ArtnetError::NotArtnet);
+        }
+
+        // The OpCode is the only little endian field
+        let op_code = u16::from_le_bytes([bytes[8], bytes[9]]);
+
+        match op_code {
+            OP_POLL => ArtPoll::decode(bytes).map(ArtnetPacket::Poll),
+            OP_POLL_REPLY => ArtPollReply::decode(bytes).map(ArtnetPacket::PollReply),
+            OP_DMX => ArtDmx::decode(bytes).map(ArtnetPacket::Dmx),
+            _ => Err(ArtnetError::UnsupportedOpCode(op_code)),
+        }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        match self {
+            ArtnetPacket::Poll(poll) => poll.encode(),
+            ArtnetPacket::PollReply(reply) => reply.encode(),
+            ArtnetPacket::Dmx(dmx) => dmx.encode(),
+        }
+    }
+}
+
+fn header(op_code: u16) -> Vec<u8> {
+    let mut bytes = ID.to_vec();
+    bytes.extend_from_slice(&op_code.to_le_bytes());
+    bytes
+}
+
+fn check_version(bytes: &[u8]) -> Result<(), ArtnetError> {
+    let version = u16::from_be_bytes([bytes[10], bytes[11]]);
+    if version < PROTOCOL_VERSION {
+        return Err(ArtnetError::UnsupportedVersion(version));
+    }
+    Ok(())
+}
+
+// Fixed size, null terminated ASCII field
+fn read_string(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|x| *x == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+fn write_string(bytes: &mut Vec<u8>, value: &str, size: usize) {
+    // Always leave room for the terminator
+    let value = &value.as_bytes()[..value.len().min(size - 1)];
+    bytes.extend_from_slice(value);
+    bytes.resize(bytes.len() + size - value.len(), 0);
+}
+
+impl ArtPoll {
+    fn decode(bytes: &[u8]) -> Result<ArtPoll, ArtnetError> {
+        if bytes.len() < POLL_SIZE {
+            return Err(ArtnetError::Truncated);
+        }
+        check_version(bytes)?;
+
+        Ok(ArtPoll {
+            flags: bytes[12],
+            diag_priority: bytes[13],
+        })
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = header(OP_POLL);
+        bytes.extend_from_slice(&PROTOCOL_VERSION.to_be_bytes());
+        bytes.push(self.flags);
+        bytes.push(self.diag_priority);
+        bytes
+    }
+}
+
+impl ArtPollReply {
+    // Universes the device sends to the network
+    pub fn inputs(&self) -> Vec<PortAddress> {
+        self.ports.iter().filter_map(|x| x.input).collect()
+    }
+
+    // Universes the device takes from the network
+    pub fn outputs(&self) -> Vec<PortAddress> {
+        self.ports.iter().filter_map(|x| x.output).collect()
+    }
+
+    fn decode(bytes: &[u8]) -> Result<ArtPollReply, ArtnetError> {
+        // Older devices send shorter replies, everything up to the MAC is required
+        if bytes.len() < 207 {
+            return Err(ArtnetError::Truncated);
+        }
+
+        let net = bytes[18] as u16 & 0x7F;
+        let subnet = bytes[19] as u16 & 0x0F;
+        let address = |switch: u8| (net << 8) | (subnet << 4) | (switch as u16 & 0x0F);
+
+        let port_count =
+            (u16::from_be_bytes([bytes[172], bytes[173]]) as usize).min(MAX_REPLY_PORTS);
+        let ports = (0..port_count)
+            .map(|i| {
+                let port_type = bytes[174 + i];
+                ArtPort {
+                    input: (port_type & 0x40 != 0).then(|| address(bytes[186 + i])),
+                    output: (port_type & 0x80 != 0).then(|| address(bytes[190 + i])),
+                }
+            })
+            .collect();
+
+        let mut mac = [0u8; 6];
+        mac.copy_from_slice(&bytes[201..207]);
+
+        Ok(ArtPollReply {
+            ip: Ipv4Addr::new(bytes[10], bytes[11], bytes[12], bytes[13]),
+            port: u16::from_le_bytes([bytes[14], bytes[15]]),
+            version: u16::from_be_bytes([bytes[16], bytes[17]]),
+            oem: u16::from_be_bytes([bytes[20], bytes[21]]),
+            esta: u16::from_le_bytes([bytes[24], bytes[25]]),
+            short_name: read_string(&bytes[26..44]),
+            long_name: read_string(&bytes[44..108]),
+            node_report: read_string(&bytes[108..172]),
+            ports,
+            style: bytes[200],
+            mac,
+            bind_index: bytes.get(211).copied().unwrap_or(0),
+        })
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let ports = &self.ports[..self.ports.len().min(MAX_REPLY_PORTS)];
+
+        // All ports of one reply share the net and subnet of the first
+        let first = ports.iter().find_map(|x| x.input.or(x.output)).unwrap_or(0);
+
+        let mut port_types = [0u8; 4];
+        let mut good_input = [0u8; 4];
+        let mut good_output = [0u8; 4];
+        let mut switch_in = [0u8; 4];
+        let mut switch_out = [0u8; 4];
+        for (i, port) in ports.iter().enumerate() {
+            if let Some(address) = port.input {
+                // DMX512 protocol, can input
+                port_types[i] |= 0x40;
+                good_input[i] = 0x80;
+                switch_in[i] = (address & 0x0F) as u8;
+            }
+            if let Some(address) = port.output {
+                port_types[i] |= 0x80;
+                good_output[i] = 0x80;
+                switch_out[i] = (address & 0x0F) as u8;
+            }
+        }
+
+        let mut bytes = header(OP_POLL_REPLY);
+        bytes.extend_from_slice(&self.ip.octets());
+        bytes.extend_from_slice(&self.port.to_le_bytes());
+        bytes.extend_from_slice(&self.version.to_be_bytes());
+        bytes.push(((first >> 8) & 0x7F) as u8);
+        bytes.push(((first >> 4) & 0x0F) as u8);
+        bytes.extend_from_slice(&self.oem.to_be_bytes());
+        // Ubea version, Status1
+        bytes.extend_from_slice(&[0, 0]);
+        bytes.extend_from_slice(&self.esta.to_le_bytes());
+        write_string(&mut bytes, &self.short_name, SHORT_NAME_SIZE);
+        write_string(&mut bytes, &self.long_name, LONG_NAME_SIZE);
+        write_string(&mut bytes, &self.node_report, LONG_NAME_SIZE);
+        bytes.extend_from_slice(&(ports.len() as u16).to_be_bytes());
+        bytes.extend_from_slice(&port_types);
+        bytes.extend_from_slice(&good_input);
+        bytes.extend_from_slice(&good_output);
+        bytes.extend_from_slice(&switch_in);
+        bytes.extend_from_slice(&switch_out);
+        // AcnPriority, SwMacro, SwRemote and spare
+        bytes.extend_from_slice(&[0; 6]);
+        bytes.push(self.style);
+        bytes.extend_from_slice(&self.mac);
+        // BindIp
+        bytes.extend_from_slice(&self.ip.octets());
+        bytes.push(self.bind_index);
+        bytes.resize(POLL_REPLY_SIZE, 0);
+        bytes
+    }
+}
+
+impl ArtDmx {
+    fn decode(bytes: &[u8]) -> Result<ArtDmx, ArtnetError> {
+        if bytes.len() < DMX_HEADER_SIZE {
+            return Err(ArtnetError::Truncated);
+        }
+
+        check_version(bytes)?;
+
+        let length = u16::from_be_bytes([bytes[16], bytes[17]]) as usize;
+        if !(2..=UNIVERSE_SIZE).contains(&length) {
+            return Err(ArtnetError::InvalidLength(length));
+        }
+        if bytes.len() < DMX_HEADER_SIZE + length {
+            return Err(ArtnetError::Truncated);
+        }
+
+        Ok(ArtDmx {
+            sequence: bytes[12],
+            physical: bytes[13],
+            // SubUni is the low byte, Net the high 7 bits
+            port_address: u16::from_le_bytes([bytes[14], bytes[15] & 0x7F]),
+            data: bytes[DMX_HEADER_SIZE..DMX_HEADER_SIZE + length].to_vec(),
+        })
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut data = self.data.clone();
+        data.truncate(UNIVERSE_SIZE);
+        // The length has to be even and at least 2
+        if data.len() < 2 || data.len() % 2 == 1 {
+            data.push(0);
+            data.resize(data.len().max(2), 0);
+        }
+
+        let mut bytes = header(OP_DMX);
+        bytes.extend_from_slice(&PROTOCOL_VERSION.to_be_bytes());
+        bytes.push(self.sequence);
+        bytes.push(self.physical);
+        bytes.extend_from_slice(&(self.port_address & 0x7FFF).to_le_bytes());
+        bytes.extend_from_slice(&(data.len() as u16).to_be_bytes());
+        bytes.extend_from_slice(&data);
+        bytes
+    }
+}
@@ -0,0 +1,300 @@
+use std::collections::HashMap;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, ToSocketAddrs, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::artnet::discovery::{ArtnetNode, Discovery, NODE_TIMEOUT, POLL_PERIOD};
+use crate::artnet::packet::{ArtDmx, ArtPoll, ArtPollReply, ArtnetPacket, ARTNET_PORT};
+use crate::dmx::{DmxInput, DmxInputs, PortAddress, UNIVERSE_SIZE};
+
+// Senders repeat unchanged universes at least every 4 seconds, so anything quieter than this is gone
+pub const STALE_TIMEOUT: Duration = Duration::from_secs(5);
+
+// How often the receive thread checks whether it should stop
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Clone, Debug)]
+pub struct ReceivedFrame {
+    pub data: [u8; UNIVERSE_SIZE],
+    pub source: SocketAddr,
+    pub sequence: u8,
+    pub received: Instant,
+}
+
+impl ReceivedFrame {
+    pub fn is_stale(&self, timeout: Duration) -> bool {
+        self.received.elapsed() > timeout
+    }
+}
+
+// Called from the receive thread whenever the discovered nodes change
+pub type NodesListener = Box<dyn Fn(Vec<ArtnetNode>) + Send + Sync>;
+
+// Everything the receive thread shares with the ArtnetReceiver
+struct Shared {
+    frames: Mutex<HashMap<PortAddress, ReceivedFrame>>,
+    discovery: Mutex<Discovery>,
+    // Where ArtPolls are sent, None until discovery is started
+    poll_target: Mutex<Option<SocketAddr>>,
+    // What we answer ArtPolls with, the IP is filled in per poller
+    poll_reply: Mutex<ArtPollReply>,
+    listener: Mutex<Option<NodesListener>>,
+    running: AtomicBool,
+}
+
+// Owns the Art-Net socket. A background thread keeps the latest frame of every universe,
+// answers ArtPolls and, once discovery is started, polls for other nodes.
+pub struct ArtnetReceiver {
+    socket: UdpSocket,
+    local_addr: SocketAddr,
+    shared: Arc<Shared>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ArtnetReceiver {
+    pub fn bind(addr: impl ToSocketAddrs) -> io::Result<ArtnetReceiver> {
+        let socket = UdpSocket::bind(addr)?;
+        socket.set_read_timeout(Some(POLL_INTERVAL))?;
+        socket.set_broadcast(true)?;
+
+        let local_addr = socket.local_addr()?;
+        let shared = Arc::new(Shared {
+            frames: Mutex::new(HashMap::new()),
+            discovery: Mutex::new(Discovery::new()),
+            poll_target: Mutex::new(None),
+            poll_reply: Mutex::new(default_poll_reply()),
+            listener: Mutex::new(None),
+            running: AtomicBool::new(true),
+        });
+
+        let thread = {
+            let socket = socket.try_clone()?;
+            let shared = shared.clone();
+            thread::Builder::new()
+                .name("artnet-receiver".to_string())
+                .spawn(move || receive_loop(socket, shared))?
+        };
+
+        Ok(ArtnetReceiver {
+            socket,
+            local_addr,
+            shared,
+            thread: Some(thread),
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    pub fn latest(&self, port_address: PortAddress) -> Option<ReceivedFrame> {
+        self.shared
+            .frames
+            .lock()
+            .unwrap()
+            .get(&port_address)
+            .cloned()
+    }
+
+    // Latest frame of every universe seen so far
+    pub fn frames(&self) -> Vec<(PortAddress, ReceivedFrame)> {
+        self.shared
+            .frames
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(port_address, frame)| (*port_address, frame.clone()))
+            .collect()
+    }
+
+    // Every universe seen so far, marked stale if nothing arrived within timeout
+    pub fn snapshot(&self, timeout: Duration) -> DmxInputs {
+        self.shared
+            .frames
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(port_address, frame)| {
+                (
+                    *port_address,
+                    DmxInput {
+                        data: frame.data,
+                        stale: frame.is_stale(timeout),
+                    },
+                )
+            })
+            .collect()
+    }
+
+    // Polls target now and every POLL_PERIOD after, usually the broadcast address
+    pub fn start_discovery(&self, target: SocketAddr) -> io::Result<()> {
+        *self.shared.poll_target.lock().unwrap() = Some(target);
+        self.poll()
+    }
+
+    // Sends an ArtPoll right away instead of waiting for the next period
+    pub fn poll(&self) -> io::Result<()> {
+        match *self.shared.poll_target.lock().unwrap() {
+            Some(target) => send_poll(&self.socket, target),
+            None => Ok(()),
+        }
+    }
+
+    pub fn nodes(&self) -> Vec<ArtnetNode> {
+        self.shared.discovery.lock().unwrap().nodes()
+    }
+
+    pub fn set_nodes_listener(&self, listener: NodesListener) {
+        *self.shared.listener.lock().unwrap() = Some(listener);
+    }
+
+    pub fn set_poll_reply(&self, reply: ArtPollReply) {
+        *self.shared.poll_reply.lock().unwrap() = reply;
+    }
+}
+
+impl Drop for ArtnetReceiver {
+    fn drop(&mut self) {
+        self.shared.running.store(false, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn default_poll_reply() -> ArtPollReply {
+    ArtPollReply {
+        ip: Ipv4Addr::UNSPECIFIED,
+        port: ARTNET_PORT,
+        version: 1,
+        // Unknown OEM, ESTA prototype id
+        oem: 0x00FF,
+        esta: 0x7FF0,
+        short_name: "wovmoh".to_string(),
+        long_name: "wovmoh lighting controller".to_string(),
+        node_report: "#0001 [0000] Running".to_string(),
+        ports: Vec::new(),
+        // Controller
+        style: 1,
+        mac: [0; 6],
+        bind_index: 1,
+    }
+}
+
+fn receive_loop(socket: UdpSocket, shared: Arc<Shared>) {
+    let mut buffer = [0u8; 1024];
+    let mut last_poll = Instant::now();
+
+    while shared.running.load(Ordering::Relaxed) {
+        if last_poll.elapsed() >= POLL_PERIOD {
+            last_poll = Instant::now();
+            if let Some(target) = *shared.poll_target.lock().unwrap() {
+                let _ = send_poll(&socket, target);
+            }
+
+            let expired = shared
+                .discovery
+                .lock()
+                .unwrap()
+                .expire(Instant::now(), NODE_TIMEOUT);
+            if expired {
+                notify(&shared);
+            }
+        }
+
+        let (len, source) = match socket.recv_from(&mut buffer) {
+            Ok(x) => x,
+            // Read timeouts land here too, giving the running check a chance
+            Err(_) => continue,
+        };
+
+        // Anything that isn't valid Art-Net is ignored, as are our own
+        // broadcast polls and the replies to them
+        match ArtnetPacket::decode(&buffer[..len]) {
+            Ok(ArtnetPacket::Dmx(dmx)) => store(&shared, dmx, source),
+            Ok(ArtnetPacket::Poll(_) | ArtnetPacket::PollReply(_)) if is_own(&socket, source) => {}
+            Ok(ArtnetPacket::Poll(_)) => {
+                let _ = send_poll_reply(&socket, &shared, source);
+            }
+            Ok(ArtnetPacket::PollReply(reply)) => {
+                let changed = shared
+                    .discovery
+                    .lock()
+                    .unwrap()
+                    .update(reply, Instant::now());
+                if changed {
+                    notify(&shared);
+                }
+            }
+            Err(_) => {}
+        }
+    }
+}
+
+fn store(shared: &Shared, dmx: ArtDmx, source: SocketAddr) {
+    // Channels past the end of a short packet read as 0
+    let mut data = [0u8; UNIVERSE_SIZE];
+    data[..dmx.data.len()].copy_from_slice(&dmx.data);
+
+    shared.frames.lock().unwrap().insert(
+        dmx.port_address,
+        ReceivedFrame {
+            data,
+            source,
+            sequence: dmx.sequence,
+            received: Instant::now(),
+        },
+    );
+}
+
+fn notify(shared: &Shared) {
+    let nodes = shared.discovery.lock().unwrap().nodes();
+    if let Some(listener) = shared.listener.lock().unwrap().as_ref() {
+        listener(nodes);
+    }
+}
+
+fn send_poll(socket: &UdpSocket, target: SocketAddr) -> io::Result<()> {
+    let packet = ArtnetPacket::Poll(ArtPoll::default());
+    socket.send_to(&packet.encode(), target).map(|_| ())
+}
+
+fn send_poll_reply(socket: &UdpSocket, shared: &Shared, poller: SocketAddr) -> io::Result<()> {
+    let mut reply = shared.poll_reply.lock().unwrap().clone();
+    reply.ip = local_ip_towards(socket, poller);
+
+    let packet = ArtnetPacket::PollReply(reply);
+    socket.send_to(&packet.encode(), poller).map(|_| ())
+}
+
+// Sent from this socket, i.e. from its port on one of our addresses
+fn is_own(socket: &UdpSocket, source: SocketAddr) -> bool {
+    let Ok(local) = socket.local_addr() else {
+        return false;
+    };
+    source.port() == local.port() && IpAddr::V4(local_ip_towards(socket, source)) == source.ip()
+}
+
+// The address the poller can reach us on, found by letting the OS route to it
+fn local_ip_towards(socket: &UdpSocket, destination: SocketAddr) -> Ipv4Addr {
+    if let Ok(SocketAddr::V4(addr)) = socket.local_addr() {
+        if !addr.ip().is_unspecified() {
+            return *addr.ip();
+        }
+    }
+
+    UdpSocket::bind("0.0.0.0:0")
+        .and_then(|x| {
+            x.connect(destination)?;
+            x.local_addr()
+        })
+        .ok()
+        .and_then(|x| match x.ip() {
+            IpAddr::V4(ip) => Some(ip),
+            IpAddr::V6(_) => None,
+        })
+        .unwrap_or(Ipv4Addr::UNSPECIFIED)
+}
@@ -0,0 +1,19 @@
+// Time source for a graph. Advanced once per frame by Graph::tick.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct Clock {
+    pub time: f64,
+    pub delta: f64,
+    pub frame: u64,
+}
+
+impl Clock {
+    pub fn new() -> Clock {
+        Clock::default()
+    }
+
+    pub fn advance(&mut self, delta: f64) {
+        self.delta = delta;
+        self.time += delta;
+        self.frame += 1;
+    }
+}
@@ -0,0 +1,167 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::fragment::Fragment;
+use crate::core::node::NodeKind;
+use crate::types::data_type::DataValue;
+
+pub type CompoundId = u32;
+
+// Inputs and outputs of a Compound node
+pub const COMPOUND_PINS: usize = 8;
+
+// Compounds inside compounds inside... deeper than this aren't processed
+pub const MAX_DEPTH: usize = 16;
+
+// A graph used as a node. Its GroupInput and GroupOutput nodes, in order, are
+// the pins of the Compound nodes using it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CompoundDef {
+    pub id: CompoundId,
+    pub name: String,
+    pub fragment: Fragment,
+}
+
+impl CompoundDef {
+    // Names of the pins, the text of the boundary nodes or "In 1", "Out 1"...
+    pub fn inputs(&self) -> Vec<String> {
+        self.pins(|x| matches!(x, NodeKind::GroupInput), "In")
+    }
+
+    pub fn outputs(&self) -> Vec<String> {
+        self.pins(|x| matches!(x, NodeKind::GroupOutput), "Out")
+    }
+
+    fn pins(&self, boundary: impl Fn(&NodeKind) -> bool, prefix: &str) -> Vec<String> {
+        self.fragment
+            .nodes
+            .iter()
+            .filter(|x| boundary(&x.kind))
+            .enumerate()
+            .map(|(i, x)| match x.text.trim() {
+                "" => format!("{} {}", prefix, i + 1),
+                text => text.to_string(),
+            })
+            .collect()
+    }
+
+    // Compounds used by its Compound nodes, as set on their Compound input
+    fn references(&self) -> impl Iterator<Item = CompoundId> + '_ {
+        self.fragment
+            .nodes
+            .iter()
+            .filter(|x| matches!(x.kind, NodeKind::Compound))
+            .filter_map(|x| match x.values.first() {
+                Some(DataValue::Number(id)) => Some(id.round().max(0.0) as CompoundId),
+                _ => None,
+            })
+    }
+}
+
+// Every compound definition, shared by the graphs. An edit replaces the
+// definition, Compound nodes using it rebuild on their next frame.
+#[derive(Clone, Default)]
+pub struct CompoundLibrary {
+    defs: BTreeMap<CompoundId, Arc<CompoundDef>>,
+    next_id: CompoundId,
+}
+
+impl CompoundLibrary {
+    pub fn get(&self, id: CompoundId) -> Option<&Arc<CompoundDef>> {
+        self.defs.get(&id)
+    }
+
+    pub fn defs(&self) -> impl Iterator<Item = &CompoundDef> {
+        self.defs.values().map(|x| x.as_ref())
+    }
+
+    // Id the next add gets
+    pub fn next_id(&self) -> CompoundId {
+        self.next_id
+    }
+
+    pub fn add(&mut self, name: &str, fragment: Fragment) -> Result<CompoundId, String> {
+        let id = self.next_id;
+        self.insert(CompoundDef {
+            id,
+            name: name.to_string(),
+            fragment,
+        })?;
+        Ok(id)
+    }
+
+    pub fn set(&mut self, id: CompoundId, fragment: Fragment) -> Result<(), String> {
+        let Some(def) = self.defs.get(&id) else {
+            return Err(format!("Compound {} doesn't exist", id));
+        };
+        self.insert(CompoundDef {
+            id,
+            name: def.name.clone(),
+            fragment,
+        })
+    }
+
+    pub fn rename(&mut self, id: CompoundId, name: &str) -> bool {
+        match self.defs.get_mut(&id) {
+            Some(def) => {
+                Arc::make_mut(def).name = name.to_string();
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Compound nodes using it show an error
+    pub fn remove(&mut self, id: CompoundId) {
+        self.defs.remove(&id);
+    }
+
+    // Rejected when the fragment is broken, has more boundary nodes than a
+    // Compound node has pins or the compound would end up inside itself
+    pub fn check(&self, def: &CompoundDef) -> Result<(), String> {
+        def.fragment.check()?;
+        if def.inputs().len() > COMPOUND_PINS || def.outputs().len() > COMPOUND_PINS {
+            return Err(format!(
+                "A compound has at most {} inputs and {} outputs",
+                COMPOUND_PINS, COMPOUND_PINS
+            ));
+        }
+
+        let mut stack: Vec<CompoundId> = def.references().collect();
+        let mut seen = Vec::new();
+        while let Some(id) = stack.pop() {
+            if id == def.id {
+                return Err("The compound would contain itself".to_string());
+            }
+            if seen.contains(&id) {
+                continue;
+            }
+            seen.push(id);
+            if let Some(x) = self.defs.get(&id) {
+                stack.extend(x.references());
+            }
+        }
+        Ok(())
+    }
+
+    fn insert(&mut self, def: CompoundDef) -> Result<(), String> {
+        self.check(&def)?;
+        self.next_id = self.next_id.max(def.id + 1);
+        self.defs.insert(def.id, Arc::new(def));
+        Ok(())
+    }
+
+    pub fn config(&self) -> Vec<CompoundDef> {
+        self.defs().cloned().collect()
+    }
+
+    // From a project, definitions that don't check out are left out
+    pub fn set_config(&mut self, defs: Vec<CompoundDef>) {
+        *self = CompoundLibrary::default();
+        for def in defs {
+            let _ = self.insert(def);
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core::node::NodeKind;
+use crate::types::data_type::DataValue;
+
+// Nodes copied out of a graph with the connections between them, see
+// Graph::extract_subgraph. As JSON it goes through the clipboard, so it can be
+// pasted into another graph or project.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Fragment {
+    pub nodes: Vec<FragmentNode>,
+    pub connections: Vec<FragmentConnection>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FragmentNode {
+    pub kind: NodeKind,
+    pub position: (f64, f64),
+    #[serde(default)]
+    pub text: String,
+    // Values of the input fields in descriptor order
+    #[serde(default)]
+    pub values: Vec<DataValue>,
+}
+
+// Nodes by index in Fragment::nodes, fields and pins by index on the node
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FragmentConnection {
+    pub from_node: usize,
+    pub from_output: usize,
+    pub to_node: usize,
+    pub to_input: usize,
+}
+
+impl Fragment {
+    pub fn from_json(json: &str) -> serde_json::Result<Fragment> {
+        serde_json::from_str(json)
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    // Connections between fields and pins the nodes have, of the same type,
+    // without loops. Anything pasted may have been edited by hand.
+    pub fn check(&self) -> Result<(), String> {
+        let mut in_degrees = vec![0; self.nodes.len()];
+        for x in &self.connections {
+            let (Some(from), Some(to)) = (self.nodes.get(x.from_node), self.nodes.get(x.to_node))
+            else {
+                return Err("Connection to a node outside the fragment".to_string());
+            };
+            if x.from_output >= from.kind.descriptor().outputs.len()
+                || x.to_input >= to.kind.descriptor().inputs.len()
+            {
+                return Err("Connection to a pin the node doesn't have".to_string());
+            }
+            let from_type = from.kind.descriptor().outputs[x.from_output].data_type;
+            let to_type = to.kind.descriptor().inputs[x.to_input].data_type;
+            if !from_type.connects_to(to_type) {
+                return Err("Connection between fields of different types".to_string());
+            }
+            in_degrees[x.to_node] += 1;
+        }
+
+        // Every node can be ordered unless there is a loop
+        let mut queue: Vec<usize> = (0..self.nodes.len())
+            .filter(|x| in_degrees[*x] == 0)
+            .collect();
+        let mut ordered = 0;
+        while let Some(node) = queue.pop() {
+            ordered += 1;
+            for x in self.connections.iter().filter(|x| x.from_node == node) {
+                in_degrees[x.to_node] -= 1;
+                if in_degrees[x.to_node] == 0 {
+                    queue.push(x.to_node);
+                }
+            }
+        }
+        match ordered == self.nodes.len() {
+            true => Ok(()),
+            false => Err("The fragment has a loop".to_string()),
+        }
+    }
+}
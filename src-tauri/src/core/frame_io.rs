@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::core::compound::CompoundLibrary;
+use crate::cues::playback::CueOutputs;
+use crate::dmx::{DmxInputs, DmxOutputs};
+use crate::fixtures::FixtureMap;
+use crate::midi::message::MidiMessage;
+use crate::midi::MidiInputs;
+use crate::osc::codec::OscMessage;
+use crate::osc::OscInputs;
+use crate::types::data_type::DataValue;
+
+// Everything a graph exchanges with the outside world during a frame.
+// Inputs are handed in before processing, outputs collected after it. Inputs
+// are shared by every graph, so they aren't copied into each one per frame.
+#[derive(Clone, Default)]
+pub struct FrameIo {
+    // Named input channels, read by ExternalInput nodes
+    pub inputs: Arc<ExternalInputs>,
+    // Latest received DMX universes, read by DmxInput nodes
+    pub dmx_in: Arc<DmxInputs>,
+    // Levels written by DmxOutput nodes, per universe
+    pub dmx_out: DmxOutputs,
+    // Latest message of every OSC address, read by OscIn nodes
+    pub osc_in: Arc<OscInputs>,
+    // Messages queued by OscOut nodes
+    pub osc_out: Vec<OscMessage>,
+    // Notes and controllers received so far, read by the MIDI input nodes
+    pub midi_in: Arc<MidiInputs>,
+    // Messages queued by MidiCCOut nodes
+    pub midi_out: Vec<MidiMessage>,
+    // Patched fixtures, written through by Fixture nodes
+    pub fixtures: Arc<FixtureMap>,
+    // Crossfaded levels of every cue list, read by CueList nodes
+    pub cues: Arc<CueOutputs>,
+    // Definitions used by Compound nodes
+    pub compounds: Arc<CompoundLibrary>,
+    // Compound nodes being processed, one inside the other
+    pub depth: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExternalValue {
+    pub value: DataValue,
+    // Increases every time any channel receives a value
+    pub serial: u64,
+}
+
+// Channel name -> latest value, see InputsManager
+pub type ExternalInputs = HashMap<String, ExternalValue>;
@@ -1,20 +1,48 @@
 use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
 
 use crate::{
     core::{
+        clock::Clock,
+        compound::{CompoundDef, CompoundId, CompoundLibrary, COMPOUND_PINS},
+        fragment::{Fragment, FragmentConnection, FragmentNode},
+        frame_io::{ExternalInputs, FrameIo},
+        history::{Change, GraphEdit, History, RemovedNode},
         input_field::*,
         node::{self, *},
         output_pin::*,
+        snapshot::{Snapshot, SnapshotFade, SnapshotId, SnapshotValue},
     },
+    cues::playback::CueOutputs,
+    dmx::{DmxInputs, DmxOutputs},
     dto::{
-        connection_dto::ConnectionDto, graph_dto::GraphDto, input_field_dto::InputFieldDto,
-        node_dto::NodeDto, output_pin_dto::OutputPinDto,
+        connection_dto::ConnectionDto, graph_delta_dto::GraphDeltaDto, graph_dto::GraphDto,
+        input_field_dto::InputFieldDto, node_dto::NodeDto, output_pin_dto::OutputPinDto,
     },
-    types::data_type::DataValue,
+    fixtures::FixtureMap,
+    midi::{message::MidiMessage, MidiInputs},
+    osc::{codec::OscMessage, OscInputs},
+    timeline::{Keyframe, Timeline},
+    types::data_type::{DataType, DataValue},
 };
 
 pub type GraphId = usize;
 
+// A graph as saved in a project: all its nodes, inserted back with the same
+// ids, and the timeline automating them
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GraphConfig {
+    pub nodes: Fragment,
+    #[serde(default)]
+    pub timeline: Timeline,
+}
+
+// Start of the error of a node given a value of the wrong type, see run
+const WRONG_TYPE: &str = "Wrong type";
+
 pub struct Graph {
     id: GraphId,
 
@@ -26,6 +54,27 @@ pub struct Graph {
     execution_order: Vec<NodeId>,
 
     order_dirty: bool,
+
+    // Nodes to run in the next frame, by id, see Graph::run
+    dirty: Vec<bool>,
+    // False runs every node every frame
+    incremental: bool,
+    // Nodes run in the last frame
+    processed: usize,
+    // Bumped whenever ids shift, anything holding one can tell it's stale
+    renumbered: u64,
+
+    clock: Clock,
+
+    io: FrameIo,
+
+    snapshot_fade: Option<SnapshotFade>,
+    // Taken of this graph, kept here so they follow its ids
+    snapshots: Vec<Snapshot>,
+
+    timeline: Timeline,
+
+    history: History,
 }
 
 impl Graph {
@@ -38,15 +87,88 @@ impl Graph {
             nodes_map: HashMap::new(),
             execution_order: Vec::new(),
             order_dirty: false,
+            dirty: Vec::new(),
+            incremental: true,
+            processed: 0,
+            renumbered: 0,
+            clock: Clock::new(),
+            io: FrameIo::default(),
+            snapshot_fade: None,
+            snapshots: Vec::new(),
+            timeline: Timeline::default(),
+            history: History::default(),
         }
     }
 
+    pub fn clock(&self) -> &Clock {
+        &self.clock
+    }
+
     pub fn get_node(&self, node_id: NodeId) -> &Node {
         &self.nodes[node_id]
     }
 
+    // Value used by an input field while it isn't connected
+    pub fn set_input_value(&mut self, input_id: InputId, value: DataValue) {
+        self.inputs[input_id].value = value;
+        self.mark_dirty(self.inputs[input_id].parent);
+        // Whoever set it takes over from a snapshot fading it
+        if let Some(fade) = &mut self.snapshot_fade {
+            fade.values.retain(|x| x.0 != input_id);
+        }
+    }
+
+    pub fn get_input_value(&self, input_id: InputId) -> DataValue {
+        self.inputs[input_id].value
+    }
+
+    pub fn get_output_value(&self, output_id: OutputId) -> DataValue {
+        self.outputs[output_id].value
+    }
+
+    pub fn set_node_text(&mut self, node_id: NodeId, text: &str) {
+        let node = &mut self.nodes[node_id];
+        node.text = text.to_string();
+        node.cache = None;
+        node.error = None;
+        self.mark_dirty(node_id);
+    }
+
+    pub fn set_incremental(&mut self, incremental: bool) {
+        self.incremental = incremental;
+    }
+
+    pub fn processed(&self) -> usize {
+        self.processed
+    }
+
+    // Runs in the next frame. New nodes all run once anyway, see order_dirty.
+    fn mark_dirty(&mut self, node_id: NodeId) {
+        if let Some(x) = self.dirty.get_mut(node_id) {
+            *x = true;
+        }
+    }
+
+    // Nodes reading the output run in the next frame
+    fn mark_readers(&mut self, output_id: OutputId) {
+        for i in 0..self.outputs[output_id].connections.len() {
+            let input_id = self.outputs[output_id].connections[i];
+            self.mark_dirty(self.inputs[input_id].parent);
+        }
+    }
+
+    // Changes every time node, field or pin ids shift, see remap_ids
+    pub fn renumbered(&self) -> u64 {
+        self.renumbered
+    }
+
     pub fn has_node(&self, node_id: NodeId) -> bool {
-        return self.nodes.len() < node_id;
+        node_id < self.nodes.len()
+    }
+
+    // Node ids go from 0 to this
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
     }
 
     pub fn add_node(&mut self, kind: NodeKind, ui_state: NodeUIState) {
@@ -83,106 +205,787 @@ impl Graph {
             ui_state,
             inputs,
             outputs,
+            state: desc.state.to_vec(),
+            text: String::new(),
+            cache: None,
+            error: None,
         };
         self.nodes.push(node);
 
         self.order_dirty = true;
     }
 
+    // Later nodes, fields and pins move down to fill the gap, so ids stay indexes
     pub fn remove_node(&mut self, node_id: NodeId) {
-        // Swap and remove, then update node id.
+        self.take_node(node_id);
+    }
 
+    // Removes the node and disconnects everything connected to it, returning
+    // what restore_node needs to put it back
+    pub(crate) fn take_node(&mut self, node_id: NodeId) -> RemovedNode {
         let node = self.nodes[node_id].clone();
+        let input_start: InputId = self.nodes[..node_id].iter().map(|x| x.inputs.len()).sum();
+        let output_start: OutputId = self.nodes[..node_id].iter().map(|x| x.outputs.len()).sum();
+        let input_end = input_start + node.inputs.len();
+        let output_end = output_start + node.outputs.len();
 
-        // Swap and remove all inputs
-        for index in &node.inputs {
-            // Get the parent of the input that will be swapped and change the parents reference to the input to
-            // the current index
-            let swapped_input_id = self.inputs.len() - 1;
-            let swapped_input_parent = &mut self.nodes[self.inputs.last().unwrap().parent];
-            let swapped_input_index = swapped_input_parent
-                .inputs
-                .iter()
-                .find(|&x| *x == swapped_input_id)
-                .unwrap()
-                .clone();
-
-            // Set the input id at the input to be swapped to the id to be swapped to
-            swapped_input_parent.inputs[swapped_input_index] = *index;
-
-            // Now swap
-            self.inputs.swap_remove(*index);
+        for input_id in &node.inputs {
+            if let Some(output_id) = self.inputs[*input_id].connected_output {
+                self.outputs[output_id]
+                    .connections
+                    .retain(|x| x != input_id);
+            }
         }
 
-        // Swap and remove all outputs
-        for index in node.outputs {
-            // Get parent of output that will be swapped, and change the parents ref to the output to the current index.
-            let swapped_output_id = self.outputs.len() - 1;
-            let swapped_output_parent = &mut self.nodes[self.outputs.last().unwrap().parent];
-            let swapped_output_index = swapped_output_parent
-                .inputs
-                .iter()
-                .find(|&x| *x == swapped_output_id)
-                .unwrap()
-                .clone();
+        let mut dependents = Vec::new();
+        for output_id in &node.outputs {
+            for input_id in &self.outputs[*output_id].connections {
+                dependents.push((*input_id, *output_id));
+            }
+        }
+        for (input_id, _) in &dependents {
+            self.inputs[*input_id].connected_output = None;
+        }
 
-            swapped_output_parent.outputs[swapped_output_index] = index;
+        let inputs = self.inputs.drain(input_start..input_end).collect();
+        let outputs = self.outputs.drain(output_start..output_end).collect();
+        self.nodes.remove(node_id);
 
-            // swap
-            self.outputs.swap_remove(index);
+        let (tracks, kept) = std::mem::take(&mut self.timeline.tracks)
+            .into_iter()
+            .partition(|x| (input_start..input_end).contains(&x.input));
+        self.timeline.tracks = kept;
+
+        let mut snapshot_values = Vec::new();
+        for snapshot in &mut self.snapshots {
+            snapshot.values.retain(|x| {
+                let removed = (input_start..input_end).contains(&x.input);
+                if removed {
+                    snapshot_values.push((snapshot.id, *x));
+                }
+                !removed
+            });
         }
 
-        // Swap and remove node
-        self.nodes.swap_remove(node_id);
+        self.remap_ids(
+            |x| if x > node_id { x - 1 } else { x },
+            |x| {
+                if x >= input_end {
+                    x - (input_end - input_start)
+                } else {
+                    x
+                }
+            },
+            |x| {
+                if x >= output_end {
+                    x - (output_end - output_start)
+                } else {
+                    x
+                }
+            },
+        );
 
-        if self.nodes.len() == 0 {
-            self.order_dirty = true;
-            return;
+        RemovedNode {
+            node,
+            input_start,
+            output_start,
+            inputs,
+            outputs,
+            dependents,
+            tracks,
+            snapshot_values,
         }
+    }
 
-        // Update node at node_id
-        let node = &mut self.nodes[node_id];
-        node.id = node_id;
+    // Puts a node taken by take_node back, ids and connections as they were
+    pub(crate) fn restore_node(&mut self, removed: RemovedNode) {
+        let node_id = removed.node.id;
+        let input_start = removed.input_start;
+        let output_start = removed.output_start;
+        let input_count = removed.inputs.len();
+        let output_count = removed.outputs.len();
+
+        self.remap_ids(
+            |x| if x >= node_id { x + 1 } else { x },
+            |x| if x >= input_start { x + input_count } else { x },
+            |x| {
+                if x >= output_start {
+                    x + output_count
+                } else {
+                    x
+                }
+            },
+        );
 
-        // Update all fields and pins parent ids
-        for input_id in &node.inputs {
-            let input_field = &mut self.inputs[*input_id];
-            input_field.parent = node_id;
+        self.nodes.insert(node_id, removed.node);
+        self.inputs.splice(input_start..input_start, removed.inputs);
+        self.outputs
+            .splice(output_start..output_start, removed.outputs);
+
+        for input_id in input_start..input_start + input_count {
+            if let Some(output_id) = self.inputs[input_id].connected_output {
+                self.outputs[output_id].connections.push(input_id);
+            }
         }
+        for (input_id, output_id) in removed.dependents {
+            self.inputs[input_id].connected_output = Some(output_id);
+        }
+        self.timeline.tracks.extend(removed.tracks);
+        for (id, value) in removed.snapshot_values {
+            if let Some(snapshot) = self.snapshots.iter_mut().find(|x| x.id == id) {
+                snapshot.values.push(value);
+            }
+        }
+    }
 
-        // Update all outputs
-        for output_id in &node.outputs {
-            let output_pin = &mut self.outputs[*output_id];
-            output_pin.parent = node_id;
+    // Renumbers every reference to nodes, fields and pins
+    fn remap_ids(
+        &mut self,
+        node_id: impl Fn(NodeId) -> NodeId,
+        input_id: impl Fn(InputId) -> InputId,
+        output_id: impl Fn(OutputId) -> OutputId,
+    ) {
+        for node in &mut self.nodes {
+            node.id = node_id(node.id);
+            for x in &mut node.inputs {
+                *x = input_id(*x);
+            }
+            for x in &mut node.outputs {
+                *x = output_id(*x);
+            }
+        }
+        for input in &mut self.inputs {
+            input.parent = node_id(input.parent);
+            input.connected_output = input.connected_output.map(&output_id);
+        }
+        for output in &mut self.outputs {
+            output.parent = node_id(output.parent);
+            for x in &mut output.connections {
+                *x = input_id(*x);
+            }
+        }
+        for track in &mut self.timeline.tracks {
+            track.input = input_id(track.input);
+        }
+        for snapshot in &mut self.snapshots {
+            for x in &mut snapshot.values {
+                x.input = input_id(x.input);
+            }
         }
 
-        self.order_dirty = true
+        // Simpler to stop a snapshot fade than to follow it
+        self.snapshot_fade = None;
+        self.order_dirty = true;
+        self.renumbered += 1;
     }
 
+    // Replaces whatever the input was connected to
     pub fn connect(&mut self, from: OutputId, to: InputId) {
-        let input_field = &mut self.inputs[to];
-        input_field.connected_output = Some(from);
+        self.set_link(to, Some(from));
+    }
 
-        let output_pin = &mut self.outputs[from];
-        output_pin.connections.push(to);
+    pub fn disconnect(&mut self, input_field_id: InputId) {
+        if self.inputs[input_field_id].connected_output.is_none() {
+            panic!("Cannot disconnect, not connected!");
+        }
+        self.set_link(input_field_id, None);
+    }
+
+    // Connects the input to the output, or disconnects it for None
+    pub(crate) fn set_link(&mut self, input_id: InputId, output_id: Option<OutputId>) {
+        if let Some(previous) = self.inputs[input_id].connected_output.take() {
+            self.outputs[previous]
+                .connections
+                .retain(|x| *x != input_id);
+        }
+        if let Some(output_id) = output_id {
+            self.inputs[input_id].connected_output = Some(output_id);
+            self.outputs[output_id].connections.push(input_id);
+        }
 
         self.order_dirty = true;
     }
 
-    pub fn disconnect(&mut self, input_field_id: InputId) {
-        let input_field = &mut self.inputs[input_field_id];
+    pub fn set_node_position(&mut self, node_id: NodeId, position: (f64, f64)) {
+        self.nodes[node_id].ui_state.position = position;
+    }
 
-        match input_field.connected_output {
-            Some(id) => id,
-            None => panic!("Cannot disconnect, not connected!"),
+    // Does anything flow from one node to the other
+    fn reaches(&self, from: NodeId, to: NodeId) -> bool {
+        let mut stack = vec![from];
+        let mut seen = vec![false; self.nodes.len()];
+        while let Some(node_id) = stack.pop() {
+            if node_id == to {
+                return true;
+            }
+            if std::mem::replace(&mut seen[node_id], true) {
+                continue;
+            }
+            for output_id in &self.nodes[node_id].outputs {
+                for input_id in &self.outputs[*output_id].connections {
+                    stack.push(self.inputs[*input_id].parent);
+                }
+            }
+        }
+        false
+    }
+
+    // Applies the edit and records it in the history. now decides whether a
+    // value change joins the previous one.
+    pub fn edit(&mut self, edit: GraphEdit, now: Instant) -> Result<(), String> {
+        let change = match edit {
+            GraphEdit::AddNode { kind, position } => {
+                self.add_node(kind, NodeUIState { position });
+                Change::Node {
+                    node: self.nodes.len() - 1,
+                    removed: None,
+                }
+            }
+            GraphEdit::RemoveNode { node } => {
+                self.check_node(node)?;
+                Change::Node {
+                    node,
+                    removed: Some(Box::new(self.take_node(node))),
+                }
+            }
+            GraphEdit::Connect { from, to } => {
+                self.check_input(to)?;
+                let Some(output) = self.outputs.get(from) else {
+                    return Err(format!("Unknown output {}", from));
+                };
+                let field = &self.inputs[to];
+                let from_type = self.nodes[output.parent].kind.descriptor().outputs
+                    [output.index as usize]
+                    .data_type;
+                let to_desc =
+                    &self.nodes[field.parent].kind.descriptor().inputs[field.index as usize];
+                if !from_type.connects_to(to_desc.data_type) {
+                    return Err(format!(
+                        "{} can't be connected to {}",
+                        DataValue::default(from_type).type_name(),
+                        DataValue::default(to_desc.data_type).type_name()
+                    ));
+                }
+                if self.reaches(self.inputs[to].parent, output.parent) {
+                    return Err("The connection would make a loop".to_string());
+                }
+
+                let before = self.inputs[to].connected_output;
+                self.set_link(to, Some(from));
+                Change::Link {
+                    input: to,
+                    before,
+                    after: Some(from),
+                }
+            }
+            GraphEdit::Disconnect { input } => {
+                self.check_input(input)?;
+                let before = self.inputs[input].connected_output;
+                if before.is_none() {
+                    return Err(format!("Input {} isn't connected", input));
+                }
+
+                self.set_link(input, None);
+                Change::Link {
+                    input,
+                    before,
+                    after: None,
+                }
+            }
+            GraphEdit::SetInputValue { input, value } => {
+                self.check_input(input)?;
+                self.check_value(input, &value)?;
+
+                let before = self.inputs[input].value;
+                self.set_input_value(input, value);
+                Change::Value {
+                    input,
+                    before,
+                    after: value,
+                }
+            }
+            GraphEdit::MoveNode { node, position } => {
+                self.check_node(node)?;
+                let before = self.nodes[node].ui_state.position;
+                self.set_node_position(node, position);
+                Change::Position {
+                    node,
+                    before,
+                    after: position,
+                }
+            }
+            GraphEdit::SetNodeText { node, text } => {
+                self.check_node(node)?;
+                let before = self.nodes[node].text.clone();
+                self.set_node_text(node, &text);
+                Change::Text {
+                    node,
+                    before,
+                    after: text,
+                }
+            }
         };
-        input_field.connected_output = None;
 
-        self.order_dirty = true;
+        self.history.record(change, now);
+        Ok(())
+    }
+
+    fn check_node(&self, node_id: NodeId) -> Result<(), String> {
+        match self.has_node(node_id) {
+            true => Ok(()),
+            false => Err(format!("Unknown node {}", node_id)),
+        }
+    }
+
+    fn check_input(&self, input_id: InputId) -> Result<(), String> {
+        match input_id < self.inputs.len() {
+            true => Ok(()),
+            false => Err(format!("Unknown input {}", input_id)),
+        }
+    }
+
+    // The value has the type of the field, any value for an Any field
+    fn check_value(&self, input_id: InputId, value: &DataValue) -> Result<(), String> {
+        let field = &self.inputs[input_id];
+        let desc = &self.nodes[field.parent].kind.descriptor().inputs[field.index as usize];
+        let expected = DataValue::default(desc.data_type).type_name();
+        match matches!(desc.data_type, DataType::Any) || expected == value.type_name() {
+            true => Ok(()),
+            false => Err(format!("Expected {}", expected)),
+        }
+    }
+
+    // Edits until end_transaction are undone as one step
+    pub fn begin_transaction(&mut self) {
+        self.history.begin_transaction();
+    }
+
+    pub fn end_transaction(&mut self) {
+        self.history.end_transaction();
+    }
+
+    // False when there is nothing to undo
+    pub fn undo(&mut self) -> bool {
+        let Some(mut step) = self.history.take_undo() else {
+            return false;
+        };
+        for change in step.iter_mut().rev() {
+            change.apply(self);
+        }
+        self.history.push_redo(step);
+        true
+    }
+
+    pub fn redo(&mut self) -> bool {
+        let Some(mut step) = self.history.take_redo() else {
+            return false;
+        };
+        for change in step.iter_mut() {
+            change.apply(self);
+        }
+        self.history.push_undo(step);
+        true
+    }
+
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    // Known nodes in id order, once each
+    fn selection(&self, node_ids: &[NodeId]) -> Vec<NodeId> {
+        let mut selected: Vec<NodeId> = node_ids
+            .iter()
+            .copied()
+            .filter(|x| self.has_node(*x))
+            .collect();
+        selected.sort();
+        selected.dedup();
+        selected
+    }
+
+    // The nodes with their values, text and positions, and the connections
+    // between them. Connections to nodes outside the selection are left out.
+    pub fn extract_subgraph(&self, node_ids: &[NodeId]) -> Fragment {
+        let selected = self.selection(node_ids);
+
+        let nodes = selected
+            .iter()
+            .map(|x| {
+                let node = &self.nodes[*x];
+                FragmentNode {
+                    kind: node.kind,
+                    position: node.ui_state.position,
+                    text: node.text.clone(),
+                    values: node.inputs.iter().map(|x| self.inputs[*x].value).collect(),
+                }
+            })
+            .collect();
+
+        let mut connections = Vec::new();
+        for (to_node, node_id) in selected.iter().enumerate() {
+            for (to_input, input_id) in self.nodes[*node_id].inputs.iter().enumerate() {
+                let Some(output_id) = self.inputs[*input_id].connected_output else {
+                    continue;
+                };
+                let output = &self.outputs[output_id];
+                if let Ok(from_node) = selected.binary_search(&output.parent) {
+                    connections.push(FragmentConnection {
+                        from_node,
+                        from_output: output.index as usize,
+                        to_node,
+                        to_input,
+                    });
+                }
+            }
+        }
+
+        Fragment { nodes, connections }
+    }
+
+    // Adds the nodes of the fragment after the existing ones, moved by offset,
+    // and returns their ids. Values of another type than the field keep the
+    // default. Undone as one step.
+    pub fn insert_fragment(
+        &mut self,
+        fragment: &Fragment,
+        offset: (f64, f64),
+    ) -> Result<Vec<NodeId>, String> {
+        fragment.check()?;
+
+        let now = Instant::now();
+        self.history.begin_transaction();
+
+        let mut ids = Vec::new();
+        for x in &fragment.nodes {
+            let position = (x.position.0 + offset.0, x.position.1 + offset.1);
+            self.add_node(x.kind, NodeUIState { position });
+            let node_id = self.nodes.len() - 1;
+            self.set_node_text(node_id, &x.text);
+            for (input_id, value) in self.nodes[node_id]
+                .inputs
+                .clone()
+                .into_iter()
+                .zip(&x.values)
+            {
+                if self.check_value(input_id, value).is_ok() {
+                    self.inputs[input_id].value = *value;
+                }
+            }
+
+            self.history.record(
+                Change::Node {
+                    node: node_id,
+                    removed: None,
+                },
+                now,
+            );
+            ids.push(node_id);
+        }
+
+        for x in &fragment.connections {
+            let from = self.nodes[ids[x.from_node]].outputs[x.from_output];
+            let to = self.nodes[ids[x.to_node]].inputs[x.to_input];
+            let before = self.inputs[to].connected_output;
+            self.set_link(to, Some(from));
+            self.history.record(
+                Change::Link {
+                    input: to,
+                    before,
+                    after: Some(from),
+                },
+                now,
+            );
+        }
+
+        self.history.end_transaction();
+        Ok(ids)
+    }
+
+    // Replaces the nodes by a Compound node set to the given id and returns
+    // the definition for it. Outputs of the rest of the graph feeding the
+    // nodes go in through a GroupInput each, outputs of the nodes feeding the
+    // rest of the graph out through a GroupOutput each. Undone as one step.
+    pub fn group_nodes(
+        &mut self,
+        node_ids: &[NodeId],
+        compound: CompoundId,
+        now: Instant,
+    ) -> Result<Fragment, String> {
+        let selected = self.selection(node_ids);
+        if selected.is_empty() {
+            return Err("Nothing to group".to_string());
+        }
+        let inside = |node_id: NodeId| selected.binary_search(&node_id).is_ok();
+
+        // Outputs outside with the fields they feed, (fragment node, field index)
+        let mut incoming: Vec<(OutputId, Vec<(usize, usize)>)> = Vec::new();
+        // Pins inside, (fragment node, pin index), with the fields they feed outside
+        let mut outgoing: Vec<((usize, usize), Vec<InputId>)> = Vec::new();
+        for (index, node_id) in selected.iter().enumerate() {
+            let node = &self.nodes[*node_id];
+            for (i, input_id) in node.inputs.iter().enumerate() {
+                let Some(output_id) = self.inputs[*input_id].connected_output else {
+                    continue;
+                };
+                if inside(self.outputs[output_id].parent) {
+                    continue;
+                }
+                match incoming.iter_mut().find(|x| x.0 == output_id) {
+                    Some(x) => x.1.push((index, i)),
+                    None => incoming.push((output_id, vec![(index, i)])),
+                }
+            }
+            for (i, output_id) in node.outputs.iter().enumerate() {
+                let fields: Vec<InputId> = self.outputs[*output_id]
+                    .connections
+                    .iter()
+                    .copied()
+                    .filter(|x| !inside(self.inputs[*x].parent))
+                    .collect();
+                if !fields.is_empty() {
+                    outgoing.push(((index, i), fields));
+                }
+            }
+        }
+
+        if incoming.len() > COMPOUND_PINS || outgoing.len() > COMPOUND_PINS {
+            return Err(format!(
+                "A compound has at most {} inputs and {} outputs",
+                COMPOUND_PINS, COMPOUND_PINS
+            ));
+        }
+        // Something fed by the nodes feeding them again
+        for (_, fields) in &outgoing {
+            for field in fields {
+                let from = self.inputs[*field].parent;
+                if incoming
+                    .iter()
+                    .any(|x| self.reaches(from, self.outputs[x.0].parent))
+                {
+                    return Err("The compound would make a loop".to_string());
+                }
+            }
+        }
+
+        let mut fragment = self.extract_subgraph(&selected);
+        let xs = fragment.nodes.iter().map(|x| x.position.0);
+        let left = xs.clone().fold(f64::INFINITY, f64::min);
+        let right = xs.fold(f64::NEG_INFINITY, f64::max);
+        let top = fragment
+            .nodes
+            .iter()
+            .map(|x| x.position.1)
+            .fold(f64::INFINITY, f64::min);
+        let boundary = |kind, x, i: usize| FragmentNode {
+            kind,
+            position: (x, top + i as f64 * 100.0),
+            text: String::new(),
+            values: Vec::new(),
+        };
+
+        for (i, (_, fields)) in incoming.iter().enumerate() {
+            let from_node = fragment.nodes.len();
+            fragment
+                .nodes
+                .push(boundary(NodeKind::GroupInput, left - 200.0, i));
+            for (to_node, to_input) in fields {
+                fragment.connections.push(FragmentConnection {
+                    from_node,
+                    from_output: 0,
+                    to_node: *to_node,
+                    to_input: *to_input,
+                });
+            }
+        }
+        for (i, ((from_node, from_output), _)) in outgoing.iter().enumerate() {
+            fragment.connections.push(FragmentConnection {
+                from_node: *from_node,
+                from_output: *from_output,
+                to_node: fragment.nodes.len(),
+                to_input: 0,
+            });
+            fragment
+                .nodes
+                .push(boundary(NodeKind::GroupOutput, right + 200.0, i));
+        }
+
+        // Checked before anything changes, see GraphManager::group_nodes
+        let def = CompoundDef {
+            id: compound,
+            name: String::new(),
+            fragment,
+        };
+        self.io.compounds.check(&def)?;
+        let fragment = def.fragment;
+
+        let position = ((left + right) / 2.0, top);
+        self.history.begin_transaction();
+        let start = self.history.pending();
+        let result = (|| {
+            let kind = NodeKind::Compound;
+            self.edit(GraphEdit::AddNode { kind, position }, now)?;
+            let node = &self.nodes[self.nodes.len() - 1];
+            let (fields, pins) = (node.inputs.clone(), node.outputs.clone());
+            let value = DataValue::Number(compound as f64);
+            self.edit(
+                GraphEdit::SetInputValue {
+                    input: fields[0],
+                    value,
+                },
+                now,
+            )?;
+
+            for ((from, _), to) in incoming.iter().zip(&fields[1..]) {
+                let (from, to) = (*from, *to);
+                self.edit(GraphEdit::Connect { from, to }, now)?;
+            }
+            for ((_, fields), from) in outgoing.iter().zip(&pins) {
+                for to in fields {
+                    let (from, to) = (*from, *to);
+                    self.edit(GraphEdit::Connect { from, to }, now)?;
+                }
+            }
+            for node in selected.iter().rev() {
+                self.edit(GraphEdit::RemoveNode { node: *node }, now)?;
+            }
+            Ok::<(), String>(())
+        })();
+        // Half a group is put back as it was
+        if result.is_err() {
+            for mut change in self.history.take_pending(start).into_iter().rev() {
+                change.apply(self);
+            }
+        }
+        self.history.end_transaction();
+
+        result.map(|_| fragment)
+    }
+
+    // Replaces a Compound node by the nodes of its definition, connected to
+    // what the node was connected to, and returns their ids. Fields fed by an
+    // unconnected pin take its value. Undone as one step.
+    pub fn expand_compound(
+        &mut self,
+        node_id: NodeId,
+        now: Instant,
+    ) -> Result<Vec<NodeId>, String> {
+        self.check_node(node_id)?;
+        let node = self.nodes[node_id].clone();
+        if !matches!(node.kind, NodeKind::Compound) {
+            return Err("Only Compound nodes can be expanded".to_string());
+        }
+
+        let (values, _) = self.input_values_for(node_id);
+        let id = match values[0] {
+            DataValue::Number(id) => id.round().max(0.0) as CompoundId,
+            _ => return Err("Expected Number".to_string()),
+        };
+        let Some(def) = self.io.compounds.get(id).cloned() else {
+            return Err(format!("Compound {} doesn't exist", id));
+        };
+
+        // What every pin was connected to
+        let sources: Vec<(Option<OutputId>, DataValue)> = node.inputs[1..]
+            .iter()
+            .map(|x| (self.inputs[*x].connected_output, self.inputs[*x].value))
+            .collect();
+        let targets: Vec<Vec<InputId>> = node
+            .outputs
+            .iter()
+            .map(|x| self.outputs[*x].connections.clone())
+            .collect();
+
+        // Centred on the node
+        let count = def.fragment.nodes.len().max(1) as f64;
+        let centre = def.fragment.nodes.iter().fold((0.0, 0.0), |a, x| {
+            (a.0 + x.position.0 / count, a.1 + x.position.1 / count)
+        });
+        let position = node.ui_state.position;
+        let offset = (position.0 - centre.0, position.1 - centre.1);
+
+        self.history.begin_transaction();
+        let result = self.expand_fragment(node_id, &def, offset, &sources, &targets, now);
+        self.history.end_transaction();
+        result
+    }
+
+    fn expand_fragment(
+        &mut self,
+        node_id: NodeId,
+        def: &CompoundDef,
+        offset: (f64, f64),
+        sources: &[(Option<OutputId>, DataValue)],
+        targets: &[Vec<InputId>],
+        now: Instant,
+    ) -> Result<Vec<NodeId>, String> {
+        let ids = self.insert_fragment(&def.fragment, offset)?;
+        let boundary = |kind: fn(&NodeKind) -> bool| -> Vec<NodeId> {
+            ids.iter()
+                .copied()
+                .filter(|x| kind(&self.nodes[*x].kind))
+                .collect()
+        };
+        let group_inputs = boundary(|x| matches!(x, NodeKind::GroupInput));
+        let group_outputs = boundary(|x| matches!(x, NodeKind::GroupOutput));
+
+        // Fields fed by a GroupInput get what fed the pin
+        for (group_input, (from, value)) in group_inputs.iter().zip(sources) {
+            let output_id = self.nodes[*group_input].outputs[0];
+            for input in self.outputs[output_id].connections.clone() {
+                match from {
+                    Some(from) => self.edit(
+                        GraphEdit::Connect {
+                            from: *from,
+                            to: input,
+                        },
+                        now,
+                    )?,
+                    None => {
+                        self.edit(GraphEdit::Disconnect { input }, now)?;
+                        let value = *value;
+                        // A pin holding another type leaves the field as it was
+                        let _ = self.edit(GraphEdit::SetInputValue { input, value }, now);
+                    }
+                }
+            }
+        }
+
+        // Fields the pin fed get what fed the GroupOutput
+        for (group_output, fields) in group_outputs.iter().zip(targets) {
+            let field = &self.inputs[self.nodes[*group_output].inputs[0]];
+            let (from, value) = (field.connected_output, field.value);
+            for input in fields.iter().copied() {
+                match from {
+                    Some(from) => self.edit(GraphEdit::Connect { from, to: input }, now)?,
+                    None => {
+                        self.edit(GraphEdit::Disconnect { input }, now)?;
+                        let _ = self.edit(GraphEdit::SetInputValue { input, value }, now);
+                    }
+                }
+            }
+        }
+
+        let mut removed: Vec<NodeId> = group_inputs
+            .iter()
+            .chain(&group_outputs)
+            .copied()
+            .chain([node_id])
+            .collect();
+        removed.sort();
+        for node in removed.iter().rev() {
+            self.edit(GraphEdit::RemoveNode { node: *node }, now)?;
+        }
+
+        // Ids after the removals
+        Ok(ids
+            .into_iter()
+            .filter(|x| !removed.contains(x))
+            .map(|x| x - removed.iter().filter(|r| **r < x).count())
+            .collect())
     }
 
     fn inputs_for(&self, node_id: NodeId) -> Vec<&InputField> {
-        let node = match self.nodes.iter().find(|&x| x.id == node_id) {
+        let node = match self.nodes.get(node_id) {
             Some(node) => node,
             None => panic!("Node not found in Graph"),
         };
@@ -191,7 +994,7 @@ impl Graph {
     }
 
     fn outputs_for(&self, node_id: NodeId) -> Vec<&OutputPin> {
-        let node = match self.nodes.iter().find(|&x| x.id == node_id) {
+        let node = match self.nodes.get(node_id) {
             Some(node) => node,
             None => panic!("Node not found in Graph"),
         };
@@ -200,7 +1003,7 @@ impl Graph {
     }
 
     fn outputs_for_mut(&mut self, node_id: NodeId) -> Vec<&mut OutputPin> {
-        let node = match self.nodes.iter().find(|&x| x.id == node_id) {
+        let node = match self.nodes.get(node_id) {
             Some(node) => node,
             None => panic!("Node not found in Graph"),
         };
@@ -223,14 +1026,34 @@ impl Graph {
         result
     }
 
-    fn input_values_for(&self, node_id: NodeId) -> Vec<DataValue> {
-        self.inputs_for(node_id)
+    // A connected value the field doesn't take, e.g. from an Any output, is
+    // replaced by the field's own value and reported
+    fn input_values_for(&self, node_id: NodeId) -> (Vec<DataValue>, Option<String>) {
+        let descs = self.nodes[node_id].kind.descriptor().inputs;
+        let mut wrong_type = None;
+        let values = self
+            .inputs_for(node_id)
             .iter()
-            .map(|input| match input.connected_output {
-                Some(output_id) => self.outputs[output_id].value.clone(),
-                None => input.value.clone(),
+            .zip(descs)
+            .map(|(input, desc)| match input.connected_output {
+                Some(output_id) => {
+                    let value = self.outputs[output_id].value;
+                    if desc.data_type.accepts(&value) {
+                        return value;
+                    }
+                    wrong_type = Some(format!(
+                        "{} on '{}', {} instead of {}",
+                        WRONG_TYPE,
+                        desc.name,
+                        value.type_name(),
+                        DataValue::default(desc.data_type).type_name()
+                    ));
+                    input.value
+                }
+                None => input.value,
             })
-            .collect()
+            .collect();
+        (values, wrong_type)
     }
 
     fn output_values_for(&mut self, node_id: NodeId) -> Vec<&mut DataValue> {
@@ -242,8 +1065,9 @@ impl Graph {
 
     fn calculate_indegrees(&self) -> HashMap<NodeId, u32> {
         let mut indegrees = HashMap::with_capacity(self.nodes.len());
-        for input in &self.inputs {
-            indegrees.insert(input.parent, 0);
+        // Every node, nodes without inputs included
+        for node in &self.nodes {
+            indegrees.insert(node.id, 0);
         }
 
         for input in &self.inputs {
@@ -296,18 +1120,346 @@ impl Graph {
         self.order_dirty = false;
     }
 
+    // Universes read by DmxInput nodes until the next call
+    pub fn set_dmx_input(&mut self, dmx_in: Arc<DmxInputs>) {
+        self.io.dmx_in = dmx_in;
+    }
+
+    // Messages read by OscIn nodes until the next call
+    pub fn set_osc_input(&mut self, osc_in: Arc<OscInputs>) {
+        self.io.osc_in = osc_in;
+    }
+
+    // Levels written by DmxOutput nodes in the last frame
+    pub fn take_dmx_output(&mut self) -> DmxOutputs {
+        std::mem::take(&mut self.io.dmx_out)
+    }
+
+    // Messages queued by OscOut nodes since the last call
+    pub fn take_osc_output(&mut self) -> Vec<OscMessage> {
+        std::mem::take(&mut self.io.osc_out)
+    }
+
+    // Named channels read by ExternalInput nodes until the next call
+    pub fn set_external_input(&mut self, inputs: Arc<ExternalInputs>) {
+        self.io.inputs = inputs;
+    }
+
+    // Controller state read by the MIDI input nodes until the next call
+    pub fn set_midi_input(&mut self, midi_in: Arc<MidiInputs>) {
+        self.io.midi_in = midi_in;
+    }
+
+    // Messages queued by MidiCCOut nodes since the last call
+    pub fn take_midi_output(&mut self) -> Vec<MidiMessage> {
+        std::mem::take(&mut self.io.midi_out)
+    }
+
+    // Patch used by Fixture nodes until the next call
+    pub fn set_fixtures(&mut self, fixtures: Arc<FixtureMap>) {
+        self.io.fixtures = fixtures;
+    }
+
+    // Cue list levels read by CueList nodes until the next call
+    pub fn set_cues(&mut self, cues: Arc<CueOutputs>) {
+        self.io.cues = cues;
+    }
+
+    // Definitions used by Compound nodes until the next call
+    pub fn set_compounds(&mut self, compounds: Arc<CompoundLibrary>) {
+        self.io.compounds = compounds;
+    }
+
+    // Values of the input fields that aren't connected, of the given nodes or
+    // all of them. Triggers are left out, they don't hold a value.
+    pub fn capture_snapshot(&self, nodes: Option<&[NodeId]>) -> Vec<SnapshotValue> {
+        self.nodes
+            .iter()
+            .filter(|x| nodes.is_none_or(|nodes| nodes.contains(&x.id)))
+            .flat_map(|x| x.inputs.iter())
+            .filter(|x| self.inputs[**x].connected_output.is_none())
+            .filter(|x| !matches!(self.inputs[**x].value, DataValue::Trigger(_)))
+            .map(|x| SnapshotValue {
+                input: *x,
+                value: self.inputs[*x].value,
+            })
+            .collect()
+    }
+
+    // Sets the values at once, or fades to them over fade seconds of ticks.
+    // Fields since removed, connected or holding another type are skipped.
+    pub fn recall_snapshot(&mut self, values: &[SnapshotValue], fade: f64) {
+        let values: Vec<_> = values
+            .iter()
+            .filter_map(|x| {
+                let field = self.inputs.get(x.input)?;
+                (field.connected_output.is_none() && field.value.type_name() == x.value.type_name())
+                    .then_some((x.input, field.value, x.value))
+            })
+            .collect();
+
+        self.snapshot_fade = Some(SnapshotFade {
+            values,
+            duration: fade,
+            elapsed: 0.0,
+        });
+        self.advance_snapshot(0.0);
+    }
+
+    pub fn snapshots(&self) -> &[Snapshot] {
+        &self.snapshots
+    }
+
+    // Kept with the graph, its values follow the ids of the fields as nodes
+    // are removed and put back
+    pub fn add_snapshot(&mut self, snapshot: Snapshot) {
+        self.snapshots.push(snapshot);
+    }
+
+    pub fn set_snapshots(&mut self, snapshots: Vec<Snapshot>) {
+        self.snapshots = snapshots;
+    }
+
+    pub fn remove_snapshot(&mut self, id: SnapshotId) {
+        self.snapshots.retain(|x| x.id != id);
+    }
+
+    // False for a snapshot the graph doesn't have
+    pub fn recall_stored_snapshot(&mut self, id: SnapshotId, fade: f64) -> bool {
+        let Some(snapshot) = self.snapshots.iter().find(|x| x.id == id) else {
+            return false;
+        };
+        let values = snapshot.values.clone();
+        self.recall_snapshot(&values, fade);
+        true
+    }
+
+    // A project just loaded, forgets everything before
+    pub fn clear_history(&mut self) {
+        self.history = History::default();
+    }
+
+    fn advance_snapshot(&mut self, delta: f64) {
+        let Some(fade) = &mut self.snapshot_fade else {
+            return;
+        };
+        fade.elapsed += delta;
+
+        for (input, value) in fade.values() {
+            self.inputs[input].value = value;
+            if let Some(x) = self.dirty.get_mut(self.inputs[input].parent) {
+                *x = true;
+            }
+        }
+        if fade.done() {
+            self.snapshot_fade = None;
+        }
+    }
+
+    pub fn timeline(&self) -> &Timeline {
+        &self.timeline
+    }
+
+    pub fn timeline_mut(&mut self) -> &mut Timeline {
+        &mut self.timeline
+    }
+
+    pub fn config(&self) -> GraphConfig {
+        GraphConfig {
+            nodes: self.extract_subgraph(&(0..self.node_count()).collect::<Vec<_>>()),
+            timeline: self.timeline.clone(),
+        }
+    }
+
+    // Adds the input's track when missing, replaces the keyframe at the same
+    // time. Checked like a value set through edit().
+    pub fn set_keyframe(&mut self, input: InputId, keyframe: Keyframe) -> Result<(), String> {
+        self.check_input(input)?;
+        self.check_value(input, &keyframe.value)?;
+        self.timeline.track_mut(input).set_keyframe(keyframe);
+        Ok(())
+    }
+
+    // False when there was none, no track is added
+    pub fn remove_keyframe(&mut self, input: InputId, time: f64) -> bool {
+        if self.timeline.track(input).is_none() {
+            return false;
+        }
+        self.timeline.track_mut(input).remove_keyframe(time)
+    }
+
+    // Tracks of connected fields, or of fields holding another type, do nothing
+    fn advance_timeline(&mut self, delta: f64) {
+        for (input, value) in self.timeline.advance(delta) {
+            if let Some(field) = self.inputs.get_mut(input) {
+                if field.connected_output.is_none() && field.value.type_name() == value.type_name()
+                {
+                    field.value = value;
+                    let parent = field.parent;
+                    self.mark_dirty(parent);
+                }
+            }
+        }
+    }
+
+    // Advance the clock by delta seconds, apply snapshot fades and the
+    // timeline, then process a frame
+    pub fn tick(&mut self, delta: f64) {
+        self.clock.advance(delta);
+        self.advance_snapshot(delta);
+        self.advance_timeline(delta);
+        self.process();
+    }
+
     pub fn process(&mut self) {
+        let mut io = std::mem::take(&mut self.io);
+        // Levels are written again every frame, a removed node stops writing
+        io.dmx_out.clear();
+        self.run(&mut io);
+        self.io = io;
+        self.clear_triggers();
+
+        // A second process() without a tick() shouldn't advance time dependent nodes again
+        self.clock.delta = 0.0;
+    }
+
+    // A frame of the graph of a Compound node, inside the frame of the graph
+    // the node is in and with its io
+    pub fn process_nested(&mut self, time: f64, delta: f64, io: &mut FrameIo) {
+        self.clock.time = time;
+        self.clock.delta = delta;
+        self.run(io);
+        self.clear_triggers();
+    }
+
+    // Runs the nodes in order. Without incremental set every node runs,
+    // otherwise only volatile nodes, nodes with state and nodes an input of
+    // changed since they last ran.
+    fn run(&mut self, io: &mut FrameIo) {
         if self.order_dirty {
-            self.sort_nodes()
+            self.sort_nodes();
+            // Ids may have moved, everything runs once
+            self.dirty = vec![true; self.nodes.len()];
         };
 
-        for node_id in &self.execution_order.clone() {
-            let node = &self.nodes[*node_id].clone();
+        let clock = self.clock;
+        self.processed = 0;
+        for i in 0..self.execution_order.len() {
+            let node_id = self.execution_order[i];
+            let node = &mut self.nodes[node_id];
+            let kind = node.kind;
+            let desc = kind.descriptor();
+            if self.incremental && !self.dirty[node_id] && !desc.volatile && desc.state.is_empty() {
+                continue;
+            }
+            self.dirty[node_id] = false;
+            self.processed += 1;
+
+            let mut state = std::mem::take(&mut node.state);
+            let text = std::mem::take(&mut node.text);
+            let mut cache = node.cache.take();
+            // The node doesn't know about a wrong type from the last frame
+            let mut error = node.error.take().filter(|x| !x.starts_with(WRONG_TYPE));
+
+            let (inputs, wrong_type) = self.input_values_for(node_id);
+            let before: Vec<DataValue> =
+                self.outputs_for(node_id).iter().map(|x| x.value).collect();
+            let mut outputs = self.output_values_for(node_id);
+
+            let mut context = ProcessContext {
+                time: clock.time,
+                delta: clock.delta,
+                state: &mut state,
+                text: &text,
+                cache: &mut cache,
+                error: &mut error,
+                io,
+            };
+
+            (desc.process)(&mut context, inputs, outputs.as_mut());
+
+            let node = &mut self.nodes[node_id];
+            node.state = state;
+            node.text = text;
+            node.cache = cache;
+            node.error = wrong_type.or(error);
+
+            for (i, value) in before.iter().enumerate() {
+                let output_id = self.nodes[node_id].outputs[i];
+                if self.outputs[output_id].value != *value {
+                    self.mark_readers(output_id);
+                }
+            }
+        }
+    }
 
-            let inputs = self.input_values_for(*node_id);
-            let mut outputs = self.output_values_for(*node_id);
+    // Triggers only last for the frame they were fired in, whatever read one
+    // runs again to see it off
+    fn clear_triggers(&mut self) {
+        for output_id in 0..self.outputs.len() {
+            if let DataValue::Trigger(ref mut t) = self.outputs[output_id].value {
+                if std::mem::take(t) {
+                    self.mark_readers(output_id);
+                }
+            }
+        }
+
+        for input in &mut self.inputs {
+            if let DataValue::Trigger(ref mut t) = input.value {
+                if std::mem::take(t) {
+                    if let Some(x) = self.dirty.get_mut(input.parent) {
+                        *x = true;
+                    }
+                }
+            }
+        }
+    }
+
+    // With the values of the input fields
+    fn node_dto(&self, node: &Node) -> NodeDto {
+        let mut dto = node.to_dto();
+        for (field, input_id) in dto.inputs.iter_mut().zip(&node.inputs) {
+            field.value = self.inputs[*input_id].value;
+        }
+        dto
+    }
+
+    // What changed since before was taken. Nodes are compared by id, so
+    // every node after a removed or restored one is in it.
+    pub fn delta(&self, before: &GraphDto) -> GraphDeltaDto {
+        let json = |x: &NodeDto| serde_json::to_value(x).ok();
+        let after = self.to_dto();
+
+        let pairs = |x: &GraphDto| -> Vec<(OutputId, InputId)> {
+            x.connections.iter().map(|x| (x.from, x.to)).collect()
+        };
+        let (old, new) = (pairs(before), pairs(&after));
+
+        let nodes = after
+            .nodes
+            .into_iter()
+            .filter(|x| before.nodes.get(x.id).is_none_or(|b| json(b) != json(x)))
+            .collect();
+        let removed = (self.nodes.len()..before.nodes.len()).collect();
+        let to_dto = |(from, to): &(OutputId, InputId)| ConnectionDto {
+            from: *from,
+            to: *to,
+        };
 
-            (node.kind.descriptor().process)(inputs, outputs.as_mut());
+        GraphDeltaDto {
+            id: self.id,
+            nodes,
+            removed,
+            connected: new
+                .iter()
+                .filter(|x| !old.contains(x))
+                .map(to_dto)
+                .collect(),
+            disconnected: old
+                .iter()
+                .filter(|x| !new.contains(x))
+                .map(to_dto)
+                .collect(),
         }
     }
 
@@ -328,7 +1480,7 @@ impl Graph {
 
         GraphDto {
             id: self.id,
-            nodes: self.nodes.iter().map(|x| x.to_dto()).collect(),
+            nodes: self.nodes.iter().map(|x| self.node_dto(x)).collect(),
             connections: connections,
         }
     }
@@ -416,4 +1568,405 @@ mod tests {
 
         assert!(graph.outputs[1].value == DataValue::Number(5.0))
     }
+
+    #[test]
+    fn test_graph_triggers_last_one_frame() {
+        let mut graph = Graph::new(0);
+
+        let ui_state = node::NodeUIState {
+            position: (0.0, 0.0),
+        };
+
+        graph.add_node(node::NodeKind::RisingEdge, ui_state.clone());
+        graph.add_node(node::NodeKind::Counter, ui_state.clone());
+
+        // Rising Edge -> Counter "Trigger"
+        graph.connect(0, 1);
+
+        graph.inputs[0].value = DataValue::Boolean(true);
+        graph.tick(0.1);
+        graph.tick(0.1);
+        assert!(graph.outputs[1].value == DataValue::Number(1.0));
+
+        graph.inputs[0].value = DataValue::Boolean(false);
+        graph.tick(0.1);
+        graph.inputs[0].value = DataValue::Boolean(true);
+        graph.tick(0.1);
+        assert!(graph.outputs[1].value == DataValue::Number(2.0));
+
+        // Unconnected trigger inputs, e.g. a button in the UI, are cleared too
+        graph.inputs[2].value = DataValue::Trigger(true);
+        graph.tick(0.1);
+        assert!(graph.outputs[1].value == DataValue::Number(0.0));
+        assert!(graph.inputs[2].value == DataValue::Trigger(false));
+    }
+
+    #[test]
+    fn test_graph_snapshots() {
+        use crate::types::color::{Color, ColorValue};
+
+        let mut graph = Graph::new(0);
+
+        let ui_state = node::NodeUIState {
+            position: (0.0, 0.0),
+        };
+
+        graph.add_node(node::NodeKind::ConstantNumber, ui_state.clone());
+        graph.add_node(node::NodeKind::ConstantColor, ui_state.clone());
+        graph.add_node(node::NodeKind::MapRange, ui_state.clone());
+
+        // Constant Number -> Map Range "Value", left out of snapshots
+        graph.connect(0, 2);
+
+        let red = DataValue::Color(Color::new(ColorValue::RGB(1.0, 0.0, 0.0)));
+        let blue = DataValue::Color(Color::new(ColorValue::RGB(0.0, 0.0, 1.0)));
+        graph.set_input_value(0, DataValue::Number(1.0));
+        graph.set_input_value(1, red);
+        graph.set_input_value(7, DataValue::Boolean(true));
+
+        let snapshot = graph.capture_snapshot(None);
+        assert_eq!(
+            vec![0, 1, 3, 4, 5, 6, 7],
+            snapshot.iter().map(|x| x.input).collect::<Vec<_>>()
+        );
+        assert_eq!(1, graph.capture_snapshot(Some(&[1])).len());
+
+        graph.set_input_value(0, DataValue::Number(0.0));
+        graph.set_input_value(1, blue);
+        graph.set_input_value(7, DataValue::Boolean(false));
+
+        // Halfway the Boolean has switched and the colour is mixed perceptually
+        graph.recall_snapshot(&snapshot, 2.0);
+        graph.tick(0.9);
+        assert!(graph.get_input_value(0) == DataValue::Number(0.45));
+        assert!(graph.get_input_value(7) == DataValue::Boolean(false));
+        graph.tick(0.1);
+        assert!(graph.get_input_value(7) == DataValue::Boolean(true));
+        let DataValue::Color(mixed) = graph.get_input_value(1) else {
+            panic!("Expected Color");
+        };
+        // Brighter than mixing the RGB values
+        assert!(mixed.r() > 0.5 && mixed.b() > 0.5);
+
+        // Setting a value takes it out of the fade
+        graph.set_input_value(0, DataValue::Number(0.25));
+        graph.tick(1.0);
+        assert!(graph.get_input_value(0) == DataValue::Number(0.25));
+        assert!(graph.get_input_value(1) == red);
+
+        graph.set_input_value(0, DataValue::Number(0.0));
+        graph.recall_snapshot(&snapshot, 0.0);
+        assert!(graph.get_input_value(0) == DataValue::Number(1.0));
+    }
+
+    fn connections(graph: &Graph) -> Vec<(OutputId, InputId)> {
+        graph
+            .to_dto()
+            .connections
+            .iter()
+            .map(|x| (x.from, x.to))
+            .collect()
+    }
+
+    #[test]
+    fn test_graph_undo_redo() {
+        use std::time::Duration;
+
+        let mut graph = Graph::new(0);
+        let now = Instant::now();
+        let edit = |graph: &mut Graph, edit: GraphEdit| graph.edit(edit, now).unwrap();
+
+        for kind in [
+            node::NodeKind::ConstantNumber,
+            node::NodeKind::ConstantNumber,
+            node::NodeKind::MapRange,
+        ] {
+            let position = (0.0, 0.0);
+            edit(&mut graph, GraphEdit::AddNode { kind, position });
+        }
+        // Numbers -> Map Range "Value" and "In Min"
+        edit(&mut graph, GraphEdit::Connect { from: 0, to: 2 });
+        edit(&mut graph, GraphEdit::Connect { from: 1, to: 3 });
+
+        // A drag is one step
+        for (i, ms) in [0, 300, 600].iter().enumerate() {
+            let value = DataValue::Number(i as f64 + 1.0);
+            let edit = GraphEdit::SetInputValue { input: 0, value };
+            graph.edit(edit, now + Duration::from_millis(*ms)).unwrap();
+        }
+
+        // Fields and pins after the removed node move down
+        edit(&mut graph, GraphEdit::RemoveNode { node: 1 });
+        assert_eq!(2, graph.nodes.len());
+        assert_eq!(vec![(0, 1)], connections(&graph));
+        graph.process();
+        assert!(graph.get_output_value(0) == DataValue::Number(3.0));
+
+        assert!(graph.undo());
+        assert_eq!(3, graph.nodes.len());
+        assert_eq!(vec![(0, 2), (1, 3)], connections(&graph));
+        assert_eq!(vec![2, 3, 4, 5, 6, 7], graph.get_node(2).inputs);
+        graph.process();
+
+        assert!(graph.undo());
+        assert!(graph.get_input_value(0) == DataValue::Number(0.0));
+        assert!(graph.redo());
+        assert!(graph.get_input_value(0) == DataValue::Number(3.0));
+
+        while graph.undo() {}
+        assert_eq!(0, graph.nodes.len());
+        assert!(graph.inputs.is_empty() && graph.outputs.is_empty());
+        while graph.redo() {}
+        assert_eq!(2, graph.nodes.len());
+        assert_eq!(vec![(0, 1)], connections(&graph));
+
+        // Rejected edits aren't recorded
+        let loop_edit = GraphEdit::Connect { from: 1, to: 0 };
+        assert!(graph.edit(loop_edit, now).is_err());
+        let value = DataValue::Boolean(true);
+        assert!(graph
+            .edit(GraphEdit::SetInputValue { input: 0, value }, now)
+            .is_err());
+        assert!(graph.undo());
+        assert_eq!(3, graph.nodes.len());
+    }
+
+    #[test]
+    fn test_graph_transactions_and_delta() {
+        let mut graph = Graph::new(0);
+        let now = Instant::now();
+        assert!(!graph.undo());
+
+        for _ in 0..3 {
+            let kind = node::NodeKind::ConstantNumber;
+            let position = (0.0, 0.0);
+            graph
+                .edit(GraphEdit::AddNode { kind, position }, now)
+                .unwrap();
+        }
+
+        // Moving a selection
+        graph.begin_transaction();
+        for node in [0, 2] {
+            let position = (10.0, node as f64);
+            graph
+                .edit(GraphEdit::MoveNode { node, position }, now)
+                .unwrap();
+        }
+        graph.end_transaction();
+
+        let before = graph.to_dto();
+        assert!(graph.undo());
+        let delta = graph.delta(&before);
+        assert_eq!(
+            vec![0, 2],
+            delta.nodes.iter().map(|x| x.id).collect::<Vec<_>>()
+        );
+        assert_eq!((0.0, 0.0), delta.nodes[1].position);
+
+        // A new edit drops what was undone
+        let text = "x".to_string();
+        graph
+            .edit(GraphEdit::SetNodeText { node: 1, text }, now)
+            .unwrap();
+        assert!(!graph.redo());
+
+        graph
+            .edit(GraphEdit::Connect { from: 0, to: 1 }, now)
+            .unwrap();
+        let before = graph.to_dto();
+        graph.edit(GraphEdit::RemoveNode { node: 0 }, now).unwrap();
+        let delta = graph.delta(&before);
+        assert_eq!(vec![2], delta.removed);
+        assert!(delta.connected.is_empty());
+        assert_eq!(0, delta.disconnected[0].from);
+
+        let before = graph.to_dto();
+        assert!(graph.undo());
+        let delta = graph.delta(&before);
+        assert_eq!(3, delta.nodes.len());
+        assert_eq!(1, delta.connected[0].to);
+    }
+
+    #[test]
+    fn test_graph_rejects_connections_between_types() {
+        let mut graph = Graph::new(0);
+        let now = Instant::now();
+        for kind in [
+            node::NodeKind::ConstantColor,
+            node::NodeKind::DimmerCurve,
+            node::NodeKind::Select,
+        ] {
+            let position = (0.0, 0.0);
+            graph
+                .edit(GraphEdit::AddNode { kind, position }, now)
+                .unwrap();
+        }
+
+        // Color -> Dimmer Curve "Value"
+        assert!(graph
+            .edit(GraphEdit::Connect { from: 0, to: 1 }, now)
+            .is_err());
+        assert!(connections(&graph).is_empty());
+
+        // Through Select, Any on either side
+        graph
+            .edit(GraphEdit::Connect { from: 0, to: 5 }, now)
+            .unwrap();
+        graph
+            .edit(GraphEdit::Connect { from: 4, to: 1 }, now)
+            .unwrap();
+        graph.process();
+
+        let fragment = Fragment {
+            nodes: graph.extract_subgraph(&[0, 1]).nodes,
+            connections: vec![FragmentConnection {
+                from_node: 0,
+                from_output: 0,
+                to_node: 1,
+                to_input: 0,
+            }],
+        };
+        assert!(fragment.check().is_err());
+        assert!(graph.insert_fragment(&fragment, (0.0, 0.0)).is_err());
+        assert_eq!(3, graph.nodes.len());
+    }
+
+    #[test]
+    fn test_graph_copy_paste() {
+        let mut graph = Graph::new(0);
+        for (kind, x) in [
+            (node::NodeKind::ConstantNumber, 0.0),
+            (node::NodeKind::ConstantNumber, 100.0),
+            (node::NodeKind::MapRange, 200.0),
+        ] {
+            graph.add_node(kind, NodeUIState { position: (x, 0.0) });
+        }
+        graph.set_input_value(1, DataValue::Number(0.5));
+        graph.set_node_text(2, "range");
+        // Number -> "Value", Number -> "In Min"
+        graph.connect(0, 2);
+        graph.connect(1, 3);
+
+        // The connection from the unselected node is dropped
+        let fragment = graph.extract_subgraph(&[2, 1, 7]);
+        assert_eq!(2, fragment.nodes.len());
+        assert_eq!(1, fragment.connections.len());
+        let json = fragment.to_json().unwrap();
+
+        let mut other = Graph::new(1);
+        other.add_node(
+            node::NodeKind::ConstantColor,
+            NodeUIState {
+                position: (0.0, 0.0),
+            },
+        );
+        let fragment = Fragment::from_json(&json).unwrap();
+        assert_eq!(
+            vec![1, 2],
+            other.insert_fragment(&fragment, (10.0, 20.0)).unwrap()
+        );
+
+        let node = other.get_node(2);
+        assert_eq!("range", node.text);
+        assert_eq!((210.0, 20.0), node.ui_state.position);
+        // Color "In", then the Number "In" at 1, so Map Range starts at 2
+        assert_eq!(vec![(1, 3)], connections(&other));
+        assert!(other.get_input_value(1) == DataValue::Number(0.5));
+        assert!(other.get_input_value(2) == DataValue::Number(0.0));
+        other.process();
+
+        // Duplicating in the same graph, undone at once
+        let fragment = graph.extract_subgraph(&[0, 1, 2]);
+        assert_eq!(
+            vec![3, 4, 5],
+            graph.insert_fragment(&fragment, (0.0, 50.0)).unwrap()
+        );
+        assert_eq!(vec![(0, 2), (1, 3), (3, 10), (4, 11)], connections(&graph));
+        assert!(graph.undo());
+        assert_eq!(3, graph.nodes.len());
+        assert_eq!(vec![(0, 2), (1, 3)], connections(&graph));
+
+        // Pasting a hand edited loop changes nothing
+        let mut fragment = graph.extract_subgraph(&[1, 2]);
+        fragment.connections.push(FragmentConnection {
+            from_node: 1,
+            from_output: 0,
+            to_node: 0,
+            to_input: 0,
+        });
+        assert!(graph.insert_fragment(&fragment, (0.0, 0.0)).is_err());
+        fragment.connections[1].to_input = 9;
+        assert!(graph.insert_fragment(&fragment, (0.0, 0.0)).is_err());
+        assert_eq!(3, graph.nodes.len());
+    }
+
+    #[test]
+    fn test_graph_incremental_matches_full() {
+        let build = || {
+            let mut graph = Graph::new(0);
+            for kind in [
+                node::NodeKind::ConstantNumber,
+                node::NodeKind::ConstantNumber,
+                node::NodeKind::Expression,
+                node::NodeKind::ConstantNumber,
+                node::NodeKind::Select,
+                node::NodeKind::Select,
+                node::NodeKind::ConstantNumber,
+            ] {
+                let ui_state = NodeUIState {
+                    position: (0.0, 0.0),
+                };
+                graph.add_node(kind, ui_state);
+            }
+            // Number -> Number -> "a * 2" -> Number, Select -> Select "True"
+            graph.connect(0, 1);
+            graph.connect(1, 2);
+            graph.connect(2, 6);
+            graph.connect(4, 11);
+            graph.set_node_text(2, "a * 2");
+            graph
+        };
+        let mut graph = build();
+        let mut full = build();
+        full.set_incremental(false);
+
+        let frames: [(&[(InputId, DataValue)], usize); 6] = [
+            (&[], 7),
+            // Only the Expression, it reads the time
+            (&[], 1),
+            (&[(0, DataValue::Number(2.0))], 4),
+            (
+                &[
+                    (7, DataValue::Boolean(true)),
+                    (8, DataValue::Trigger(true)),
+                    (10, DataValue::Boolean(true)),
+                ],
+                3,
+            ),
+            // Both Selects again once the trigger is off
+            (&[], 3),
+            (&[], 1),
+        ];
+        for (values, processed) in frames {
+            for (input, value) in values {
+                graph.set_input_value(*input, *value);
+                full.set_input_value(*input, *value);
+            }
+            graph.tick(0.1);
+            full.tick(0.1);
+
+            assert_eq!(processed, graph.processed());
+            assert_eq!(7, full.processed());
+            for (a, b) in graph.outputs.iter().zip(&full.outputs) {
+                assert!(a.value == b.value);
+            }
+        }
+        assert!(graph.get_output_value(3) == DataValue::Number(4.0));
+
+        // Text changes run the node again
+        graph.set_node_text(2, "a * 3");
+        graph.tick(0.1);
+        assert!(graph.get_output_value(3) == DataValue::Number(6.0));
+    }
 }
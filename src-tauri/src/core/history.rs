@@ -0,0 +1,270 @@
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+use crate::core::graph::Graph;
+use crate::core::input_field::{InputField, InputId};
+use crate::core::node::{Node, NodeId, NodeKind};
+use crate::core::output_pin::{OutputId, OutputPin};
+use crate::core::snapshot::{SnapshotId, SnapshotValue};
+use crate::timeline::Track;
+use crate::types::data_type::DataValue;
+
+// Changes to the same input closer together than this are one step, e.g.
+// dragging a slider
+pub const COALESCE_TIME: Duration = Duration::from_millis(500);
+
+// Oldest steps are dropped past this
+pub const MAX_STEPS: usize = 500;
+
+// An edit asked for by the UI, see Graph::edit
+#[derive(Clone, Deserialize)]
+#[serde(tag = "type")]
+pub enum GraphEdit {
+    AddNode {
+        kind: NodeKind,
+        position: (f64, f64),
+    },
+    RemoveNode {
+        node: NodeId,
+    },
+    Connect {
+        from: OutputId,
+        to: InputId,
+    },
+    Disconnect {
+        input: InputId,
+    },
+    SetInputValue {
+        input: InputId,
+        value: DataValue,
+    },
+    MoveNode {
+        node: NodeId,
+        position: (f64, f64),
+    },
+    SetNodeText {
+        node: NodeId,
+        text: String,
+    },
+}
+
+// A node taken out of the graph with everything needed to put it back as it was
+#[derive(Clone)]
+pub struct RemovedNode {
+    pub node: Node,
+    // Where its fields and pins were
+    pub input_start: InputId,
+    pub output_start: OutputId,
+    pub inputs: Vec<InputField>,
+    pub outputs: Vec<OutputPin>,
+    // Inputs of other nodes that were connected to it, (input, output)
+    pub dependents: Vec<(InputId, OutputId)>,
+    pub tracks: Vec<Track>,
+    // Values snapshots held for its fields
+    pub snapshot_values: Vec<(SnapshotId, SnapshotValue)>,
+}
+
+// One reversible change. Applying it swaps before and after, so the same
+// call undoes and redoes it.
+#[derive(Clone)]
+pub enum Change {
+    // Added or removed, removed holds it while it's out of the graph
+    Node {
+        node: NodeId,
+        removed: Option<Box<RemovedNode>>,
+    },
+    Link {
+        input: InputId,
+        before: Option<OutputId>,
+        after: Option<OutputId>,
+    },
+    Value {
+        input: InputId,
+        before: DataValue,
+        after: DataValue,
+    },
+    Position {
+        node: NodeId,
+        before: (f64, f64),
+        after: (f64, f64),
+    },
+    Text {
+        node: NodeId,
+        before: String,
+        after: String,
+    },
+}
+
+impl Change {
+    pub fn apply(&mut self, graph: &mut Graph) {
+        match self {
+            Change::Node { node, removed } => match removed.take() {
+                Some(x) => graph.restore_node(*x),
+                None => *removed = Some(Box::new(graph.take_node(*node))),
+            },
+            Change::Link {
+                input,
+                before,
+                after,
+            } => {
+                graph.set_link(*input, *before);
+                std::mem::swap(before, after);
+            }
+            Change::Value {
+                input,
+                before,
+                after,
+            } => {
+                graph.set_input_value(*input, *before);
+                std::mem::swap(before, after);
+            }
+            Change::Position {
+                node,
+                before,
+                after,
+            } => {
+                graph.set_node_position(*node, *before);
+                std::mem::swap(before, after);
+            }
+            Change::Text {
+                node,
+                before,
+                after,
+            } => {
+                graph.set_node_text(*node, before);
+                std::mem::swap(before, after);
+            }
+        }
+    }
+}
+
+// Undo and redo stacks of a graph. A step is the changes of one edit, or of
+// every edit in a transaction.
+#[derive(Default)]
+pub struct History {
+    undo: Vec<Vec<Change>>,
+    redo: Vec<Vec<Change>>,
+    transaction: Option<Vec<Change>>,
+    // Nested begin_transaction calls, the outermost end records the step
+    depth: usize,
+    // Input of the last recorded value change and when, for coalescing
+    last_value: Option<(InputId, Instant)>,
+}
+
+impl History {
+    // Changes already applied to the graph. Anything undone can't be redone
+    // after it.
+    pub fn record(&mut self, change: Change, now: Instant) {
+        self.redo.clear();
+
+        let coalesce = match (&change, self.last_value) {
+            (Change::Value { input, .. }, Some((last, time))) => {
+                *input == last && now.duration_since(time) < COALESCE_TIME
+            }
+            _ => false,
+        };
+        self.last_value = match &change {
+            Change::Value { input, .. } => Some((*input, now)),
+            _ => None,
+        };
+
+        // The last change is the one to the same input, it keeps the value
+        // from before the drag started
+        if coalesce {
+            let last = match &mut self.transaction {
+                Some(changes) => changes.last_mut(),
+                None => self.undo.last_mut().and_then(|x| x.last_mut()),
+            };
+            if let (Some(Change::Value { after, .. }), Change::Value { after: value, .. }) =
+                (last, &change)
+            {
+                *after = *value;
+                return;
+            }
+        }
+
+        match &mut self.transaction {
+            Some(changes) => changes.push(change),
+            None => self.push(vec![change]),
+        }
+    }
+
+    fn push(&mut self, step: Vec<Change>) {
+        self.undo.push(step);
+        if self.undo.len() > MAX_STEPS {
+            self.undo.remove(0);
+        }
+    }
+
+    pub fn begin_transaction(&mut self) {
+        if self.depth == 0 {
+            self.transaction = Some(Vec::new());
+        }
+        self.depth += 1;
+    }
+
+    pub fn end_transaction(&mut self) {
+        if self.depth == 0 {
+            return;
+        }
+        self.depth -= 1;
+        if self.depth == 0 {
+            match self.transaction.take() {
+                Some(changes) if !changes.is_empty() => self.push(changes),
+                _ => (),
+            }
+        }
+    }
+
+    // Changes recorded so far in the open transaction
+    pub fn pending(&self) -> usize {
+        self.transaction.as_ref().map_or(0, |x| x.len())
+    }
+
+    // Takes the changes recorded in the open transaction after the first
+    // count, to revert them
+    pub fn take_pending(&mut self, count: usize) -> Vec<Change> {
+        self.last_value = None;
+        match &mut self.transaction {
+            Some(changes) => changes.split_off(count.min(changes.len())),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    // The step to undo, an open transaction ends first
+    pub fn take_undo(&mut self) -> Option<Vec<Change>> {
+        self.close();
+        self.undo.pop()
+    }
+
+    pub fn take_redo(&mut self) -> Option<Vec<Change>> {
+        self.close();
+        self.redo.pop()
+    }
+
+    // A step once undone, its changes swapped back
+    pub fn push_redo(&mut self, step: Vec<Change>) {
+        self.redo.push(step);
+    }
+
+    pub fn push_undo(&mut self, step: Vec<Change>) {
+        self.push(step);
+    }
+
+    fn close(&mut self) {
+        if self.depth > 0 {
+            self.depth = 1;
+            self.end_transaction();
+        }
+        self.last_value = None;
+    }
+}
@@ -5,6 +5,7 @@ use crate::{
 
 pub type InputId = usize;
 
+#[derive(Clone)]
 pub struct InputField {
     pub parent: NodeId,
     pub index: u8,
@@ -1,4 +1,10 @@
+pub mod clock;
+pub mod compound;
+pub mod fragment;
+pub mod frame_io;
 pub mod graph;
+pub mod history;
 pub mod input_field;
 pub mod node;
 pub mod output_pin;
+pub mod snapshot;
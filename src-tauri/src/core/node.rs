@@ -1,5 +1,9 @@
-use serde::Deserialize;
+use std::any::Any;
+use std::sync::Arc;
 
+use serde::{Deserialize, Serialize};
+
+use crate::core::frame_io::FrameIo;
 use crate::core::graph::Graph;
 use crate::dto::input_field_dto::InputFieldDto;
 use crate::dto::node_dto::NodeDto;
@@ -16,6 +20,11 @@ pub struct Node {
     pub ui_state: NodeUIState,
     pub inputs: Vec<usize>,
     pub outputs: Vec<usize>,
+    pub state: Vec<DataValue>,
+    // Free text parameter, e.g. the formula of an Expression node
+    pub text: String,
+    pub cache: NodeCache,
+    pub error: Option<String>,
 }
 
 impl Node {
@@ -24,6 +33,8 @@ impl Node {
             id: self.id,
             kind: self.kind.into(),
             position: self.ui_state.position,
+            text: self.text.clone(),
+            error: self.error.clone(),
             inputs: self
                 .kind
                 .descriptor()
@@ -50,10 +61,52 @@ impl Node {
     }
 }
 
-#[derive(Copy, Clone, Deserialize, strum_macros::IntoStaticStr)]
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize, strum_macros::IntoStaticStr)]
 pub enum NodeKind {
     ConstantColor,
     ConstantNumber,
+    DimmerCurve,
+    DmxInput,
+    DmxOutput,
+    Greater,
+    Less,
+    Equal,
+    InRange,
+    And,
+    Or,
+    Not,
+    Xor,
+    Select,
+    NumberToBoolean,
+    BooleanToNumber,
+    RisingEdge,
+    FallingEdge,
+    Toggle,
+    Counter,
+    OneShot,
+    Debounce,
+    MapRange,
+    Clamp,
+    Smoothstep,
+    Lerp,
+    Ease,
+    Envelope,
+    Slew,
+    Smooth,
+    Expression,
+    OscIn,
+    OscOut,
+    MidiNoteIn,
+    MidiCCIn,
+    MidiCCOut,
+    ExternalInput,
+    Fixture,
+    Group,
+    LookAt,
+    CueList,
+    GroupInput,
+    GroupOutput,
+    Compound,
 }
 
 impl NodeKind {
@@ -61,6 +114,48 @@ impl NodeKind {
         match self {
             NodeKind::ConstantColor => &nodes::constants::color::CONSTANT_COLOR_DESCRIPTOR,
             NodeKind::ConstantNumber => &nodes::constants::number::CONSTANT_NUMBER_DESCRIPTOR,
+            NodeKind::DimmerCurve => &nodes::dmx::dimmer_curve::DIMMER_CURVE_DESCRIPTOR,
+            NodeKind::DmxInput => &nodes::dmx::dmx_input::DMX_INPUT_DESCRIPTOR,
+            NodeKind::DmxOutput => &nodes::dmx::dmx_output::DMX_OUTPUT_DESCRIPTOR,
+            NodeKind::Greater => &nodes::logic::compare::GREATER_DESCRIPTOR,
+            NodeKind::Less => &nodes::logic::compare::LESS_DESCRIPTOR,
+            NodeKind::Equal => &nodes::logic::compare::EQUAL_DESCRIPTOR,
+            NodeKind::InRange => &nodes::logic::compare::IN_RANGE_DESCRIPTOR,
+            NodeKind::And => &nodes::logic::gates::AND_DESCRIPTOR,
+            NodeKind::Or => &nodes::logic::gates::OR_DESCRIPTOR,
+            NodeKind::Not => &nodes::logic::gates::NOT_DESCRIPTOR,
+            NodeKind::Xor => &nodes::logic::gates::XOR_DESCRIPTOR,
+            NodeKind::Select => &nodes::logic::select::SELECT_DESCRIPTOR,
+            NodeKind::NumberToBoolean => &nodes::logic::convert::NUMBER_TO_BOOLEAN_DESCRIPTOR,
+            NodeKind::BooleanToNumber => &nodes::logic::convert::BOOLEAN_TO_NUMBER_DESCRIPTOR,
+            NodeKind::RisingEdge => &nodes::trigger::edge::RISING_EDGE_DESCRIPTOR,
+            NodeKind::FallingEdge => &nodes::trigger::edge::FALLING_EDGE_DESCRIPTOR,
+            NodeKind::Toggle => &nodes::trigger::toggle::TOGGLE_DESCRIPTOR,
+            NodeKind::Counter => &nodes::trigger::counter::COUNTER_DESCRIPTOR,
+            NodeKind::OneShot => &nodes::trigger::timer::ONE_SHOT_DESCRIPTOR,
+            NodeKind::Debounce => &nodes::trigger::timer::DEBOUNCE_DESCRIPTOR,
+            NodeKind::MapRange => &nodes::maths::map_range::MAP_RANGE_DESCRIPTOR,
+            NodeKind::Clamp => &nodes::maths::clamp::CLAMP_DESCRIPTOR,
+            NodeKind::Smoothstep => &nodes::maths::smoothstep::SMOOTHSTEP_DESCRIPTOR,
+            NodeKind::Lerp => &nodes::maths::lerp::LERP_DESCRIPTOR,
+            NodeKind::Ease => &nodes::maths::ease::EASE_DESCRIPTOR,
+            NodeKind::Envelope => &nodes::time::envelope::ENVELOPE_DESCRIPTOR,
+            NodeKind::Slew => &nodes::time::slew::SLEW_DESCRIPTOR,
+            NodeKind::Smooth => &nodes::time::slew::SMOOTH_DESCRIPTOR,
+            NodeKind::Expression => &nodes::maths::expression::EXPRESSION_DESCRIPTOR,
+            NodeKind::OscIn => &nodes::osc::osc_in::OSC_IN_DESCRIPTOR,
+            NodeKind::OscOut => &nodes::osc::osc_out::OSC_OUT_DESCRIPTOR,
+            NodeKind::MidiNoteIn => &nodes::midi::note_in::MIDI_NOTE_IN_DESCRIPTOR,
+            NodeKind::MidiCCIn => &nodes::midi::cc_in::MIDI_CC_IN_DESCRIPTOR,
+            NodeKind::MidiCCOut => &nodes::midi::cc_out::MIDI_CC_OUT_DESCRIPTOR,
+            NodeKind::ExternalInput => &nodes::io::external_input::EXTERNAL_INPUT_DESCRIPTOR,
+            NodeKind::Fixture => &nodes::fixtures::fixture::FIXTURE_DESCRIPTOR,
+            NodeKind::Group => &nodes::fixtures::group::GROUP_DESCRIPTOR,
+            NodeKind::LookAt => &nodes::fixtures::look_at::LOOK_AT_DESCRIPTOR,
+            NodeKind::CueList => &nodes::fixtures::cue_list::CUE_LIST_DESCRIPTOR,
+            NodeKind::GroupInput => &nodes::subgraph::group_io::GROUP_INPUT_DESCRIPTOR,
+            NodeKind::GroupOutput => &nodes::subgraph::group_io::GROUP_OUTPUT_DESCRIPTOR,
+            NodeKind::Compound => &nodes::subgraph::compound::COMPOUND_DESCRIPTOR,
         }
     }
 }
@@ -69,9 +164,34 @@ pub struct NodeDescriptor {
     pub name: &'static str,
     pub inputs: &'static [InputDesc],
     pub outputs: &'static [OutputDesc],
-    pub process: fn(Vec<DataValue>, &mut Vec<&mut DataValue>),
+    pub process: fn(&mut ProcessContext, Vec<DataValue>, &mut [&mut DataValue]),
+    // Initial values of the node's state slots, copied into each new node
+    pub state: &'static [DataValue],
+    // Reads the clock or FrameIo, or writes to FrameIo, so it runs every frame.
+    // Other nodes without state only run when an input changed.
+    pub volatile: bool,
+}
+
+// Everything a node can see besides its inputs and outputs
+pub struct ProcessContext<'a> {
+    // Seconds since the graph clock started
+    pub time: f64,
+    // Seconds since the previous frame
+    pub delta: f64,
+    // Per node memory persisted between frames, see NodeDescriptor::state
+    pub state: &'a mut Vec<DataValue>,
+    pub text: &'a str,
+    // Cleared whenever the text changes
+    pub cache: &'a mut NodeCache,
+    // Shown on the node in the UI, None when it is working
+    pub error: &'a mut Option<String>,
+    // External inputs to read and outputs to queue, see FrameIo
+    pub io: &'a mut FrameIo,
 }
 
+// Anything a node derives from its text parameter, e.g. a parsed expression
+pub type NodeCache = Option<Arc<dyn Any + Send + Sync>>;
+
 pub struct InputDesc {
     pub id: usize,
     pub name: &'static str,
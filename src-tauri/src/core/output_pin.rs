@@ -5,6 +5,7 @@ use crate::{
 
 pub type OutputId = usize;
 
+#[derive(Clone)]
 pub struct OutputPin {
     pub parent: NodeId,
     pub index: u8,
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core::graph::GraphId;
+use crate::core::input_field::InputId;
+use crate::types::data_type::DataValue;
+
+pub type SnapshotId = u32;
+
+// Values of the input fields of a graph that weren't connected when it was
+// taken, "all the knobs as they are now"
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub id: SnapshotId,
+    pub name: String,
+    pub graph: GraphId,
+    pub values: Vec<SnapshotValue>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotValue {
+    pub input: InputId,
+    pub value: DataValue,
+}
+
+// A recall in progress, advanced by Graph::tick
+pub struct SnapshotFade {
+    // Input, value when recalled, value recalled
+    pub values: Vec<(InputId, DataValue, DataValue)>,
+    pub duration: f64,
+    pub elapsed: f64,
+}
+
+impl SnapshotFade {
+    // Values for the time elapsed
+    pub fn values(&self) -> impl Iterator<Item = (InputId, DataValue)> + '_ {
+        let t = if self.duration <= 0.0 {
+            1.0
+        } else {
+            self.elapsed / self.duration
+        };
+        self.values
+            .iter()
+            .map(move |(input, from, to)| (*input, from.interpolate(to, t)))
+    }
+
+    pub fn done(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+}
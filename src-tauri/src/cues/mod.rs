@@ -0,0 +1,218 @@
+pub mod playback;
+
+use serde::{Deserialize, Serialize};
+
+use crate::fixtures::patch::FixtureId;
+use crate::fixtures::profile::Attribute;
+
+pub type CueListId = u32;
+
+// Level of one attribute of one fixture, 0..1
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CueValue {
+    pub fixture: FixtureId,
+    pub attribute: Attribute,
+    pub value: f64,
+}
+
+// Times in seconds. Levels going up take fade_in, going down fade_out.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Cue {
+    pub number: f64,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub values: Vec<CueValue>,
+    #[serde(default)]
+    pub fade_in: f64,
+    #[serde(default)]
+    pub fade_out: f64,
+    // Before the fade starts
+    #[serde(default)]
+    pub delay: f64,
+    // GO the next cue this many seconds after this one finished fading
+    #[serde(default)]
+    pub follow: Option<f64>,
+}
+
+impl Cue {
+    pub fn new(number: f64, values: Vec<CueValue>) -> Cue {
+        Cue {
+            number,
+            name: String::new(),
+            values,
+            fade_in: 0.0,
+            fade_out: 0.0,
+            delay: 0.0,
+            follow: None,
+        }
+    }
+}
+
+// Cues in number order
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CueList {
+    pub id: CueListId,
+    pub name: String,
+    #[serde(default)]
+    pub cues: Vec<Cue>,
+}
+
+impl CueList {
+    pub fn new(id: CueListId, name: &str) -> CueList {
+        CueList {
+            id,
+            name: name.to_string(),
+            cues: Vec::new(),
+        }
+    }
+
+    // Replaces the cue with the same number
+    pub fn set_cue(&mut self, cue: Cue) {
+        self.remove_cue(cue.number);
+        let index = self.cues.partition_point(|x| x.number < cue.number);
+        self.cues.insert(index, cue);
+    }
+
+    pub fn remove_cue(&mut self, number: f64) -> bool {
+        let count = self.cues.len();
+        self.cues.retain(|x| x.number != number);
+        self.cues.len() != count
+    }
+
+    pub fn index_of(&self, number: f64) -> Option<usize> {
+        self.cues.iter().position(|x| x.number == number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::playback::Playback;
+    use super::*;
+
+    // Dimmer of fixture 0 and pan of fixture 1
+    fn cue(number: f64, dimmer: f64, pan: f64) -> Cue {
+        Cue::new(
+            number,
+            vec![
+                CueValue {
+                    fixture: 0,
+                    attribute: Attribute::Intensity,
+                    value: dimmer,
+                },
+                CueValue {
+                    fixture: 1,
+                    attribute: Attribute::Pan,
+                    value: pan,
+                },
+            ],
+        )
+    }
+
+    fn level(playback: &Playback, fixture: FixtureId, attribute: Attribute) -> Option<f64> {
+        let level = playback.output().levels.get(&(fixture, attribute)).copied();
+        // Rounded so fade steps compare exactly
+        level.map(|x| (x * 1000.0).round() / 1000.0)
+    }
+
+    #[test]
+    fn crossfade_splits_up_and_down() {
+        let mut list = CueList::new(0, "Main");
+        list.set_cue(cue(2.0, 0.2, 1.0));
+        list.set_cue(cue(1.0, 1.0, 0.0));
+        let mut third = Cue::new(
+            3.0,
+            vec![CueValue {
+                fixture: 2,
+                attribute: Attribute::Intensity,
+                value: 1.0,
+            }],
+        );
+        third.fade_in = 1.0;
+        third.fade_out = 4.0;
+        third.delay = 0.5;
+        list.set_cue(third);
+        list.cues[1].fade_in = 2.0;
+        list.cues[1].fade_out = 4.0;
+        assert_eq!(
+            vec![1.0, 2.0, 3.0],
+            list.cues.iter().map(|x| x.number).collect::<Vec<_>>()
+        );
+
+        let mut playback = Playback::new();
+        assert!(playback.go(&list));
+        assert_eq!(Some(1.0), level(&playback, 0, Attribute::Intensity));
+        assert!(!playback.output().fading);
+
+        // Dimmer going down takes fade_out, pan going up fade_in
+        assert!(playback.go(&list));
+        playback.tick(&list, 1.0);
+        assert_eq!(Some(0.8), level(&playback, 0, Attribute::Intensity));
+        assert_eq!(Some(0.5), level(&playback, 1, Attribute::Pan));
+        playback.tick(&list, 1.0);
+        assert_eq!(Some(0.6), level(&playback, 0, Attribute::Intensity));
+        assert_eq!(Some(1.0), level(&playback, 1, Attribute::Pan));
+        playback.tick(&list, 2.0);
+        assert_eq!(Some(0.2), level(&playback, 0, Attribute::Intensity));
+        assert!(!playback.output().fading);
+
+        // Nothing moves during the delay. Intensities missing from the cue
+        // fade out, the pan holds.
+        assert!(playback.go(&list));
+        playback.tick(&list, 0.5);
+        assert_eq!(Some(0.2), level(&playback, 0, Attribute::Intensity));
+        assert_eq!(Some(0.0), level(&playback, 2, Attribute::Intensity));
+        playback.tick(&list, 0.5);
+        assert_eq!(Some(0.175), level(&playback, 0, Attribute::Intensity));
+        assert_eq!(Some(0.5), level(&playback, 2, Attribute::Intensity));
+        playback.tick(&list, 4.0);
+        assert_eq!(None, level(&playback, 0, Attribute::Intensity));
+        assert_eq!(Some(1.0), level(&playback, 2, Attribute::Intensity));
+        assert_eq!(Some(1.0), level(&playback, 1, Attribute::Pan));
+        assert!(!playback.go(&list));
+    }
+
+    #[test]
+    fn follow_back_goto_and_release() {
+        let mut list = CueList::new(0, "Chase");
+        for number in [1.0, 2.0, 3.0] {
+            let mut cue = cue(number, number / 4.0, 0.0);
+            cue.follow = Some(1.0);
+            cue.fade_out = 2.0;
+            list.set_cue(cue);
+        }
+
+        let mut playback = Playback::new();
+        assert!(!playback.back(&list));
+        playback.go(&list);
+        playback.tick(&list, 0.5);
+        assert_eq!(Some(1.0), playback.cue());
+        playback.tick(&list, 0.5);
+        assert_eq!(Some(2.0), playback.cue());
+        assert_eq!(Some(0.5), level(&playback, 0, Attribute::Intensity));
+
+        assert!(playback.back(&list));
+        assert_eq!(Some(1.0), playback.cue());
+        assert!(playback.output().fading);
+        playback.tick(&list, 2.0);
+        assert_eq!(Some(0.25), level(&playback, 0, Attribute::Intensity));
+
+        assert!(!playback.goto(&list, 5.0));
+        assert!(playback.goto(&list, 3.0));
+        assert_eq!(Some(0.75), level(&playback, 0, Attribute::Intensity));
+        // Follows stop at the end of the list
+        playback.tick(&list, 5.0);
+        assert_eq!(Some(3.0), playback.cue());
+
+        // In the current cue's fade out, then nothing is left
+        playback.release(&list);
+        assert_eq!(None, playback.cue());
+        playback.tick(&list, 1.0);
+        assert_eq!(Some(0.375), level(&playback, 0, Attribute::Intensity));
+        assert_eq!(Some(0.0), level(&playback, 1, Attribute::Pan));
+        playback.tick(&list, 1.0);
+        assert!(playback.output().levels.is_empty());
+        playback.tick(&list, 5.0);
+        assert_eq!(None, playback.cue());
+    }
+}
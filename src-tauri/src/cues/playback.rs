@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+
+use crate::cues::{Cue, CueList, CueListId};
+use crate::fixtures::patch::FixtureId;
+use crate::fixtures::profile::Attribute;
+
+pub type CueKey = (FixtureId, Attribute);
+
+// What a cue list outputs this frame, read by CueList nodes
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CueOutput {
+    // Number of the cue last gone to, None when released
+    pub cue: Option<f64>,
+    pub fading: bool,
+    pub levels: HashMap<CueKey, f64>,
+}
+
+pub type CueOutputs = HashMap<CueListId, CueOutput>;
+
+struct Fade {
+    from: HashMap<CueKey, f64>,
+    // Levels at the end, keys missing from it are dropped at the end
+    to: HashMap<CueKey, f64>,
+    up: f64,
+    down: f64,
+    delay: f64,
+    elapsed: f64,
+}
+
+impl Fade {
+    // Start, end and how long it takes
+    fn span(&self, key: &CueKey) -> (f64, f64, f64) {
+        // Missing intensities come from and go to 0, anything else snaps
+        let (start, end) = match (self.from.get(key).copied(), self.to.get(key).copied()) {
+            (Some(start), Some(end)) => (start, end),
+            (None, Some(end)) if key.1 == Attribute::Intensity => (0.0, end),
+            (None, Some(end)) => (end, end),
+            (Some(start), None) if key.1 == Attribute::Intensity => (start, 0.0),
+            (Some(start), None) => (start, start),
+            (None, None) => (0.0, 0.0),
+        };
+
+        let duration = if end > start {
+            self.up
+        } else if end < start {
+            self.down
+        } else {
+            0.0
+        };
+        (start, end, duration)
+    }
+
+    fn levels(&self) -> HashMap<CueKey, f64> {
+        let time = self.elapsed - self.delay;
+        let mut levels = HashMap::new();
+        for key in self.from.keys().chain(self.to.keys()) {
+            let (start, end, duration) = self.span(key);
+            let progress = if time < 0.0 {
+                0.0
+            } else if duration <= 0.0 {
+                1.0
+            } else {
+                (time / duration).min(1.0)
+            };
+            levels.insert(*key, start + (end - start) * progress);
+        }
+        levels
+    }
+
+    // Once every level has arrived
+    fn done(&self) -> bool {
+        let time = self.elapsed - self.delay;
+        time >= 0.0
+            && self
+                .from
+                .keys()
+                .chain(self.to.keys())
+                .all(|key| time >= self.span(key).2)
+    }
+}
+
+// Plays a cue list: GO, BACK, GOTO and RELEASE start crossfades from the
+// current levels, tick advances them. Intensities missing from a cue fade
+// out, other attributes keep their last level until released.
+#[derive(Default)]
+pub struct Playback {
+    cue: Option<f64>,
+    levels: HashMap<CueKey, f64>,
+    fade: Option<Fade>,
+    // Seconds since the current cue finished fading, for its follow
+    since_done: f64,
+}
+
+impl Playback {
+    pub fn new() -> Playback {
+        Playback::default()
+    }
+
+    pub fn cue(&self) -> Option<f64> {
+        self.cue
+    }
+
+    // The cue after the current one, false at the end of the list
+    pub fn go(&mut self, list: &CueList) -> bool {
+        let next = match self.cue {
+            Some(current) => list.cues.iter().find(|x| x.number > current),
+            None => list.cues.first(),
+        };
+        match next {
+            Some(cue) => {
+                self.start(cue);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // The cue before the current one, in its times
+    pub fn back(&mut self, list: &CueList) -> bool {
+        let previous = self
+            .cue
+            .and_then(|current| list.cues.iter().rev().find(|x| x.number < current));
+        match previous {
+            Some(cue) => {
+                self.start(cue);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn goto(&mut self, list: &CueList, number: f64) -> bool {
+        match list.index_of(number) {
+            Some(index) => {
+                self.start(&list.cues[index]);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Intensities fade out in the current cue's fade_out, then nothing is output
+    pub fn release(&mut self, list: &CueList) {
+        let down = self
+            .cue
+            .and_then(|x| list.index_of(x))
+            .map_or(0.0, |x| list.cues[x].fade_out);
+
+        self.cue = None;
+        self.fade = Some(Fade {
+            from: self.levels.clone(),
+            to: HashMap::new(),
+            up: 0.0,
+            down,
+            delay: 0.0,
+            elapsed: 0.0,
+        });
+        self.since_done = 0.0;
+        self.tick(list, 0.0);
+    }
+
+    fn start(&mut self, cue: &Cue) {
+        let mut to: HashMap<CueKey, f64> = self
+            .levels
+            .iter()
+            .filter(|(key, _)| key.1 != Attribute::Intensity)
+            .map(|(key, level)| (*key, *level))
+            .collect();
+        for value in &cue.values {
+            to.insert((value.fixture, value.attribute), value.value);
+        }
+
+        self.cue = Some(cue.number);
+        self.fade = Some(Fade {
+            from: self.levels.clone(),
+            to,
+            up: cue.fade_in,
+            down: cue.fade_out,
+            delay: cue.delay,
+            elapsed: 0.0,
+        });
+        self.since_done = 0.0;
+        self.update();
+    }
+
+    // Advances the fade by delta seconds, then any follow
+    pub fn tick(&mut self, list: &CueList, delta: f64) {
+        match &mut self.fade {
+            Some(fade) => {
+                fade.elapsed += delta;
+                self.update();
+            }
+            None => {
+                self.since_done += delta;
+                let follow = self
+                    .cue
+                    .and_then(|x| list.index_of(x))
+                    .and_then(|x| list.cues[x].follow);
+                if follow.is_some_and(|x| self.since_done >= x) {
+                    self.go(list);
+                }
+            }
+        }
+    }
+
+    fn update(&mut self) {
+        let Some(fade) = &self.fade else {
+            return;
+        };
+
+        if fade.done() {
+            self.levels = fade.to.clone();
+            self.fade = None;
+        } else {
+            self.levels = fade.levels();
+        }
+    }
+
+    pub fn output(&self) -> CueOutput {
+        CueOutput {
+            cue: self.cue,
+            fading: self.fade.is_some(),
+            levels: self.levels.clone(),
+        }
+    }
+}
@@ -0,0 +1,281 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::graph::GraphId;
+use crate::dmx::{DmxOutput, PortAddress, UNIVERSE_SIZE};
+use crate::dto::channel_info_dto::{ChannelInfoDto, ChannelSourceDto};
+
+pub const DEFAULT_PRIORITY: u8 = 100;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum MergePolicy {
+    // Highest level
+    #[default]
+    Htp,
+    // The source that changed the channel last
+    Ltp,
+    // The source with the highest priority, HTP between equals
+    Priority,
+    // Sum of every source, clipped at full
+    Additive,
+}
+
+// Something writing into output universes
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(tag = "type", content = "id")]
+pub enum MergeSource {
+    Graph(GraphId),
+    // A received Art-Net universe, see UniverseMerge::input
+    Input(PortAddress),
+}
+
+// Channels start..=end (from 1) merged differently from the rest of the universe
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChannelRange {
+    pub start: u16,
+    pub end: u16,
+    pub policy: MergePolicy,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SourcePriority {
+    pub source: MergeSource,
+    pub priority: u8,
+}
+
+// How the sources of one output universe are merged. Universes without one
+// are HTP and every source has DEFAULT_PRIORITY.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct UniverseMerge {
+    pub universe: PortAddress,
+    pub policy: MergePolicy,
+    // Later ranges override earlier ones
+    #[serde(default)]
+    pub ranges: Vec<ChannelRange>,
+    #[serde(default)]
+    pub priorities: Vec<SourcePriority>,
+    // Received universe merged in as one more source
+    #[serde(default)]
+    pub input: Option<PortAddress>,
+}
+
+impl UniverseMerge {
+    pub fn new(universe: PortAddress, policy: MergePolicy) -> UniverseMerge {
+        UniverseMerge {
+            universe,
+            policy,
+            ranges: Vec::new(),
+            priorities: Vec::new(),
+            input: None,
+        }
+    }
+
+    // Channel index from 0
+    pub fn policy_at(&self, index: usize) -> MergePolicy {
+        let channel = index + 1;
+        self.ranges
+            .iter()
+            .rev()
+            .find(|x| (x.start as usize..=x.end as usize).contains(&channel))
+            .map_or(self.policy, |x| x.policy)
+    }
+
+    pub fn priority_of(&self, source: MergeSource) -> u8 {
+        self.priorities
+            .iter()
+            .find(|x| x.source == source)
+            .map_or(DEFAULT_PRIORITY, |x| x.priority)
+    }
+}
+
+// What a source wrote into a universe in the last frame
+struct SourceLevels {
+    levels: [Option<u8>; UNIVERSE_SIZE],
+    // Frame each channel last changed in, for LTP
+    changed: [u64; UNIVERSE_SIZE],
+}
+
+struct Writer {
+    source: MergeSource,
+    level: u8,
+    priority: u8,
+    changed: u64,
+}
+
+// Merges the levels of every source into output universes. Keeps what every
+// source wrote so LTP can tell who changed a channel last, and so the owner
+// of any channel can be looked up afterwards.
+#[derive(Default)]
+pub struct Merger {
+    configs: Vec<UniverseMerge>,
+    sources: HashMap<PortAddress, HashMap<MergeSource, SourceLevels>>,
+    universes: HashMap<PortAddress, [u8; UNIVERSE_SIZE]>,
+    frame: u64,
+}
+
+impl Merger {
+    pub fn new() -> Merger {
+        Merger::default()
+    }
+
+    pub fn configs(&self) -> &[UniverseMerge] {
+        &self.configs
+    }
+
+    pub fn config(&self, universe: PortAddress) -> UniverseMerge {
+        self.configs
+            .iter()
+            .find(|x| x.universe == universe)
+            .cloned()
+            .unwrap_or(UniverseMerge::new(universe, MergePolicy::Htp))
+    }
+
+    // Replaces the configuration of the same universe
+    pub fn set_config(&mut self, config: UniverseMerge) {
+        self.remove_config(config.universe);
+        self.configs.push(config);
+    }
+
+    pub fn remove_config(&mut self, universe: PortAddress) {
+        self.configs.retain(|x| x.universe != universe);
+    }
+
+    pub fn set_configs(&mut self, configs: Vec<UniverseMerge>) {
+        self.configs.clear();
+        for config in configs {
+            self.set_config(config);
+        }
+    }
+
+    // Merges one frame. A source missing from writes stopped writing.
+    pub fn merge(&mut self, writes: Vec<(MergeSource, PortAddress, DmxOutput)>) {
+        self.frame += 1;
+
+        let mut sources: HashMap<PortAddress, HashMap<MergeSource, SourceLevels>> = HashMap::new();
+        for (source, universe, output) in writes {
+            let previous = self
+                .sources
+                .get_mut(&universe)
+                .and_then(|x| x.remove(&source));
+
+            let changed = match previous {
+                Some(previous) => {
+                    let mut changed = previous.changed;
+                    for (i, level) in output.levels.iter().enumerate() {
+                        if *level != previous.levels[i] {
+                            changed[i] = self.frame;
+                        }
+                    }
+                    changed
+                }
+                None => [self.frame; UNIVERSE_SIZE],
+            };
+
+            sources.entry(universe).or_default().insert(
+                source,
+                SourceLevels {
+                    levels: output.levels,
+                    changed,
+                },
+            );
+        }
+        self.sources = sources;
+
+        let mut universes = HashMap::new();
+        for universe in self.sources.keys() {
+            let config = self.config(*universe);
+            let sources = self.sorted_sources(*universe);
+
+            let mut data = [0; UNIVERSE_SIZE];
+            for (i, level) in data.iter_mut().enumerate() {
+                *level = resolve(&config, &writers(&config, &sources, i), i).0;
+            }
+            universes.insert(*universe, data);
+        }
+        self.universes = universes;
+    }
+
+    pub fn universe(&self, universe: PortAddress) -> Option<&[u8; UNIVERSE_SIZE]> {
+        self.universes.get(&universe)
+    }
+
+    // Why a channel (from 1) has its level: every source writing it and which won
+    pub fn inspect(&self, universe: PortAddress, channel: u16) -> ChannelInfoDto {
+        let config = self.config(universe);
+        let index = (channel.clamp(1, UNIVERSE_SIZE as u16) - 1) as usize;
+        let writers = writers(&config, &self.sorted_sources(universe), index);
+        let (level, owners) = resolve(&config, &writers, index);
+
+        let sources = writers
+            .iter()
+            .map(|x| ChannelSourceDto {
+                source: x.source,
+                level: x.level,
+                priority: x.priority,
+                owner: owners.contains(&x.source),
+            })
+            .collect();
+
+        ChannelInfoDto {
+            universe,
+            channel: index as u16 + 1,
+            level,
+            policy: config.policy_at(index),
+            sources,
+        }
+    }
+
+    // Sorted so ties go the same way every frame
+    fn sorted_sources(&self, universe: PortAddress) -> Vec<(MergeSource, &SourceLevels)> {
+        let mut sources: Vec<(MergeSource, &SourceLevels)> = self
+            .sources
+            .get(&universe)
+            .map(|x| x.iter().map(|(source, levels)| (*source, levels)).collect())
+            .unwrap_or_default();
+        sources.sort_by_key(|x| x.0);
+        sources
+    }
+}
+
+fn writers(
+    config: &UniverseMerge,
+    sources: &[(MergeSource, &SourceLevels)],
+    index: usize,
+) -> Vec<Writer> {
+    sources
+        .iter()
+        .filter_map(|(source, levels)| {
+            levels.levels[index].map(|level| Writer {
+                source: *source,
+                level,
+                priority: config.priority_of(*source),
+                changed: levels.changed[index],
+            })
+        })
+        .collect()
+}
+
+// Level of a channel and the sources that decided it
+fn resolve(config: &UniverseMerge, writers: &[Writer], index: usize) -> (u8, Vec<MergeSource>) {
+    let winner = match config.policy_at(index) {
+        MergePolicy::Htp => writers.iter().max_by_key(|x| x.level),
+        // Equal frames, e.g. two sources appearing together, fall back to HTP
+        MergePolicy::Ltp => writers.iter().max_by_key(|x| (x.changed, x.level)),
+        MergePolicy::Priority => writers.iter().max_by_key(|x| (x.priority, x.level)),
+        MergePolicy::Additive => {
+            let sum: u32 = writers.iter().map(|x| x.level as u32).sum();
+            let owners = writers
+                .iter()
+                .filter(|x| x.level > 0)
+                .map(|x| x.source)
+                .collect();
+            return (sum.min(255) as u8, owners);
+        }
+    };
+
+    match winner {
+        Some(x) => (x.level, vec![x.source]),
+        None => (0, Vec::new()),
+    }
+}
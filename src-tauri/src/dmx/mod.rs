@@ -0,0 +1,248 @@
+pub mod merge;
+
+use std::collections::HashMap;
+
+pub const UNIVERSE_SIZE: usize = 512;
+
+// 15 bit Art-Net style address: net (7 bits), subnet (4 bits), universe (4 bits)
+pub type PortAddress = u16;
+
+pub fn port_address(net: u8, subnet: u8, universe: u8) -> PortAddress {
+    ((net as u16 & 0x7F) << 8) | ((subnet as u16 & 0x0F) << 4) | (universe as u16 & 0x0F)
+}
+
+// (net, subnet, universe)
+pub fn split_port_address(address: PortAddress) -> (u8, u8, u8) {
+    (
+        ((address >> 8) & 0x7F) as u8,
+        ((address >> 4) & 0x0F) as u8,
+        (address & 0x0F) as u8,
+    )
+}
+
+// Latest levels received for a universe
+#[derive(Clone, Debug, PartialEq)]
+pub struct DmxInput {
+    pub data: [u8; UNIVERSE_SIZE],
+    // Nothing has been received for longer than the timeout
+    pub stale: bool,
+}
+
+pub type DmxInputs = HashMap<PortAddress, DmxInput>;
+
+// Levels written by DmxOutput nodes during a frame, None where nothing wrote
+#[derive(Clone, Debug, PartialEq)]
+pub struct DmxOutput {
+    pub levels: [Option<u8>; UNIVERSE_SIZE],
+}
+
+impl DmxOutput {
+    pub fn new() -> DmxOutput {
+        DmxOutput {
+            levels: [None; UNIVERSE_SIZE],
+        }
+    }
+
+    // Channel index from 0, out of range channels are ignored
+    pub fn set(&mut self, index: usize, level: u8) {
+        if let Some(x) = self.levels.get_mut(index) {
+            *x = Some(level);
+        }
+    }
+}
+
+impl Default for DmxOutput {
+    fn default() -> Self {
+        DmxOutput::new()
+    }
+}
+
+pub type DmxOutputs = HashMap<PortAddress, DmxOutput>;
+
+#[cfg(test)]
+mod tests {
+    use super::merge::*;
+    use super::*;
+
+    use MergeSource::{Graph, Input};
+
+    fn output(levels: &[(usize, u8)]) -> DmxOutput {
+        let mut output = DmxOutput::new();
+        for (channel, level) in levels {
+            output.set(channel - 1, *level);
+        }
+        output
+    }
+
+    fn merger(policy: MergePolicy) -> Merger {
+        let mut merger = Merger::new();
+        merger.set_config(UniverseMerge::new(1, policy));
+        merger
+    }
+
+    fn levels(merger: &Merger) -> [u8; 3] {
+        let data = merger.universe(1).unwrap();
+        [data[0], data[1], data[2]]
+    }
+
+    #[test]
+    fn htp_takes_highest() {
+        let mut merger = merger(MergePolicy::Htp);
+        merger.merge(vec![
+            (Graph(0), 1, output(&[(1, 200), (2, 10)])),
+            (Graph(1), 1, output(&[(1, 100), (2, 50), (3, 5)])),
+        ]);
+        assert_eq!([200, 50, 5], levels(&merger));
+
+        // Unconfigured universes are HTP too
+        merger.merge(vec![
+            (Graph(0), 2, output(&[(1, 30)])),
+            (Graph(1), 2, output(&[(1, 60)])),
+        ]);
+        assert_eq!(60, merger.universe(2).unwrap()[0]);
+        assert!(merger.universe(1).is_none());
+    }
+
+    #[test]
+    fn ltp_takes_latest_change() {
+        let mut merger = merger(MergePolicy::Ltp);
+
+        // Appearing together, the higher one wins
+        merger.merge(vec![
+            (Graph(0), 1, output(&[(1, 200), (2, 10)])),
+            (Graph(1), 1, output(&[(1, 100), (2, 50)])),
+        ]);
+        assert_eq!([200, 50, 0], levels(&merger));
+
+        // Graph 1 moves channel 1 down, it still takes over
+        merger.merge(vec![
+            (Graph(0), 1, output(&[(1, 200), (2, 10)])),
+            (Graph(1), 1, output(&[(1, 90), (2, 50)])),
+        ]);
+        assert_eq!([90, 50, 0], levels(&merger));
+
+        // Holding a value keeps it, until the other source changes
+        merger.merge(vec![
+            (Graph(0), 1, output(&[(1, 200), (2, 10)])),
+            (Graph(1), 1, output(&[(1, 90), (2, 50)])),
+        ]);
+        assert_eq!([90, 50, 0], levels(&merger));
+        merger.merge(vec![
+            (Graph(0), 1, output(&[(1, 200), (2, 11)])),
+            (Graph(1), 1, output(&[(1, 90), (2, 50)])),
+        ]);
+        assert_eq!([90, 11, 0], levels(&merger));
+
+        // A source that stops writing gives the channel back
+        merger.merge(vec![(Graph(0), 1, output(&[(1, 200), (2, 11)]))]);
+        assert_eq!([200, 11, 0], levels(&merger));
+    }
+
+    #[test]
+    fn priority_takes_highest_priority() {
+        let mut config = UniverseMerge::new(1, MergePolicy::Priority);
+        config.priorities = vec![SourcePriority {
+            source: Graph(1),
+            priority: 150,
+        }];
+        let mut merger = Merger::new();
+        merger.set_config(config);
+
+        merger.merge(vec![
+            (Graph(0), 1, output(&[(1, 255), (2, 255), (3, 20)])),
+            (Graph(1), 1, output(&[(1, 0), (2, 100)])),
+            (Graph(2), 1, output(&[(3, 40)])),
+        ]);
+        // Graph 1 wins where it writes, even with 0. HTP between equals.
+        assert_eq!([0, 100, 40], levels(&merger));
+    }
+
+    #[test]
+    fn additive_sums_and_clips() {
+        let mut merger = merger(MergePolicy::Additive);
+        merger.merge(vec![
+            (Graph(0), 1, output(&[(1, 200), (2, 10), (3, 0)])),
+            (Graph(1), 1, output(&[(1, 100), (2, 50)])),
+        ]);
+        assert_eq!([255, 60, 0], levels(&merger));
+    }
+
+    #[test]
+    fn ranges_override_the_universe_policy() {
+        let mut config = UniverseMerge::new(1, MergePolicy::Htp);
+        config.ranges = vec![
+            ChannelRange {
+                start: 2,
+                end: 3,
+                policy: MergePolicy::Additive,
+            },
+            // Later ranges win
+            ChannelRange {
+                start: 3,
+                end: 3,
+                policy: MergePolicy::Ltp,
+            },
+        ];
+        assert_eq!(MergePolicy::Htp, config.policy_at(0));
+        assert_eq!(MergePolicy::Additive, config.policy_at(1));
+        assert_eq!(MergePolicy::Ltp, config.policy_at(2));
+        assert_eq!(MergePolicy::Htp, config.policy_at(3));
+
+        let mut merger = Merger::new();
+        merger.set_config(config);
+        merger.merge(vec![
+            (Graph(0), 1, output(&[(1, 10), (2, 10), (3, 10)])),
+            (Graph(1), 1, output(&[(1, 20), (2, 20), (3, 20)])),
+        ]);
+        merger.merge(vec![
+            (Graph(0), 1, output(&[(1, 10), (2, 10), (3, 5)])),
+            (Graph(1), 1, output(&[(1, 20), (2, 20), (3, 20)])),
+        ]);
+        assert_eq!([20, 30, 5], levels(&merger));
+    }
+
+    #[test]
+    fn inspect_channel_owners() {
+        let mut config = UniverseMerge::new(1, MergePolicy::Htp);
+        config.ranges = vec![ChannelRange {
+            start: 2,
+            end: 2,
+            policy: MergePolicy::Additive,
+        }];
+        let mut merger = Merger::new();
+        merger.set_config(config);
+        merger.merge(vec![
+            (Input(7), 1, output(&[(1, 100), (2, 0)])),
+            (Graph(0), 1, output(&[(1, 200), (2, 10)])),
+            (Graph(1), 1, output(&[(2, 50)])),
+        ]);
+
+        let info = merger.inspect(1, 1);
+        assert_eq!(
+            (1, 200, MergePolicy::Htp),
+            (info.channel, info.level, info.policy)
+        );
+        let sources: Vec<(MergeSource, u8, bool)> = info
+            .sources
+            .iter()
+            .map(|x| (x.source, x.level, x.owner))
+            .collect();
+        assert_eq!(vec![(Graph(0), 200, true), (Input(7), 100, false)], sources);
+
+        // Everything adding to the level owns it
+        let info = merger.inspect(1, 2);
+        assert_eq!(60, info.level);
+        let owners: Vec<MergeSource> = info
+            .sources
+            .iter()
+            .filter(|x| x.owner)
+            .map(|x| x.source)
+            .collect();
+        assert_eq!(vec![Graph(0), Graph(1)], owners);
+
+        // Nobody writes channel 3
+        let info = merger.inspect(1, 3);
+        assert_eq!(0, info.level);
+        assert!(info.sources.is_empty());
+    }
+}
@@ -0,0 +1,15 @@
+use serde::Serialize;
+
+use crate::dmx::PortAddress;
+
+#[derive(Serialize, Clone)]
+pub struct ArtnetNodeDto {
+    pub ip: String,
+    pub short_name: String,
+    pub long_name: String,
+    pub mac: String,
+    pub inputs: Vec<PortAddress>,
+    pub outputs: Vec<PortAddress>,
+    // Milliseconds since the last ArtPollReply
+    pub last_seen: u64,
+}
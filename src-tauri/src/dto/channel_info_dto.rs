@@ -0,0 +1,22 @@
+use serde::Serialize;
+
+use crate::dmx::merge::{MergePolicy, MergeSource};
+use crate::dmx::PortAddress;
+
+#[derive(Serialize)]
+pub struct ChannelSourceDto {
+    pub source: MergeSource,
+    pub level: u8,
+    pub priority: u8,
+    // Decided the output level
+    pub owner: bool,
+}
+
+#[derive(Serialize)]
+pub struct ChannelInfoDto {
+    pub universe: PortAddress,
+    pub channel: u16,
+    pub level: u8,
+    pub policy: MergePolicy,
+    pub sources: Vec<ChannelSourceDto>,
+}
@@ -0,0 +1,26 @@
+use serde::Serialize;
+
+use crate::core::compound::{CompoundDef, CompoundId};
+
+#[derive(Serialize)]
+pub struct CompoundDto {
+    pub id: CompoundId,
+    pub name: String,
+    // Names of the pins of the Compound nodes using it, in order
+    pub inputs: Vec<String>,
+    pub outputs: Vec<String>,
+    // The graph as clipboard JSON, pasted into a graph to edit it
+    pub fragment: String,
+}
+
+impl From<&CompoundDef> for CompoundDto {
+    fn from(def: &CompoundDef) -> CompoundDto {
+        CompoundDto {
+            id: def.id,
+            name: def.name.clone(),
+            inputs: def.inputs(),
+            outputs: def.outputs(),
+            fragment: def.fragment.to_json().unwrap_or_default(),
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use serde::Serialize;
+
+use crate::cues::{Cue, CueListId};
+
+#[derive(Serialize)]
+pub struct CueListDto {
+    pub id: CueListId,
+    pub name: String,
+    pub cues: Vec<Cue>,
+    // Number of the cue last gone to, None when released
+    pub current: Option<f64>,
+    pub fading: bool,
+}
@@ -6,6 +6,7 @@ use serde::Serialize;
 pub enum DataValueDto {
     Number(f64),
     Boolean(bool),
+    Trigger(bool),
     Color(color::Color),
     Vector3(vector::Vec3),
     Point3(vector::Point3),
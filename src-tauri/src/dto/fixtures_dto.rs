@@ -0,0 +1,23 @@
+use serde::Serialize;
+
+use crate::fixtures::group::FixtureGroup;
+use crate::fixtures::patch::{FixtureId, PatchedFixture};
+
+#[derive(Serialize)]
+pub struct PatchDto {
+    pub fixtures: Vec<PatchedFixture>,
+    pub groups: Vec<FixtureGroup>,
+    // Pairs of fixtures sharing channels
+    pub overlaps: Vec<(FixtureId, FixtureId)>,
+    // Fixtures whose profile or mode is missing, nothing is output for them
+    pub unresolved: Vec<FixtureId>,
+    // Fixtures that don't fit in their universe, not output either
+    pub out_of_range: Vec<FixtureId>,
+}
+
+#[derive(Serialize)]
+pub struct FixtureImportDto {
+    // FixtureProfile::key of the imported profile
+    pub profile: String,
+    pub warnings: Vec<String>,
+}
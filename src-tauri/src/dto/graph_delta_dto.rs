@@ -0,0 +1,19 @@
+use serde::Serialize;
+
+use crate::core::graph::GraphId;
+use crate::core::node::NodeId;
+
+use super::connection_dto::ConnectionDto;
+use super::node_dto::NodeDto;
+
+// What an edit, undo or redo changed, see Graph::delta
+#[derive(Serialize)]
+pub struct GraphDeltaDto {
+    pub id: GraphId,
+    // Added or changed, replacing the node with the same id
+    pub nodes: Vec<NodeDto>,
+    // Ids no longer in the graph
+    pub removed: Vec<NodeId>,
+    pub connected: Vec<ConnectionDto>,
+    pub disconnected: Vec<ConnectionDto>,
+}
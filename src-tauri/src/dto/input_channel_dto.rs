@@ -0,0 +1,11 @@
+use serde::Serialize;
+
+use crate::types::data_type::DataValue;
+
+#[derive(Serialize)]
+pub struct InputChannelDto {
+    pub name: String,
+    pub value: DataValue,
+    // Milliseconds since the channel last received a value
+    pub last_received: u64,
+}
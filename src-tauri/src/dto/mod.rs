@@ -1,6 +1,14 @@
+pub mod artnet_node_dto;
+pub mod channel_info_dto;
+pub mod compound_dto;
 pub mod connection_dto;
+pub mod cue_list_dto;
 pub mod data_value_dto;
+pub mod fixtures_dto;
+pub mod graph_delta_dto;
 pub mod graph_dto;
+pub mod input_channel_dto;
 pub mod input_field_dto;
 pub mod node_dto;
 pub mod output_pin_dto;
+pub mod outputs_dto;
@@ -8,6 +8,8 @@ pub struct NodeDto {
     pub id: NodeId,
     pub kind: &'static str,
     pub position: (f64, f64),
+    pub text: String,
+    pub error: Option<String>,
 
     pub inputs: Vec<InputFieldDto>,
     pub outputs: Vec<OutputPinDto>,
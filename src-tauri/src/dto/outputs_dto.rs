@@ -0,0 +1,19 @@
+use serde::Serialize;
+
+use crate::dmx::merge::UniverseMerge;
+use crate::outputs::{InterfaceId, InterfaceKind, InterfaceStatus, Route};
+
+#[derive(Serialize)]
+pub struct OutputInterfaceDto {
+    pub id: InterfaceId,
+    pub name: String,
+    pub kind: InterfaceKind,
+    pub status: InterfaceStatus,
+}
+
+#[derive(Serialize)]
+pub struct OutputsDto {
+    pub interfaces: Vec<OutputInterfaceDto>,
+    pub routes: Vec<Route>,
+    pub merge: Vec<UniverseMerge>,
+}
@@ -0,0 +1,168 @@
+use crate::expression::parser::{BinaryOp, Expr, Function, UnaryOp};
+use crate::types::color::{Color, ColorValue};
+use crate::types::data_type::{DataValue, EvalError};
+use crate::types::interpolation;
+use crate::types::vector::Vec3;
+
+// Evaluates expr with variables[i] bound to Expr::Variable(i)
+pub fn evaluate(expr: &Expr, variables: &[DataValue]) -> Result<DataValue, EvalError> {
+    match expr {
+        Expr::Value(value) => Ok(*value),
+        Expr::Variable(index) => Ok(variables[*index]),
+        Expr::Unary(op, operand) => unary(*op, evaluate(operand, variables)?),
+        Expr::Binary(op, lhs, rhs) => {
+            let lhs = evaluate(lhs, variables)?;
+
+            // Short circuit
+            match (op, truthy("&&", lhs)) {
+                (BinaryOp::And, Ok(false)) => return Ok(DataValue::Boolean(false)),
+                (BinaryOp::Or, Ok(true)) => return Ok(DataValue::Boolean(true)),
+                _ => {}
+            }
+
+            binary(*op, lhs, evaluate(rhs, variables)?)
+        }
+        Expr::Call(function, args) => {
+            let args = args
+                .iter()
+                .map(|x| evaluate(x, variables))
+                .collect::<Result<Vec<DataValue>, EvalError>>()?;
+            call(*function, &args)
+        }
+        Expr::Conditional(condition, then, otherwise) => {
+            if truthy("?", evaluate(condition, variables)?)? {
+                evaluate(then, variables)
+            } else {
+                evaluate(otherwise, variables)
+            }
+        }
+    }
+}
+
+// Booleans and Triggers as they are, Numbers are true when non zero
+fn truthy(op: &'static str, value: DataValue) -> Result<bool, EvalError> {
+    match value {
+        DataValue::Boolean(b) | DataValue::Trigger(b) => Ok(b),
+        DataValue::Number(n) => Ok(n != 0.0),
+        _ => Err(EvalError::TypeError {
+            op,
+            lhs: value.type_name(),
+            rhs: value.type_name(),
+        }),
+    }
+}
+
+fn unary(op: UnaryOp, value: DataValue) -> Result<DataValue, EvalError> {
+    match op {
+        UnaryOp::Neg => DataValue::map_components("-", &[value], |x| -x[0]),
+        UnaryOp::Not => Ok(DataValue::Boolean(!truthy("!", value)?)),
+    }
+}
+
+fn binary(op: BinaryOp, lhs: DataValue, rhs: DataValue) -> Result<DataValue, EvalError> {
+    use DataValue::Number;
+
+    let same_type = lhs.type_name() == rhs.type_name();
+    let symbol = op.symbol();
+
+    match op {
+        // Same types use the DataValue operators, mixing in a Number applies it to every component
+        BinaryOp::Add if same_type => lhs + rhs,
+        BinaryOp::Sub if same_type => lhs - rhs,
+        BinaryOp::Mul if same_type => lhs * rhs,
+        BinaryOp::Div if same_type => lhs / rhs,
+        BinaryOp::Add => DataValue::map_components(symbol, &[lhs, rhs], |x| x[0] + x[1]),
+        BinaryOp::Sub => DataValue::map_components(symbol, &[lhs, rhs], |x| x[0] - x[1]),
+        BinaryOp::Mul => DataValue::map_components(symbol, &[lhs, rhs], |x| x[0] * x[1]),
+        BinaryOp::Div => DataValue::map_components(symbol, &[lhs, rhs], |x| x[0] / x[1]),
+        BinaryOp::Rem => DataValue::map_components(symbol, &[lhs, rhs], |x| x[0] % x[1]),
+        BinaryOp::Pow => DataValue::map_components(symbol, &[lhs, rhs], |x| x[0].powf(x[1])),
+
+        BinaryOp::Equal => Ok(DataValue::Boolean(lhs == rhs)),
+        BinaryOp::NotEqual => Ok(DataValue::Boolean(lhs != rhs)),
+
+        BinaryOp::Less | BinaryOp::LessEqual | BinaryOp::Greater | BinaryOp::GreaterEqual => {
+            match (lhs, rhs) {
+                (Number(a), Number(b)) => Ok(DataValue::Boolean(match op {
+                    BinaryOp::Less => a < b,
+                    BinaryOp::LessEqual => a <= b,
+                    BinaryOp::Greater => a > b,
+                    _ => a >= b,
+                })),
+                _ => Err(EvalError::TypeError {
+                    op: symbol,
+                    lhs: lhs.type_name(),
+                    rhs: rhs.type_name(),
+                }),
+            }
+        }
+
+        BinaryOp::And | BinaryOp::Or => {
+            let (a, b) = match (truthy(symbol, lhs), truthy(symbol, rhs)) {
+                (Ok(a), Ok(b)) => (a, b),
+                _ => {
+                    return Err(EvalError::TypeError {
+                        op: symbol,
+                        lhs: lhs.type_name(),
+                        rhs: rhs.type_name(),
+                    })
+                }
+            };
+            Ok(DataValue::Boolean(if op == BinaryOp::And {
+                a && b
+            } else {
+                a || b
+            }))
+        }
+    }
+}
+
+fn call(function: Function, args: &[DataValue]) -> Result<DataValue, EvalError> {
+    let name = function.name();
+    let map = |f: fn(&[f64]) -> f64| DataValue::map_components(name, args, f);
+
+    match function {
+        Function::Sin => map(|x| x[0].sin()),
+        Function::Cos => map(|x| x[0].cos()),
+        Function::Tan => map(|x| x[0].tan()),
+        Function::Asin => map(|x| x[0].asin()),
+        Function::Acos => map(|x| x[0].acos()),
+        Function::Atan => map(|x| x[0].atan()),
+        Function::Atan2 => map(|x| x[0].atan2(x[1])),
+        Function::Sqrt => map(|x| x[0].sqrt()),
+        Function::Abs => map(|x| x[0].abs()),
+        Function::Floor => map(|x| x[0].floor()),
+        Function::Ceil => map(|x| x[0].ceil()),
+        Function::Round => map(|x| x[0].round()),
+        Function::Fract => map(|x| x[0] - x[0].floor()),
+        Function::Sign => map(|x| if x[0] == 0.0 { 0.0 } else { x[0].signum() }),
+        Function::Exp => map(|x| x[0].exp()),
+        Function::Ln => map(|x| x[0].ln()),
+        Function::Pow => map(|x| x[0].powf(x[1])),
+        Function::Min => map(|x| x[0].min(x[1])),
+        Function::Max => map(|x| x[0].max(x[1])),
+        Function::Clamp => map(|x| interpolation::clamp(x[0], x[1], x[2])),
+        Function::Lerp => map(|x| interpolation::lerp(x[0], x[1], x[2])),
+        Function::Smoothstep => map(|x| interpolation::smoothstep(x[0], x[1], x[2])),
+        Function::Vec3 | Function::Rgb => {
+            let mut n = [0.0; 3];
+            for (i, arg) in args.iter().enumerate() {
+                match arg {
+                    DataValue::Number(x) => n[i] = *x,
+                    _ => {
+                        return Err(EvalError::TypeError {
+                            op: name,
+                            lhs: "Number",
+                            rhs: arg.type_name(),
+                        })
+                    }
+                }
+            }
+
+            Ok(match function {
+                Function::Vec3 => DataValue::Vector3(Vec3::new(n[0], n[1], n[2])),
+                _ => DataValue::Color(Color::new(ColorValue::RGB(n[0], n[1], n[2]))),
+            })
+        }
+    }
+}
@@ -0,0 +1,121 @@
+// Small maths language used by the Expression node, e.g. "sin(t * 2) * 0.5 + 0.5"
+pub mod eval;
+pub mod parser;
+
+pub use eval::evaluate;
+pub use parser::{parse, Expr, ParseError};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::graph::Graph;
+    use crate::core::node::{NodeKind, NodeUIState};
+    use crate::types::data_type::DataValue::{self, *};
+    use crate::types::vector::Vec3;
+
+    fn eval(source: &str, variables: &[DataValue]) -> DataValue {
+        let expr = parse(source, &["a", "b", "t"]).unwrap();
+        evaluate(&expr, variables).unwrap()
+    }
+
+    #[test]
+    fn arithmetic_and_precedence() {
+        let vars = [Number(2.0), Number(3.0), Number(0.0)];
+
+        assert_eq!(Number(14.0), eval("a + b * 4", &vars));
+        assert_eq!(Number(20.0), eval("(a + b) * 4", &vars));
+        assert_eq!(Number(-4.0), eval("-a^2", &vars));
+        assert_eq!(Number(512.0), eval("2^3^2", &vars));
+        assert_eq!(Number(1.0), eval("7 % b", &vars));
+        assert_eq!(Number(0.001), eval("1e-3", &vars));
+        assert_eq!(Number(0.5), eval("sin(t * 2) * 0.5 + 0.5", &vars));
+        assert_eq!(Number(3.0), eval("max(a, b)", &vars));
+        assert_eq!(Number(1.0), eval("clamp(a, 0, 1)", &vars));
+    }
+
+    #[test]
+    fn comparisons_and_logic() {
+        let vars = [Number(2.0), Number(3.0), Number(0.0)];
+
+        assert_eq!(Boolean(true), eval("a < b && b <= 3", &vars));
+        assert_eq!(Boolean(false), eval("a == b || !(a != b)", &vars));
+        assert_eq!(Number(3.0), eval("a > b ? a : b", &vars));
+        assert_eq!(Boolean(true), eval("true || 1 / 0", &vars));
+    }
+
+    #[test]
+    fn vectors_and_colors() {
+        let v = Vector3(Vec3::new(1.0, 2.0, 3.0));
+        let vars = [v, Number(2.0), Number(0.0)];
+
+        assert_eq!(Vector3(Vec3::new(2.0, 4.0, 6.0)), eval("a * b", &vars));
+        assert_eq!(Vector3(Vec3::new(2.0, 4.0, 6.0)), eval("a + a", &vars));
+        assert_eq!(
+            Vector3(Vec3::new(1.0, 2.0, 3.0)),
+            eval("vec3(1, 2, b + 1)", &vars)
+        );
+        assert_eq!(Vector3(Vec3::new(-1.0, -2.0, -3.0)), eval("-a", &vars));
+    }
+
+    #[test]
+    fn errors() {
+        let vars = ["a"];
+
+        assert_eq!(4, parse("1 + * 2", &vars).unwrap_err().position);
+        assert_eq!(0, parse("x + 1", &vars).unwrap_err().position);
+        assert!(parse("sin(1, 2)", &vars).is_err());
+        assert!(parse("nope(1)", &vars).is_err());
+        assert!(parse("(1 + 2", &vars).is_err());
+        assert!(parse("1 $ 2", &vars).is_err());
+
+        // Refused before they could overflow the stack
+        let nested = |n| format!("{}1{}", "(".repeat(n), ")".repeat(n));
+        assert_eq!(
+            Number(1.0),
+            evaluate(&parse(&nested(32), &vars).unwrap(), &[]).unwrap()
+        );
+        assert_eq!(33, parse(&nested(100), &vars).unwrap_err().position);
+        assert!(parse(&"-".repeat(100), &vars).is_err());
+        assert!(parse(&"1+".repeat(10_000), &vars).is_err());
+
+        let expr = parse("a + 1", &vars).unwrap();
+        assert!(evaluate(&expr, &[Boolean(true)]).is_err());
+    }
+
+    #[test]
+    fn expression_node() {
+        let mut graph = Graph::new(0);
+        graph.add_node(
+            NodeKind::Expression,
+            NodeUIState {
+                position: (0.0, 0.0),
+            },
+        );
+
+        graph.set_node_text(0, "a * 2 + t");
+        graph.set_input_value(0, Number(3.0));
+        graph.tick(0.5);
+        assert_eq!(Number(6.5), graph.get_output_value(0));
+        assert!(graph.get_node(0).error.is_none());
+
+        // Parsed once, not every frame
+        assert!(graph.get_node(0).cache.is_some());
+
+        // Parse errors keep the last output
+        graph.set_node_text(0, "a *");
+        graph.tick(0.5);
+        assert_eq!(Number(6.5), graph.get_output_value(0));
+        assert!(graph.get_node(0).error.is_some());
+
+        // Type errors are reported the same way
+        graph.set_node_text(0, "a + b");
+        graph.set_input_value(1, Boolean(true));
+        graph.tick(0.5);
+        assert!(graph.get_node(0).error.is_some());
+
+        graph.set_input_value(1, Number(1.0));
+        graph.tick(0.5);
+        assert_eq!(Number(4.0), graph.get_output_value(0));
+        assert!(graph.get_node(0).error.is_none());
+    }
+}
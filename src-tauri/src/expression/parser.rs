@@ -0,0 +1,460 @@
+use std::fmt::{Display, Formatter};
+
+use crate::types::data_type::DataValue;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    Value(DataValue),
+    // Index into the variable names given to parse()
+    Variable(usize),
+    Unary(UnaryOp, Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Call(Function, Vec<Expr>),
+    // condition ? then : else
+    Conditional(Box<Expr>, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum UnaryOp {
+    Neg,
+    Not,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Pow,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Equal,
+    NotEqual,
+    And,
+    Or,
+}
+
+impl BinaryOp {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Mul => "*",
+            BinaryOp::Div => "/",
+            BinaryOp::Rem => "%",
+            BinaryOp::Pow => "^",
+            BinaryOp::Less => "<",
+            BinaryOp::LessEqual => "<=",
+            BinaryOp::Greater => ">",
+            BinaryOp::GreaterEqual => ">=",
+            BinaryOp::Equal => "==",
+            BinaryOp::NotEqual => "!=",
+            BinaryOp::And => "&&",
+            BinaryOp::Or => "||",
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Function {
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    Atan2,
+    Sqrt,
+    Abs,
+    Floor,
+    Ceil,
+    Round,
+    Fract,
+    Sign,
+    Exp,
+    Ln,
+    Pow,
+    Min,
+    Max,
+    Clamp,
+    Lerp,
+    Smoothstep,
+    Vec3,
+    Rgb,
+}
+
+const FUNCTIONS: &[(&str, Function, usize)] = &[
+    ("sin", Function::Sin, 1),
+    ("cos", Function::Cos, 1),
+    ("tan", Function::Tan, 1),
+    ("asin", Function::Asin, 1),
+    ("acos", Function::Acos, 1),
+    ("atan", Function::Atan, 1),
+    ("atan2", Function::Atan2, 2),
+    ("sqrt", Function::Sqrt, 1),
+    ("abs", Function::Abs, 1),
+    ("floor", Function::Floor, 1),
+    ("ceil", Function::Ceil, 1),
+    ("round", Function::Round, 1),
+    ("fract", Function::Fract, 1),
+    ("sign", Function::Sign, 1),
+    ("exp", Function::Exp, 1),
+    ("ln", Function::Ln, 1),
+    ("pow", Function::Pow, 2),
+    ("min", Function::Min, 2),
+    ("max", Function::Max, 2),
+    ("clamp", Function::Clamp, 3),
+    ("lerp", Function::Lerp, 3),
+    ("smoothstep", Function::Smoothstep, 3),
+    ("vec3", Function::Vec3, 3),
+    ("rgb", Function::Rgb, 3),
+];
+
+impl Function {
+    pub fn name(&self) -> &'static str {
+        FUNCTIONS.iter().find(|x| x.1 == *self).unwrap().0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+    pub message: String,
+    // Byte offset into the source
+    pub position: usize,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{} at {}", self.message, self.position)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Op(&'static str),
+    End,
+}
+
+const OPERATORS: &[&str] = &[
+    "<=", ">=", "==", "!=", "&&", "||", "+", "-", "*", "/", "%", "^", "<", ">", "!", "(", ")", ",",
+    "?", ":",
+];
+
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, ParseError> {
+    let mut tokens = Vec::new();
+    let bytes = source.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let c = bytes[i] as char;
+
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            let start = i;
+            while i < bytes.len() && ((bytes[i] as char).is_ascii_digit() || bytes[i] == b'.') {
+                i += 1;
+            }
+            // Exponent, e.g. 1e-3
+            if i < bytes.len() && (bytes[i] == b'e' || bytes[i] == b'E') {
+                let mut j = i + 1;
+                if j < bytes.len() && (bytes[j] == b'+' || bytes[j] == b'-') {
+                    j += 1;
+                }
+                if j < bytes.len() && (bytes[j] as char).is_ascii_digit() {
+                    i = j;
+                    while i < bytes.len() && (bytes[i] as char).is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+            }
+            let number = source[start..i].parse::<f64>().map_err(|_| ParseError {
+                message: format!("Invalid number '{}'", &source[start..i]),
+                position: start,
+            })?;
+            tokens.push((Token::Number(number), start));
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < bytes.len()
+                && ((bytes[i] as char).is_ascii_alphanumeric() || bytes[i] == b'_')
+            {
+                i += 1;
+            }
+            tokens.push((Token::Ident(source[start..i].to_string()), start));
+        } else {
+            match OPERATORS.iter().find(|op| source[i..].starts_with(**op)) {
+                Some(op) => {
+                    tokens.push((Token::Op(op), i));
+                    i += op.len();
+                }
+                None => {
+                    return Err(ParseError {
+                        message: format!(
+                            "Unexpected character '{}'",
+                            &source[i..].chars().next().unwrap()
+                        ),
+                        position: i,
+                    })
+                }
+            }
+        }
+    }
+
+    tokens.push((Token::End, source.len()));
+    Ok(tokens)
+}
+
+// Parentheses, calls, conditionals and unary operators nested deeper than
+// this are refused, so the parser and evaluate() don't overflow the stack
+const MAX_DEPTH: usize = 32;
+// Same for long chains of binary operators, which nest to the left
+const MAX_TOKENS: usize = 1000;
+
+struct Parser<'a> {
+    tokens: Vec<(Token, usize)>,
+    current: usize,
+    variables: &'a [&'a str],
+    depth: usize,
+}
+
+// Parses source into an Expr. Identifiers must be one of variables, a function or a constant (pi, e, true, false).
+pub fn parse(source: &str, variables: &[&str]) -> Result<Expr, ParseError> {
+    let tokens = tokenize(source)?;
+    if tokens.len() > MAX_TOKENS {
+        return Err(ParseError {
+            message: "Expression is too long".to_string(),
+            position: tokens[MAX_TOKENS].1,
+        });
+    }
+
+    let mut parser = Parser {
+        tokens,
+        current: 0,
+        variables,
+        depth: 0,
+    };
+
+    let expr = parser.conditional()?;
+    match parser.peek() {
+        Token::End => Ok(expr),
+        _ => Err(parser.error("Unexpected token")),
+    }
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.current].0
+    }
+
+    fn position(&self) -> usize {
+        self.tokens[self.current].1
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.current].0.clone();
+        if token != Token::End {
+            self.current += 1;
+        }
+        token
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        ParseError {
+            message: message.to_string(),
+            position: self.position(),
+        }
+    }
+
+    // Consumes the operator if it is next
+    fn accept(&mut self, op: &str) -> bool {
+        if matches!(self.peek(), Token::Op(x) if *x == op) {
+            self.current += 1;
+            return true;
+        }
+        false
+    }
+
+    fn expect(&mut self, op: &str) -> Result<(), ParseError> {
+        if self.accept(op) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("Expected '{}'", op)))
+        }
+    }
+
+    // Runs a rule one level deeper
+    fn nested(
+        &mut self,
+        rule: fn(&mut Self) -> Result<Expr, ParseError>,
+    ) -> Result<Expr, ParseError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error("Expression is nested too deeply"));
+        }
+
+        self.depth += 1;
+        let result = rule(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn conditional(&mut self) -> Result<Expr, ParseError> {
+        let condition = self.binary(0)?;
+
+        if self.accept("?") {
+            let then = self.nested(Parser::conditional)?;
+            self.expect(":")?;
+            let otherwise = self.nested(Parser::conditional)?;
+            return Ok(Expr::Conditional(
+                Box::new(condition),
+                Box::new(then),
+                Box::new(otherwise),
+            ));
+        }
+
+        Ok(condition)
+    }
+
+    // Precedence climbing over the left associative operators, lowest first
+    fn binary(&mut self, level: usize) -> Result<Expr, ParseError> {
+        const LEVELS: &[&[(&str, BinaryOp)]] = &[
+            &[("||", BinaryOp::Or)],
+            &[("&&", BinaryOp::And)],
+            &[("==", BinaryOp::Equal), ("!=", BinaryOp::NotEqual)],
+            &[
+                ("<=", BinaryOp::LessEqual),
+                (">=", BinaryOp::GreaterEqual),
+                ("<", BinaryOp::Less),
+                (">", BinaryOp::Greater),
+            ],
+            &[("+", BinaryOp::Add), ("-", BinaryOp::Sub)],
+            &[
+                ("*", BinaryOp::Mul),
+                ("/", BinaryOp::Div),
+                ("%", BinaryOp::Rem),
+            ],
+        ];
+
+        if level == LEVELS.len() {
+            return self.unary();
+        }
+
+        let mut lhs = self.binary(level + 1)?;
+        'outer: loop {
+            for (symbol, op) in LEVELS[level] {
+                if self.accept(symbol) {
+                    let rhs = self.binary(level + 1)?;
+                    lhs = Expr::Binary(*op, Box::new(lhs), Box::new(rhs));
+                    continue 'outer;
+                }
+            }
+            return Ok(lhs);
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        if self.accept("-") {
+            let operand = self.nested(Parser::unary)?;
+            return Ok(Expr::Unary(UnaryOp::Neg, Box::new(operand)));
+        }
+        if self.accept("!") {
+            let operand = self.nested(Parser::unary)?;
+            return Ok(Expr::Unary(UnaryOp::Not, Box::new(operand)));
+        }
+        self.power()
+    }
+
+    // Right associative and binds tighter than unary minus, so -2^2 == -4
+    fn power(&mut self) -> Result<Expr, ParseError> {
+        let base = self.primary()?;
+
+        if self.accept("^") {
+            let exponent = self.nested(Parser::unary)?;
+            return Ok(Expr::Binary(
+                BinaryOp::Pow,
+                Box::new(base),
+                Box::new(exponent),
+            ));
+        }
+
+        Ok(base)
+    }
+
+    fn primary(&mut self) -> Result<Expr, ParseError> {
+        let position = self.position();
+
+        match self.advance() {
+            Token::Number(n) => Ok(Expr::Value(DataValue::Number(n))),
+            Token::Op("(") => {
+                let expr = self.nested(Parser::conditional)?;
+                self.expect(")")?;
+                Ok(expr)
+            }
+            Token::Ident(name) => {
+                if self.accept("(") {
+                    return self.call(&name, position);
+                }
+
+                if let Some(index) = self.variables.iter().position(|x| *x == name) {
+                    return Ok(Expr::Variable(index));
+                }
+
+                match name.as_str() {
+                    "pi" => Ok(Expr::Value(DataValue::Number(std::f64::consts::PI))),
+                    "e" => Ok(Expr::Value(DataValue::Number(std::f64::consts::E))),
+                    "true" => Ok(Expr::Value(DataValue::Boolean(true))),
+                    "false" => Ok(Expr::Value(DataValue::Boolean(false))),
+                    _ => Err(ParseError {
+                        message: format!("Unknown variable '{}'", name),
+                        position,
+                    }),
+                }
+            }
+            Token::End => Err(ParseError {
+                message: "Unexpected end of expression".to_string(),
+                position,
+            }),
+            Token::Op(op) => Err(ParseError {
+                message: format!("Unexpected '{}'", op),
+                position,
+            }),
+        }
+    }
+
+    fn call(&mut self, name: &str, position: usize) -> Result<Expr, ParseError> {
+        let (function, arity) = match FUNCTIONS.iter().find(|x| x.0 == name) {
+            Some((_, function, arity)) => (*function, *arity),
+            None => {
+                return Err(ParseError {
+                    message: format!("Unknown function '{}'", name),
+                    position,
+                })
+            }
+        };
+
+        let mut args = Vec::new();
+        if !self.accept(")") {
+            loop {
+                args.push(self.nested(Parser::conditional)?);
+                if self.accept(")") {
+                    break;
+                }
+                self.expect(",")?;
+            }
+        }
+
+        if args.len() != arity {
+            return Err(ParseError {
+                message: format!("{} takes {} arguments, got {}", name, arity, args.len()),
+                position,
+            });
+        }
+
+        Ok(Expr::Call(function, args))
+    }
+}
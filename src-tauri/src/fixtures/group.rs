@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+
+use crate::fixtures::patch::FixtureId;
+
+pub type GroupId = u32;
+
+// Fixtures in the order values spread over them
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FixtureGroup {
+    pub id: GroupId,
+    pub name: String,
+    pub fixtures: Vec<FixtureId>,
+}
+
+// How the members of a group are offset from each other
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Spread {
+    // First member leads, the last lags most
+    Linear,
+    Reversed,
+    // The middle leads, pairs either side follow together
+    CentreOut,
+    // Shuffled by a seed, the same seed shuffles the same way
+    Random(u64),
+}
+
+impl Spread {
+    // Index used by the Group node's "Mode" input
+    pub fn from_index(index: f64, seed: f64) -> Spread {
+        match index.round() as i64 {
+            1 => Spread::Reversed,
+            2 => Spread::CentreOut,
+            3 => Spread::Random(seed.round().max(0.0) as u64),
+            _ => Spread::Linear,
+        }
+    }
+
+    // Offset of every member, 0 for the ones leading, below 1
+    pub fn offsets(&self, count: usize) -> Vec<f64> {
+        if count == 0 {
+            return Vec::new();
+        }
+
+        let n = count as f64;
+        match self {
+            Spread::Linear => (0..count).map(|i| i as f64 / n).collect(),
+            Spread::Reversed => (0..count).map(|i| (count - 1 - i) as f64 / n).collect(),
+            Spread::CentreOut => {
+                // Steps away from the middle, the middle pair of an even group is 0
+                let steps = count.div_ceil(2) as f64;
+                (0..count)
+                    .map(|i| ((2 * i).abs_diff(count - 1) / 2) as f64 / steps)
+                    .collect()
+            }
+            Spread::Random(seed) => {
+                let mut offsets = Spread::Linear.offsets(count);
+                shuffle(&mut offsets, *seed);
+                offsets
+            }
+        }
+    }
+}
+
+// Value of every member. Offsets lag the value by amount (in cycles across
+// the whole group) and wrap, so a ramp into value chases across the group.
+pub fn spread(value: f64, amount: f64, offsets: &[f64]) -> Vec<f64> {
+    offsets
+        .iter()
+        .map(|offset| {
+            let x = value.clamp(0.0, 1.0) - amount.clamp(0.0, 1.0) * offset;
+            if x < 0.0 {
+                x + 1.0
+            } else {
+                x
+            }
+        })
+        .collect()
+}
+
+// Fisher-Yates with xorshift, no need for a better generator to order lights
+fn shuffle(values: &mut [f64], seed: u64) {
+    // xorshift never leaves 0
+    let mut state = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+    for i in (1..values.len()).rev() {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        values.swap(i, (state % (i as u64 + 1)) as usize);
+    }
+}
@@ -0,0 +1,131 @@
+use std::f64::consts::{PI, TAU};
+
+use crate::types::curve;
+use crate::types::vector::{cross, dot, unit_vector, Point3, Vec3};
+
+// How a moving head is hung. The beam points along up at tilt 0 (away from
+// the base, so down for a fixture hung from a truss) and tilting with pan at
+// its centre swings it towards forward.
+#[derive(Copy, Clone, Debug)]
+pub struct Mounting {
+    pub position: Point3,
+    pub up: Vec3,
+    pub forward: Vec3,
+    // Full ranges in degrees, centred on DMX 0.5
+    pub pan_range: f64,
+    pub tilt_range: f64,
+}
+
+// Angles in radians from the centre of the ranges
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct PanTilt {
+    pub pan: f64,
+    pub tilt: f64,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Aim {
+    pub angles: PanTilt,
+    // False when the target is out of range, the angles are the nearest reachable
+    pub reachable: bool,
+}
+
+impl Mounting {
+    // Pan and tilt pointing at target, the solution closest to current so
+    // the head takes the shortest way across the pan wrap
+    pub fn look_at(&self, target: Point3, current: PanTilt) -> Aim {
+        let (up, forward, right) = self.axes();
+        let pan_max = self.pan_range.to_radians() / 2.0;
+        let tilt_max = self.tilt_range.to_radians() / 2.0;
+
+        let direction = target - self.position;
+        if direction.length_squared() == 0.0 {
+            return Aim {
+                angles: current,
+                reachable: true,
+            };
+        }
+        let direction = unit_vector(direction);
+
+        let tilt = dot(direction, up).clamp(-1.0, 1.0).acos();
+        let side = dot(direction, right);
+        let ahead = dot(direction, forward);
+        // Straight along up any pan works, keep the current one
+        let azimuth = if side.abs() < 1e-9 && ahead.abs() < 1e-9 {
+            current.pan
+        } else {
+            side.atan2(ahead)
+        };
+
+        // The same direction with the head turned round and tilted the other way
+        let mut candidates = Vec::new();
+        for (pan, tilt) in [(azimuth, tilt), (azimuth + PI, -tilt)] {
+            // Every turn of the pan range
+            let first = ((-pan_max - pan) / TAU).ceil() as i64;
+            let last = ((pan_max - pan) / TAU).floor() as i64;
+            for turn in first..=last {
+                candidates.push(PanTilt {
+                    pan: pan + turn as f64 * TAU,
+                    tilt,
+                });
+            }
+        }
+
+        let distance = |x: &PanTilt| (x.pan - current.pan).abs() + (x.tilt - current.tilt).abs();
+        let reachable = candidates
+            .iter()
+            .filter(|x| x.tilt.abs() <= tilt_max + 1e-9)
+            .min_by(|a, b| distance(a).total_cmp(&distance(b)));
+
+        match reachable {
+            Some(angles) => Aim {
+                angles: *angles,
+                reachable: true,
+            },
+            None => {
+                // As close as the tilt goes, on the nearest pan
+                let nearest = candidates
+                    .iter()
+                    .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+                    .copied()
+                    .unwrap_or(current);
+                Aim {
+                    angles: PanTilt {
+                        pan: nearest.pan.clamp(-pan_max, pan_max),
+                        tilt: nearest.tilt.clamp(-tilt_max, tilt_max),
+                    },
+                    reachable: false,
+                }
+            }
+        }
+    }
+
+    // 0..1 over the ranges
+    pub fn to_unit(&self, angles: PanTilt) -> (f64, f64) {
+        (
+            curve::clamp_unit(0.5 + angles.pan.to_degrees() / self.pan_range),
+            curve::clamp_unit(0.5 + angles.tilt.to_degrees() / self.tilt_range),
+        )
+    }
+
+    // 16 bit pan and tilt levels
+    pub fn to_dmx(&self, angles: PanTilt) -> (u16, u16) {
+        let (pan, tilt) = self.to_unit(angles);
+        (curve::to_u16(pan), curve::to_u16(tilt))
+    }
+
+    // Up, and forward and right square to it
+    fn axes(&self) -> (Vec3, Vec3, Vec3) {
+        let up = unit_vector(self.up);
+        let mut forward = self.forward - dot(self.forward, up) * up;
+        if forward.length_squared() < 1e-12 {
+            // Forward along up, any square direction will do
+            forward = cross(up, Vec3::new(1.0, 0.0, 0.0));
+            if forward.length_squared() < 1e-12 {
+                forward = cross(up, Vec3::new(0.0, 0.0, 1.0));
+            }
+        }
+        let forward = unit_vector(forward);
+        (up, forward, cross(up, forward))
+    }
+}
@@ -0,0 +1,496 @@
+pub mod group;
+pub mod look_at;
+pub mod ofl;
+pub mod patch;
+pub mod profile;
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::dmx::{DmxOutput, PortAddress};
+use crate::fixtures::group::{FixtureGroup, GroupId};
+use crate::fixtures::patch::{FixtureId, PatchedFixture};
+use crate::fixtures::profile::{Attribute, Channel, FixtureProfile};
+use crate::types::curve;
+
+// A patched fixture with the channels of its mode, what Fixture nodes write through
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResolvedFixture {
+    pub universe: PortAddress,
+    // First channel, from 1
+    pub address: u16,
+    pub channels: Vec<Channel>,
+}
+
+impl ResolvedFixture {
+    pub fn resolve(fixture: &PatchedFixture, profile: &FixtureProfile) -> Option<ResolvedFixture> {
+        profile.mode(&fixture.mode).map(|mode| ResolvedFixture {
+            universe: fixture.universe,
+            address: fixture.address,
+            channels: mode.channels.clone(),
+        })
+    }
+
+    pub fn has(&self, attribute: Attribute) -> bool {
+        self.channels.iter().any(|x| x.attribute == attribute)
+    }
+
+    // Every channel at its default level
+    pub fn write_defaults(&self, output: &mut DmxOutput) {
+        for (i, channel) in self.channels.iter().enumerate() {
+            if let Some(index) = self.index(i) {
+                output.set(index, channel.default);
+            }
+        }
+    }
+
+    // Value 0..1 over every channel of the attribute, split into coarse,
+    // fine and ultra fine bytes. Nothing happens without such a channel.
+    pub fn write(&self, output: &mut DmxOutput, attribute: Attribute, value: f64) {
+        let bytes = self
+            .channels
+            .iter()
+            .filter(|x| x.attribute == attribute)
+            .map(|x| x.fine as u32 + 1)
+            .max();
+        let Some(bytes) = bytes else {
+            return;
+        };
+
+        let max = (1u64 << (8 * bytes)) - 1;
+        let level = (curve::clamp_unit(value) * max as f64).round() as u64;
+
+        for (i, channel) in self.channels.iter().enumerate() {
+            if let (true, Some(index)) = (channel.attribute == attribute, self.index(i)) {
+                let shift = 8 * (bytes - 1 - channel.fine as u32);
+                output.set(index, (level >> shift) as u8);
+            }
+        }
+    }
+
+    // Index in the universe of the nth channel, from 0. None at address 0,
+    // past the end of the universe DmxOutput::set ignores it.
+    fn index(&self, channel: usize) -> Option<usize> {
+        (self.address as usize).checked_sub(1).map(|x| x + channel)
+    }
+}
+
+// The patch as saved in a project, with the profiles it uses so the project
+// still loads where they were never imported
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct FixtureConfig {
+    #[serde(default)]
+    pub profiles: Vec<FixtureProfile>,
+    #[serde(default)]
+    pub patch: Vec<PatchedFixture>,
+    #[serde(default)]
+    pub groups: Vec<FixtureGroup>,
+}
+
+// Every patched fixture whose profile is known, and the groups, shared with the graphs
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FixtureMap {
+    pub fixtures: HashMap<FixtureId, ResolvedFixture>,
+    // Members in order
+    pub groups: HashMap<GroupId, Vec<FixtureId>>,
+}
+
+impl FixtureMap {
+    pub fn get(&self, id: FixtureId) -> Option<&ResolvedFixture> {
+        self.fixtures.get(&id)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct FixtureLibrary {
+    profiles: Vec<FixtureProfile>,
+}
+
+impl FixtureLibrary {
+    // The generic profiles
+    pub fn new() -> FixtureLibrary {
+        FixtureLibrary {
+            profiles: profile::builtin_profiles(),
+        }
+    }
+
+    pub fn profiles(&self) -> &[FixtureProfile] {
+        &self.profiles
+    }
+
+    pub fn get(&self, key: &str) -> Option<&FixtureProfile> {
+        self.profiles.iter().find(|x| x.key() == key)
+    }
+
+    // Replaces the profile with the same key
+    pub fn add(&mut self, profile: FixtureProfile) {
+        let key = profile.key();
+        self.profiles.retain(|x| x.key() != key);
+        self.profiles.push(profile);
+    }
+}
+
+impl Default for FixtureLibrary {
+    fn default() -> Self {
+        FixtureLibrary::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::look_at::{Mounting, PanTilt};
+    use super::ofl::{self, OflError};
+    use super::patch::*;
+    use super::profile::*;
+    use super::*;
+    use crate::managers::fixtures_manager::FixturesManager;
+    use crate::types::vector::Vec3;
+
+    const RGBW_PAR: &str = include_str!("samples/generic/rgbw-par.json");
+    const SPOT: &str = include_str!("samples/generic/spot-moving-head.json");
+    const PIXEL_BAR: &str = include_str!("samples/generic/pixel-bar.json");
+
+    fn attributes(mode: &Mode) -> Vec<(Attribute, u8)> {
+        mode.channels
+            .iter()
+            .map(|x| (x.attribute, x.fine))
+            .collect()
+    }
+
+    fn levels(output: &DmxOutput, channels: std::ops::RangeInclusive<usize>) -> Vec<Option<u8>> {
+        channels.map(|x| output.levels[x - 1]).collect()
+    }
+
+    #[test]
+    fn patch_rejects_overlaps() {
+        let library = FixtureLibrary::new();
+        let rgb = library.get("Generic/RGB").unwrap();
+        let head = library.get("Generic/Moving Head").unwrap();
+        let mut patch = PatchTable::new();
+
+        let a = patch.patch("A", rgb, "3 channel", 1, 1).unwrap();
+        assert_eq!(
+            Err(PatchError::Overlap(a)),
+            patch.patch("B", rgb, "3 channel", 1, 3)
+        );
+        let b = patch.patch("B", rgb, "3 channel", 1, 4).unwrap();
+        // Another universe
+        patch.patch("C", rgb, "3 channel", 2, 1).unwrap();
+
+        assert_eq!(
+            Err(PatchError::OutOfRange(505)),
+            patch.patch("D", head, "Standard", 1, 505)
+        );
+        // Past the top of u16, not just of the universe
+        assert_eq!(
+            Err(PatchError::OutOfRange(u16::MAX)),
+            patch.patch("D", head, "Standard", 1, u16::MAX)
+        );
+        assert_eq!(
+            Err(PatchError::OutOfRange(u16::MAX)),
+            patch.move_to(a, 1, u16::MAX)
+        );
+        patch.patch("D", head, "Standard", 1, 502).unwrap();
+        assert_eq!(
+            Err(PatchError::UnknownMode("Basic".to_string())),
+            patch.patch("E", rgb, "Basic", 1, 100)
+        );
+
+        // Moving onto itself is fine, onto a neighbour isn't
+        patch.move_to(b, 1, 5).unwrap();
+        assert_eq!(Err(PatchError::Overlap(b)), patch.move_to(a, 1, 3));
+        assert_eq!(Err(PatchError::UnknownFixture(9)), patch.move_to(9, 1, 1));
+        assert_eq!(1, patch.get(a).unwrap().address);
+
+        patch.unpatch(b);
+        patch.move_to(a, 1, 3).unwrap();
+        assert!(patch.overlaps().is_empty());
+
+        let mut fixtures = patch.fixtures().to_vec();
+        fixtures[1].address = 4;
+        fixtures[1].universe = 1;
+        patch.set_fixtures(fixtures);
+        assert_eq!(vec![(a, 2)], patch.overlaps());
+    }
+
+    #[test]
+    fn loaded_patch_is_checked() {
+        let mut fixtures = FixturesManager::new();
+        let a = fixtures
+            .patch("A", "Generic/RGB", "3 channel", 1, 1)
+            .unwrap();
+        let b = fixtures
+            .patch("B", "Generic/RGB", "3 channel", 1, 10)
+            .unwrap();
+
+        // Edited by hand: at address 0, and a footprint that doesn't fit the mode
+        let mut config = fixtures.config();
+        config.patch[0].address = 0;
+        config.patch[1].address = 511;
+        config.patch[1].footprint = 1;
+        fixtures.set_config(config);
+        assert_eq!(3, fixtures.patch_table().get(b).unwrap().footprint);
+        assert_eq!(vec![a, b], fixtures.to_dto().out_of_range);
+        assert!(fixtures.fixture_map().fixtures.is_empty());
+
+        // Written anyway, address 0 has no channel 0 to land on
+        let fixture = ResolvedFixture::resolve(
+            &fixtures.patch_table().fixtures()[0],
+            fixtures.library().get("Generic/RGB").unwrap(),
+        )
+        .unwrap();
+        let mut output = DmxOutput::new();
+        fixture.write_defaults(&mut output);
+        fixture.write(&mut output, Attribute::Red, 1.0);
+        assert!(output.levels.iter().all(|x| x.is_none()));
+    }
+
+    #[test]
+    fn replaced_profile_updates_footprints() {
+        let mut fixtures = FixturesManager::new();
+        let mut profile = fixtures.library().get("Generic/RGB").unwrap().clone();
+        profile.manufacturer = "Acme".to_string();
+        profile.modes[0].channels.truncate(2);
+        let channels = profile.modes[0].channels.clone();
+        assert!(fixtures.add_profile(profile.clone()).is_empty());
+        let a = fixtures.patch("A", "Acme/RGB", "3 channel", 1, 1).unwrap();
+        let b = fixtures.patch("B", "Acme/RGB", "3 channel", 1, 3).unwrap();
+
+        // Two channels more, A now runs into B
+        profile.modes[0].channels.extend(channels);
+        let warnings = fixtures.add_profile(profile.clone());
+        assert_eq!(
+            vec![format!("Fixture {} now overlaps fixture {}", a, b)],
+            warnings
+        );
+        assert_eq!(4, fixtures.patch_table().get(a).unwrap().footprint);
+        assert_eq!(vec![(a, b)], fixtures.to_dto().overlaps);
+
+        // Without the mode the fixtures aren't output, and say so
+        profile.modes[0].name = "4 channel".to_string();
+        assert_eq!(2, fixtures.add_profile(profile).len());
+        assert_eq!(vec![a, b], fixtures.unresolved());
+        assert!(fixtures.fixture_map().fixtures.is_empty());
+    }
+
+    #[test]
+    fn ofl_imports_modes() {
+        use Attribute::*;
+
+        let import = ofl::import(RGBW_PAR, "Generic").unwrap();
+        assert!(import.warnings.is_empty());
+        assert_eq!("Generic/RGBW Par", import.profile.key());
+        assert_eq!(4, import.profile.mode("4-channel").unwrap().footprint());
+        assert_eq!(
+            vec![
+                (Intensity, 0),
+                (Red, 0),
+                (Green, 0),
+                (Blue, 0),
+                (White, 0),
+                (Shutter, 0),
+                (Effect, 0),
+                (Effect, 0)
+            ],
+            attributes(import.profile.mode("8-channel").unwrap())
+        );
+
+        let import = ofl::import(SPOT, "Generic").unwrap();
+        assert!(import.warnings.is_empty());
+        assert_eq!(
+            vec![
+                (Pan, 0),
+                (Pan, 1),
+                (Tilt, 0),
+                (Tilt, 1),
+                (Speed, 0),
+                (Intensity, 0),
+                (Intensity, 1),
+                (Shutter, 0),
+                (ColorWheel, 0),
+                (Gobo, 0),
+                (GoboRotation, 0),
+                (Prism, 0),
+                (Focus, 0),
+                (Other, 0),
+                (Control, 0)
+            ],
+            attributes(import.profile.mode("Extended").unwrap())
+        );
+        // 50%, 32768 of 16 bit and 255 of 8 bit
+        let defaults: Vec<u8> = import.profile.modes[1].channels[..8]
+            .iter()
+            .map(|x| x.default)
+            .collect();
+        assert_eq!(vec![128, 0, 128, 0, 0, 0, 0, 255], defaults);
+        assert_eq!(11, import.profile.mode("Basic").unwrap().footprint());
+    }
+
+    #[test]
+    fn ofl_reports_unsupported() {
+        let import = ofl::import(PIXEL_BAR, "Generic").unwrap();
+        // The matrix mode is skipped, unknown capabilities become Other
+        assert_eq!(1, import.profile.modes.len());
+        assert_eq!(
+            vec![
+                (Attribute::Intensity, 0),
+                (Attribute::Other, 0),
+                (Attribute::Other, 0)
+            ],
+            attributes(&import.profile.modes[0])
+        );
+
+        let mut warnings = import.warnings.clone();
+        warnings.sort();
+        assert_eq!(
+            vec![
+                "Channel 'Fog': unsupported capability 'Fog'",
+                "Channel 'Lime': unsupported capability 'ColorIntensity Lime'",
+                "Mode 'Pixels': matrix channels are not supported, skipped the mode",
+                "Template channels are not supported",
+            ],
+            warnings
+        );
+
+        assert!(matches!(
+            ofl::import("{\"name\": \"No modes\"}", "Generic"),
+            Err(OflError::Invalid(_))
+        ));
+        assert!(matches!(
+            ofl::import("[", "Generic"),
+            Err(OflError::Json(_))
+        ));
+
+        // The folder is the manufacturer
+        let dir = std::env::temp_dir().join("wovmoh_ofl").join("acme");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("rgbw-par.json");
+        std::fs::write(&path, RGBW_PAR).unwrap();
+        let import = ofl::load(&path).unwrap();
+        assert_eq!("acme/RGBW Par", import.profile.key());
+        let _ = std::fs::remove_file(path);
+    }
+
+    // Hung at 5 m facing +z, so positive pan turns towards -x
+    fn hung() -> Mounting {
+        Mounting {
+            position: Vec3::new(0.0, 5.0, 0.0),
+            up: Vec3::new(0.0, -1.0, 0.0),
+            forward: Vec3::new(0.0, 0.0, 1.0),
+            pan_range: 540.0,
+            tilt_range: 270.0,
+        }
+    }
+
+    fn degrees(pan: f64, tilt: f64) -> PanTilt {
+        PanTilt {
+            pan: pan.to_radians(),
+            tilt: tilt.to_radians(),
+        }
+    }
+
+    fn assert_aim(expected: (f64, f64), angles: PanTilt) {
+        let (pan, tilt) = (angles.pan.to_degrees(), angles.tilt.to_degrees());
+        assert!(
+            (pan - expected.0).abs() < 1e-6 && (tilt - expected.1).abs() < 1e-6,
+            "expected {:?}, got ({}, {})",
+            expected,
+            pan,
+            tilt
+        );
+    }
+
+    // A point seen from the hung fixture at the azimuth (from forward, towards
+    // -x) and 45 degrees off straight down
+    fn around(azimuth: f64) -> Vec3 {
+        let azimuth = azimuth.to_radians();
+        Vec3::new(-azimuth.sin(), 4.0, azimuth.cos())
+    }
+
+    #[test]
+    fn look_at_known_orientations() {
+        let mounting = hung();
+        let centre = PanTilt::default();
+
+        // Straight down keeps the pan
+        let aim = mounting.look_at(Vec3::new(0.0, 0.0, 0.0), degrees(30.0, 10.0));
+        assert!(aim.reachable);
+        assert_aim((30.0, 0.0), aim.angles);
+        assert_eq!((32768, 32768), mounting.to_dmx(centre));
+
+        let aim = mounting.look_at(Vec3::new(0.0, 0.0, 5.0), centre);
+        assert_aim((0.0, 45.0), aim.angles);
+        // 0.5 + 45 / 270 of 65535
+        assert_eq!((32768, 43690), mounting.to_dmx(aim.angles));
+        assert_aim(
+            (90.0, 45.0),
+            mounting.look_at(around(90.0), degrees(0.0, 10.0)).angles,
+        );
+
+        // Standing on the floor, the horizon is the end of a 180 degree tilt
+        let standing = Mounting {
+            position: Vec3::new(0.0, 0.0, 0.0),
+            up: Vec3::new(0.0, 1.0, 0.0),
+            tilt_range: 180.0,
+            ..mounting
+        };
+        let aim = standing.look_at(Vec3::new(0.0, 0.0, 10.0), centre);
+        assert_aim((0.0, 90.0), aim.angles);
+        assert_eq!((32768, 65535), standing.to_dmx(aim.angles));
+        assert_eq!((65535, 0), standing.to_dmx(degrees(270.0, -90.0)));
+    }
+
+    #[test]
+    fn look_at_takes_shortest_pan() {
+        let mounting = hung();
+
+        // Behind at 170 degrees: from -200 across the wrap, not back round
+        let aim = mounting.look_at(around(170.0), degrees(-200.0, 45.0));
+        assert_aim((-190.0, 45.0), aim.angles);
+        let aim = mounting.look_at(around(170.0), degrees(100.0, 45.0));
+        assert_aim((170.0, 45.0), aim.angles);
+        // From the centre it is shorter to tilt the other way
+        let aim = mounting.look_at(around(170.0), PanTilt::default());
+        assert_aim((-10.0, -45.0), aim.angles);
+
+        // Above the fixture is out of a 180 degree tilt
+        let narrow = Mounting {
+            tilt_range: 180.0,
+            ..mounting
+        };
+        let aim = narrow.look_at(Vec3::new(0.0, 10.0, 0.0), PanTilt::default());
+        assert!(!aim.reachable);
+        assert_aim((0.0, 90.0), aim.angles);
+    }
+
+    #[test]
+    fn fixture_writes_fine_channels() {
+        let library = FixtureLibrary::new();
+        let mut patch = PatchTable::new();
+        let head = library.get("Generic/Moving Head").unwrap();
+        let id = patch.patch("Head", head, "Standard", 1, 10).unwrap();
+        let fixture = ResolvedFixture::resolve(patch.get(id).unwrap(), head).unwrap();
+
+        let mut output = DmxOutput::new();
+        fixture.write_defaults(&mut output);
+        assert_eq!(None, output.levels[8]);
+        assert_eq!(
+            vec![Some(128), Some(0), Some(128), Some(0)],
+            levels(&output, 10..=13)
+        );
+        assert_eq!(Some(255), output.levels[15]);
+
+        fixture.write(&mut output, Attribute::Pan, 1.0);
+        fixture.write(&mut output, Attribute::Tilt, 0.5);
+        fixture.write(&mut output, Attribute::Intensity, 0.2);
+        // No such channel
+        fixture.write(&mut output, Attribute::Zoom, 1.0);
+
+        assert_eq!(
+            vec![Some(255), Some(255), Some(128), Some(0), Some(0), Some(51)],
+            levels(&output, 10..=15)
+        );
+        assert_eq!(None, output.levels[21]);
+    }
+}
@@ -0,0 +1,331 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::Path;
+
+use serde_json::{Map, Value};
+
+use crate::fixtures::profile::{Attribute, Channel, FixtureProfile, Mode};
+
+// Open Fixture Library fixture definitions, see
+// https://github.com/OpenLightingProject/open-fixture-library/blob/master/docs/fixture-format.md
+
+#[derive(Debug)]
+pub enum OflError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    // A required property is missing or has the wrong type
+    Invalid(String),
+}
+
+impl Display for OflError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            OflError::Io(e) => write!(f, "{}", e),
+            OflError::Json(e) => write!(f, "Invalid fixture JSON: {}", e),
+            OflError::Invalid(what) => write!(f, "Invalid fixture: {}", what),
+        }
+    }
+}
+
+// What the profile couldn't express is reported rather than failing the import
+#[derive(Clone, Debug, PartialEq)]
+pub struct OflImport {
+    pub profile: FixtureProfile,
+    pub warnings: Vec<String>,
+}
+
+// OFL keeps fixtures in <manufacturer>/<fixture>.json, the folder names the manufacturer
+pub fn load(path: &Path) -> Result<OflImport, OflError> {
+    let json = fs::read_to_string(path).map_err(OflError::Io)?;
+    let manufacturer = path
+        .parent()
+        .and_then(|x| x.file_name())
+        .map_or("Unknown".to_string(), |x| x.to_string_lossy().to_string());
+    import(&json, &manufacturer)
+}
+
+pub fn import(json: &str, manufacturer: &str) -> Result<OflImport, OflError> {
+    let fixture: Value = serde_json::from_str(json).map_err(OflError::Json)?;
+    let fixture = fixture
+        .as_object()
+        .ok_or_else(|| OflError::Invalid("not an object".to_string()))?;
+    let name = fixture
+        .get("name")
+        .and_then(Value::as_str)
+        .ok_or_else(|| OflError::Invalid("no name".to_string()))?;
+
+    let mut warnings = Vec::new();
+    if fixture.contains_key("templateChannels") {
+        warnings.push("Template channels are not supported".to_string());
+    }
+
+    let wheels = fixture.get("wheels").and_then(Value::as_object);
+    let mut channels: HashMap<String, Channel> = HashMap::new();
+    if let Some(available) = fixture.get("availableChannels").and_then(Value::as_object) {
+        for (key, channel) in available {
+            import_channel(key, channel, wheels, &mut channels, &mut warnings);
+        }
+    }
+
+    let mut modes = Vec::new();
+    for mode in fixture
+        .get("modes")
+        .and_then(Value::as_array)
+        .ok_or_else(|| OflError::Invalid("no modes".to_string()))?
+    {
+        if let Some(mode) = import_mode(mode, &channels, &mut warnings) {
+            modes.push(mode);
+        }
+    }
+
+    Ok(OflImport {
+        profile: FixtureProfile {
+            manufacturer: manufacturer.to_string(),
+            name: name.to_string(),
+            modes,
+        },
+        warnings,
+    })
+}
+
+// Adds the channel and its fine aliases under their keys
+fn import_channel(
+    key: &str,
+    channel: &Value,
+    wheels: Option<&Map<String, Value>>,
+    channels: &mut HashMap<String, Channel>,
+    warnings: &mut Vec<String>,
+) {
+    let capabilities: Vec<&Value> = match (channel.get("capability"), channel.get("capabilities")) {
+        (Some(capability), _) => vec![capability],
+        (None, Some(Value::Array(capabilities))) => capabilities.iter().collect(),
+        _ => Vec::new(),
+    };
+
+    // The first capability with a known attribute decides, e.g. the colour
+    // macros after the wheel slots of a colour wheel channel don't
+    let mut attribute = None;
+    let mut unsupported: Vec<String> = Vec::new();
+    for capability in &capabilities {
+        match capability_attribute(key, capability, wheels) {
+            Some(Some(x)) => {
+                attribute.get_or_insert(x);
+            }
+            Some(None) => (),
+            None => {
+                let kind = capability_label(capability);
+                if !unsupported.contains(&kind) {
+                    unsupported.push(kind);
+                }
+            }
+        }
+    }
+    for kind in unsupported {
+        warnings.push(format!(
+            "Channel '{}': unsupported capability '{}'",
+            key, kind
+        ));
+    }
+    if capabilities.is_empty() {
+        warnings.push(format!("Channel '{}': no capabilities", key));
+    }
+    let attribute = attribute.unwrap_or(Attribute::Other);
+
+    let aliases: Vec<&str> = channel
+        .get("fineChannelAliases")
+        .and_then(Value::as_array)
+        .map(|x| x.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    if aliases.len() > 2 {
+        warnings.push(format!(
+            "Channel '{}': only two fine channels are supported",
+            key
+        ));
+    }
+
+    let aliases = &aliases[..aliases.len().min(2)];
+    let resolution = match channel.get("dmxValueResolution").and_then(Value::as_str) {
+        Some("8bit") => 1,
+        Some("16bit") => 2,
+        Some("24bit") => 3,
+        _ => aliases.len() + 1,
+    };
+    let default = default_bytes(channel.get("defaultValue"), resolution, aliases.len() + 1);
+
+    channels.insert(
+        key.to_string(),
+        Channel::new(key, attribute).with_default(default[0]),
+    );
+    for (i, alias) in aliases.iter().enumerate() {
+        channels.insert(
+            alias.to_string(),
+            Channel::fine(alias, attribute, i as u8 + 1).with_default(default[i + 1]),
+        );
+    }
+}
+
+// The type, with the colour for colours the profile has no attribute for
+fn capability_label(capability: &Value) -> String {
+    let kind = capability.get("type").and_then(Value::as_str).unwrap_or("");
+    match capability.get("color").and_then(Value::as_str) {
+        Some(color) => format!("{} {}", kind, color),
+        None => kind.to_string(),
+    }
+}
+
+// Some(None) for capabilities that don't tell what the channel is, None
+// for the ones the profile has no attribute for
+fn capability_attribute(
+    key: &str,
+    capability: &Value,
+    wheels: Option<&Map<String, Value>>,
+) -> Option<Option<Attribute>> {
+    let kind = capability.get("type").and_then(Value::as_str).unwrap_or("");
+    let attribute = match kind {
+        "NoFunction" => return Some(None),
+        "Intensity" => Attribute::Intensity,
+        "ColorIntensity" => {
+            return color_attribute(capability.get("color").and_then(Value::as_str)?).map(Some)
+        }
+        "ColorPreset" => Attribute::ColorWheel,
+        "ColorTemperature" => Attribute::ColorTemperature,
+        "WheelSlot" | "WheelShake" | "WheelSlotRotation" | "WheelRotation" => {
+            let wheel = capability
+                .get("wheel")
+                .and_then(Value::as_str)
+                .unwrap_or(key);
+            let gobo = is_gobo_wheel(wheel, wheels);
+            match (gobo, kind) {
+                (true, "WheelSlotRotation" | "WheelRotation") => Attribute::GoboRotation,
+                (true, _) => Attribute::Gobo,
+                (false, _) => Attribute::ColorWheel,
+            }
+        }
+        "Pan" => Attribute::Pan,
+        "Tilt" => Attribute::Tilt,
+        "PanTiltSpeed" | "Speed" => Attribute::Speed,
+        "ShutterStrobe" => match capability.get("shutterEffect").and_then(Value::as_str) {
+            Some("Open" | "Closed") => Attribute::Shutter,
+            _ => Attribute::Strobe,
+        },
+        "StrobeSpeed" | "StrobeDuration" => Attribute::Strobe,
+        "Zoom" => Attribute::Zoom,
+        "Focus" => Attribute::Focus,
+        "Iris" | "IrisEffect" => Attribute::Iris,
+        "Frost" | "FrostEffect" => Attribute::Frost,
+        "Prism" | "PrismRotation" => Attribute::Prism,
+        "Effect" | "EffectSpeed" | "EffectDuration" | "EffectParameter" | "SoundSensitivity" => {
+            Attribute::Effect
+        }
+        "Maintenance" => Attribute::Control,
+        "Generic" => Attribute::Other,
+        _ => return None,
+    };
+    Some(Some(attribute))
+}
+
+fn color_attribute(color: &str) -> Option<Attribute> {
+    Some(match color {
+        "Red" => Attribute::Red,
+        "Green" => Attribute::Green,
+        "Blue" => Attribute::Blue,
+        "White" | "Warm White" | "Cold White" => Attribute::White,
+        "Amber" => Attribute::Amber,
+        "UV" => Attribute::Uv,
+        "Cyan" => Attribute::Cyan,
+        "Magenta" => Attribute::Magenta,
+        "Yellow" => Attribute::Yellow,
+        // Lime, Indigo...
+        _ => return None,
+    })
+}
+
+// By the slot types of the wheel, or its name when it isn't described
+fn is_gobo_wheel(wheel: &str, wheels: Option<&Map<String, Value>>) -> bool {
+    let slots = wheels
+        .and_then(|x| x.get(wheel))
+        .and_then(|x| x.get("slots"))
+        .and_then(Value::as_array);
+
+    match slots {
+        Some(slots) => slots.iter().any(|x| {
+            matches!(
+                x.get("type").and_then(Value::as_str),
+                Some("Gobo" | "AnimationGoboStart")
+            )
+        }),
+        None => wheel.to_lowercase().contains("gobo"),
+    }
+}
+
+// Coarse then fine bytes of a default given as a DMX value of resolution
+// bytes, or as a percentage
+fn default_bytes(value: Option<&Value>, resolution: usize, bytes: usize) -> Vec<u8> {
+    let max = |bytes: usize| (1u64 << (8 * bytes)) - 1;
+
+    let level = match value {
+        Some(Value::Number(n)) => {
+            let level = n.as_u64().unwrap_or(0).min(max(resolution));
+            // Scaled up to the channel's resolution, 255 of 8 bit is 65535 of 16
+            if resolution < bytes {
+                level * max(bytes) / max(resolution)
+            } else {
+                level >> (8 * (resolution - bytes))
+            }
+        }
+        Some(Value::String(s)) => s
+            .strip_suffix('%')
+            .and_then(|x| x.trim().parse::<f64>().ok())
+            .map_or(0, |x| {
+                { (x / 100.0).clamp(0.0, 1.0) * max(bytes) as f64 }.round() as u64
+            }),
+        _ => 0,
+    };
+
+    (0..bytes)
+        .map(|i| (level >> (8 * (bytes - 1 - i))) as u8)
+        .collect()
+}
+
+fn import_mode(
+    mode: &Value,
+    channels: &HashMap<String, Channel>,
+    warnings: &mut Vec<String>,
+) -> Option<Mode> {
+    let name = mode
+        .get("name")
+        .and_then(Value::as_str)
+        .unwrap_or("Default");
+
+    let mut mode_channels = Vec::new();
+    for channel in mode.get("channels").and_then(Value::as_array)? {
+        match channel {
+            // Unused channel
+            Value::Null => mode_channels.push(Channel::new("Unused", Attribute::Other)),
+            Value::String(key) => match channels.get(key) {
+                Some(channel) => mode_channels.push(channel.clone()),
+                None => {
+                    warnings.push(format!(
+                        "Mode '{}': unknown channel '{}', skipped the mode",
+                        name, key
+                    ));
+                    return None;
+                }
+            },
+            // Matrix inserts, the footprint can't be known without the matrix
+            _ => {
+                warnings.push(format!(
+                    "Mode '{}': matrix channels are not supported, skipped the mode",
+                    name
+                ));
+                return None;
+            }
+        }
+    }
+
+    Some(Mode {
+        name: name.to_string(),
+        channels: mode_channels,
+    })
+}
@@ -0,0 +1,204 @@
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+use crate::dmx::{PortAddress, UNIVERSE_SIZE};
+use crate::fixtures::group::GroupId;
+use crate::fixtures::profile::{FixtureProfile, Mode};
+
+pub type FixtureId = u32;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PatchedFixture {
+    pub id: FixtureId,
+    pub name: String,
+    // FixtureProfile::key
+    pub profile: String,
+    pub mode: String,
+    pub universe: PortAddress,
+    // First channel, from 1
+    pub address: u16,
+    // Channels taken, from the mode
+    pub footprint: u16,
+}
+
+impl PatchedFixture {
+    // Last channel taken, wider than an address so a fixture patched near
+    // the top of u16 doesn't overflow
+    pub fn end(&self) -> u32 {
+        self.address as u32 + self.footprint.max(1) as u32 - 1
+    }
+
+    // Between channel 1 and the end of the universe
+    pub fn in_range(&self) -> bool {
+        self.address >= 1 && self.end() <= UNIVERSE_SIZE as u32
+    }
+
+    pub fn overlaps(&self, other: &PatchedFixture) -> bool {
+        self.universe == other.universe
+            && self.address as u32 <= other.end()
+            && other.address as u32 <= self.end()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PatchError {
+    UnknownProfile(String),
+    UnknownMode(String),
+    UnknownFixture(FixtureId),
+    UnknownGroup(GroupId),
+    // The fixture wouldn't fit between 1 and 512
+    OutOfRange(u16),
+    Overlap(FixtureId),
+}
+
+impl Display for PatchError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            PatchError::UnknownProfile(key) => write!(f, "Unknown fixture profile '{}'", key),
+            PatchError::UnknownMode(mode) => write!(f, "Unknown mode '{}'", mode),
+            PatchError::UnknownFixture(id) => write!(f, "Unknown fixture {}", id),
+            PatchError::UnknownGroup(id) => write!(f, "Unknown group {}", id),
+            PatchError::OutOfRange(address) => {
+                write!(f, "Address {} doesn't fit in a universe", address)
+            }
+            PatchError::Overlap(id) => write!(f, "Overlaps fixture {}", id),
+        }
+    }
+}
+
+// Where every fixture instance sits in the DMX address space
+#[derive(Clone, Debug, Default)]
+pub struct PatchTable {
+    fixtures: Vec<PatchedFixture>,
+    next_id: FixtureId,
+}
+
+impl PatchTable {
+    pub fn new() -> PatchTable {
+        PatchTable::default()
+    }
+
+    pub fn fixtures(&self) -> &[PatchedFixture] {
+        &self.fixtures
+    }
+
+    pub fn get(&self, id: FixtureId) -> Option<&PatchedFixture> {
+        self.fixtures.iter().find(|x| x.id == id)
+    }
+
+    pub fn patch(
+        &mut self,
+        name: &str,
+        profile: &FixtureProfile,
+        mode: &str,
+        universe: PortAddress,
+        address: u16,
+    ) -> Result<FixtureId, PatchError> {
+        let footprint = find_mode(profile, mode)?.footprint();
+
+        let fixture = PatchedFixture {
+            id: self.next_id,
+            name: name.to_string(),
+            profile: profile.key(),
+            mode: mode.to_string(),
+            universe,
+            address,
+            footprint,
+        };
+        self.check(&fixture)?;
+
+        self.next_id += 1;
+        self.fixtures.push(fixture);
+        Ok(self.next_id - 1)
+    }
+
+    pub fn unpatch(&mut self, id: FixtureId) {
+        self.fixtures.retain(|x| x.id != id);
+    }
+
+    pub fn move_to(
+        &mut self,
+        id: FixtureId,
+        universe: PortAddress,
+        address: u16,
+    ) -> Result<(), PatchError> {
+        let mut fixture = self
+            .get(id)
+            .cloned()
+            .ok_or(PatchError::UnknownFixture(id))?;
+        fixture.universe = universe;
+        fixture.address = address;
+        self.check(&fixture)?;
+
+        if let Some(x) = self.fixtures.iter_mut().find(|x| x.id == id) {
+            *x = fixture;
+        }
+        Ok(())
+    }
+
+    // Would the fixture fit where it is, ignoring itself
+    pub fn check(&self, fixture: &PatchedFixture) -> Result<(), PatchError> {
+        if !fixture.in_range() {
+            return Err(PatchError::OutOfRange(fixture.address));
+        }
+
+        match self
+            .fixtures
+            .iter()
+            .find(|x| x.id != fixture.id && x.overlaps(fixture))
+        {
+            Some(x) => Err(PatchError::Overlap(x.id)),
+            None => Ok(()),
+        }
+    }
+
+    // Pairs of fixtures sharing channels, e.g. in a project edited by hand
+    pub fn overlaps(&self) -> Vec<(FixtureId, FixtureId)> {
+        let mut overlaps = Vec::new();
+        for (i, a) in self.fixtures.iter().enumerate() {
+            for b in &self.fixtures[i + 1..] {
+                if a.overlaps(b) {
+                    overlaps.push((a.id, b.id));
+                }
+            }
+        }
+        overlaps
+    }
+
+    // Fixtures that don't fit in their universe, e.g. in a project edited by hand
+    pub fn out_of_range(&self) -> Vec<FixtureId> {
+        self.fixtures
+            .iter()
+            .filter(|x| !x.in_range())
+            .map(|x| x.id)
+            .collect()
+    }
+
+    // Footprints of the fixtures of a profile after it was replaced. Returns
+    // the fixtures whose mode it no longer has, they keep their footprint.
+    pub fn update_profile(&mut self, profile: &FixtureProfile) -> Vec<FixtureId> {
+        let key = profile.key();
+        let mut missing = Vec::new();
+        for fixture in self.fixtures.iter_mut().filter(|x| x.profile == key) {
+            match profile.mode(&fixture.mode) {
+                Some(mode) => fixture.footprint = mode.footprint(),
+                None => missing.push(fixture.id),
+            }
+        }
+        missing
+    }
+
+    // Loaded as they are, overlaps and fixtures out of range included, so
+    // nothing in a project is lost
+    pub fn set_fixtures(&mut self, fixtures: Vec<PatchedFixture>) {
+        self.next_id = fixtures.iter().map(|x| x.id + 1).max().unwrap_or(0);
+        self.fixtures = fixtures;
+    }
+}
+
+pub fn find_mode<'a>(profile: &'a FixtureProfile, mode: &str) -> Result<&'a Mode, PatchError> {
+    profile
+        .mode(mode)
+        .ok_or_else(|| PatchError::UnknownMode(mode.to_string()))
+}
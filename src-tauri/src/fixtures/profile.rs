@@ -0,0 +1,195 @@
+use serde::{Deserialize, Serialize};
+
+// What a channel controls. Fine channels share the attribute of their coarse
+// channel, see Channel::fine.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Attribute {
+    Intensity,
+    Red,
+    Green,
+    Blue,
+    White,
+    Amber,
+    Uv,
+    Cyan,
+    Magenta,
+    Yellow,
+    ColorWheel,
+    ColorTemperature,
+    Pan,
+    Tilt,
+    Gobo,
+    GoboRotation,
+    Prism,
+    Strobe,
+    Shutter,
+    Zoom,
+    Focus,
+    Iris,
+    Frost,
+    Speed,
+    Effect,
+    Control,
+    // Anything else, left at its default
+    Other,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Channel {
+    pub name: String,
+    pub attribute: Attribute,
+    // 0 for the coarse channel, 1 for fine, 2 for ultra fine
+    #[serde(default)]
+    pub fine: u8,
+    // Level while nothing drives the attribute
+    #[serde(default)]
+    pub default: u8,
+}
+
+impl Channel {
+    pub fn new(name: &str, attribute: Attribute) -> Channel {
+        Channel {
+            name: name.to_string(),
+            attribute,
+            fine: 0,
+            default: 0,
+        }
+    }
+
+    pub fn fine(name: &str, attribute: Attribute, fine: u8) -> Channel {
+        Channel {
+            fine,
+            ..Channel::new(name, attribute)
+        }
+    }
+
+    pub fn with_default(self, default: u8) -> Channel {
+        Channel { default, ..self }
+    }
+}
+
+// Channels in DMX order from the start address
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Mode {
+    pub name: String,
+    pub channels: Vec<Channel>,
+}
+
+impl Mode {
+    pub fn footprint(&self) -> u16 {
+        self.channels.len() as u16
+    }
+
+    pub fn has(&self, attribute: Attribute) -> bool {
+        self.channels.iter().any(|x| x.attribute == attribute)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FixtureProfile {
+    pub manufacturer: String,
+    pub name: String,
+    pub modes: Vec<Mode>,
+}
+
+impl FixtureProfile {
+    // How the patch refers to the profile
+    pub fn key(&self) -> String {
+        format!("{}/{}", self.manufacturer, self.name)
+    }
+
+    pub fn mode(&self, name: &str) -> Option<&Mode> {
+        self.modes.iter().find(|x| x.name == name)
+    }
+}
+
+fn generic(name: &str, modes: Vec<Mode>) -> FixtureProfile {
+    FixtureProfile {
+        manufacturer: "Generic".to_string(),
+        name: name.to_string(),
+        modes,
+    }
+}
+
+fn mode(name: &str, channels: Vec<Channel>) -> Mode {
+    Mode {
+        name: name.to_string(),
+        channels,
+    }
+}
+
+// Always available, imported profiles come on top
+pub fn builtin_profiles() -> Vec<FixtureProfile> {
+    use Attribute::*;
+
+    vec![
+        generic(
+            "Dimmer",
+            vec![
+                mode("8 bit", vec![Channel::new("Dimmer", Intensity)]),
+                mode(
+                    "16 bit",
+                    vec![
+                        Channel::new("Dimmer", Intensity),
+                        Channel::fine("Dimmer fine", Intensity, 1),
+                    ],
+                ),
+            ],
+        ),
+        generic(
+            "RGB",
+            vec![mode(
+                "3 channel",
+                vec![
+                    Channel::new("Red", Red),
+                    Channel::new("Green", Green),
+                    Channel::new("Blue", Blue),
+                ],
+            )],
+        ),
+        generic(
+            "RGBW",
+            vec![
+                mode(
+                    "4 channel",
+                    vec![
+                        Channel::new("Red", Red),
+                        Channel::new("Green", Green),
+                        Channel::new("Blue", Blue),
+                        Channel::new("White", White),
+                    ],
+                ),
+                mode(
+                    "5 channel",
+                    vec![
+                        Channel::new("Dimmer", Intensity),
+                        Channel::new("Red", Red),
+                        Channel::new("Green", Green),
+                        Channel::new("Blue", Blue),
+                        Channel::new("White", White),
+                    ],
+                ),
+            ],
+        ),
+        generic(
+            "Moving Head",
+            vec![mode(
+                "Standard",
+                vec![
+                    Channel::new("Pan", Pan).with_default(128),
+                    Channel::fine("Pan fine", Pan, 1),
+                    Channel::new("Tilt", Tilt).with_default(128),
+                    Channel::fine("Tilt fine", Tilt, 1),
+                    Channel::new("Speed", Speed),
+                    Channel::new("Dimmer", Intensity),
+                    // Open
+                    Channel::new("Shutter", Shutter).with_default(255),
+                    Channel::new("Red", Red),
+                    Channel::new("Green", Green),
+                    Channel::new("Blue", Blue),
+                    Channel::new("Gobo", Gobo),
+                ],
+            )],
+        ),
+    ]
+}
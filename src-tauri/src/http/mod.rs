@@ -0,0 +1,134 @@
+pub mod server;
+
+use std::collections::HashMap;
+
+use crate::types::data_type::DataValue;
+use crate::types::vector::Vec3;
+
+// Latest value PUT to an input name
+#[derive(Clone, Debug, PartialEq)]
+pub struct HttpInput {
+    pub value: DataValue,
+    // Increases with every request, across all names
+    pub serial: u64,
+}
+
+pub type HttpInputs = HashMap<String, HttpInput>;
+
+// null fires a Trigger, [x, y, z] is a Vector3
+pub fn value_from_json(json: &serde_json::Value) -> Option<DataValue> {
+    match json {
+        serde_json::Value::Null => Some(DataValue::Trigger(true)),
+        serde_json::Value::Bool(b) => Some(DataValue::Boolean(*b)),
+        serde_json::Value::Number(n) => n.as_f64().map(DataValue::Number),
+        serde_json::Value::Array(a) if a.len() == 3 => {
+            let n: Vec<f64> = a.iter().filter_map(|x| x.as_f64()).collect();
+            (n.len() == 3).then(|| DataValue::Vector3(Vec3::new(n[0], n[1], n[2])))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::{SocketAddr, TcpStream};
+
+    use super::server::HttpServer;
+    use super::*;
+
+    fn request(addr: SocketAddr, method: &str, path: &str, body: &str) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}",
+            method,
+            path,
+            body.len(),
+            body
+        )
+        .unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response.lines().next().unwrap_or("").to_string()
+    }
+
+    #[test]
+    fn values_from_json() {
+        let value = |s: &str| value_from_json(&serde_json::from_str(s).unwrap());
+
+        assert_eq!(Some(DataValue::Number(0.5)), value("0.5"));
+        assert_eq!(Some(DataValue::Boolean(true)), value("true"));
+        assert_eq!(Some(DataValue::Trigger(true)), value("null"));
+        assert_eq!(
+            Some(DataValue::Vector3(Vec3::new(1.0, 2.0, 3.0))),
+            value("[1, 2, 3]")
+        );
+        assert_eq!(None, value("[1, 2]"));
+        assert_eq!(None, value("[1, \"a\", 3]"));
+        assert_eq!(None, value("\"text\""));
+    }
+
+    #[test]
+    fn server_loopback() {
+        let server = HttpServer::bind("127.0.0.1:0").unwrap();
+        let addr = server.local_addr();
+
+        assert_eq!(
+            "HTTP/1.1 204 No Content",
+            request(addr, "PUT", "/inputs/house/level", "0.5")
+        );
+        assert_eq!(
+            "HTTP/1.1 204 No Content",
+            request(addr, "POST", "/inputs/go", "null")
+        );
+        assert_eq!(
+            "HTTP/1.1 400 Bad Request",
+            request(addr, "PUT", "/inputs/house/level", "loud")
+        );
+        assert_eq!(
+            "HTTP/1.1 405 Method Not Allowed",
+            request(addr, "GET", "/inputs/go", "")
+        );
+        assert_eq!("HTTP/1.1 404 Not Found", request(addr, "PUT", "/", "1"));
+
+        let values = server.snapshot();
+        assert_eq!(2, values.len());
+        assert_eq!(DataValue::Number(0.5), values["house/level"].value);
+        assert_eq!(DataValue::Trigger(true), values["go"].value);
+        assert!(values["go"].serial > values["house/level"].serial);
+    }
+
+    #[test]
+    fn server_rejects_long_lines() {
+        let server = HttpServer::bind("127.0.0.1:0").unwrap();
+        let addr = server.local_addr();
+
+        // The answer may be lost to a reset, the rest of the request is never read
+        let path = format!("/inputs/{}", "a".repeat(10_000));
+        let mut stream = TcpStream::connect(addr).unwrap();
+        let _ = write!(
+            stream,
+            "PUT {} HTTP/1.1\r\nContent-Length: 1\r\n\r\n1",
+            path
+        );
+        let mut response = String::new();
+        let _ = stream.read_to_string(&mut response);
+        assert!(response.is_empty() || response.starts_with("HTTP/1.1 414"));
+
+        let header = format!("X-Long: {}", "a".repeat(10_000));
+        let mut stream = TcpStream::connect(addr).unwrap();
+        let _ = write!(stream, "PUT /inputs/a HTTP/1.1\r\n{}\r\n\r\n", header);
+        let mut response = String::new();
+        let _ = stream.read_to_string(&mut response);
+        assert!(response.is_empty() || response.starts_with("HTTP/1.1 431"));
+
+        // Still answering
+        assert_eq!(
+            "HTTP/1.1 204 No Content",
+            request(addr, "PUT", "/inputs/house/level", "0.5")
+        );
+        assert_eq!(1, server.snapshot().len());
+    }
+}
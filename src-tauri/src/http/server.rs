@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::http::{value_from_json, HttpInput, HttpInputs};
+
+pub const HTTP_INPUT_PORT: u16 = 8088;
+// Environment variable with the address to listen on instead of localhost
+pub const HTTP_INPUT_ADDR_VAR: &str = "WOVMOH_HTTP_INPUT";
+
+// How often the accept loop checks whether it should stop
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+const READ_TIMEOUT: Duration = Duration::from_secs(2);
+const MAX_BODY: usize = 64 * 1024;
+// Longest request or header line, and most header lines, a request may have
+const MAX_LINE: usize = 8 * 1024;
+const MAX_HEADERS: usize = 64;
+
+struct Shared {
+    values: Mutex<HttpInputs>,
+    serial: AtomicU64,
+    running: AtomicBool,
+}
+
+// Minimal HTTP endpoint for scripts and web pages, e.g.
+//   curl -X PUT localhost:8088/inputs/house/level -d 0.5
+// Bodies are JSON: numbers, booleans, [x, y, z] or null to fire a trigger.
+pub struct HttpServer {
+    local_addr: SocketAddr,
+    shared: Arc<Shared>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl HttpServer {
+    pub fn bind(addr: impl ToSocketAddrs) -> io::Result<HttpServer> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+
+        let local_addr = listener.local_addr()?;
+        let shared = Arc::new(Shared {
+            values: Mutex::new(HashMap::new()),
+            serial: AtomicU64::new(0),
+            running: AtomicBool::new(true),
+        });
+
+        let thread = {
+            let shared = shared.clone();
+            thread::Builder::new()
+                .name("http-input".to_string())
+                .spawn(move || accept_loop(listener, shared))?
+        };
+
+        Ok(HttpServer {
+            local_addr,
+            shared,
+            thread: Some(thread),
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    pub fn snapshot(&self) -> HttpInputs {
+        self.shared.values.lock().unwrap().clone()
+    }
+}
+
+impl Drop for HttpServer {
+    fn drop(&mut self) {
+        self.shared.running.store(false, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn accept_loop(listener: TcpListener, shared: Arc<Shared>) {
+    while shared.running.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                // Requests are tiny, handling them in turn is enough
+                let _ = handle(stream, &shared);
+            }
+            Err(_) => thread::sleep(POLL_INTERVAL),
+        }
+    }
+}
+
+fn handle(stream: TcpStream, shared: &Shared) -> io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let status = read_request(&mut reader, shared)?;
+
+    let mut stream = stream;
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        status
+    )
+}
+
+// Stores the value of the request, returning the status to answer with
+fn read_request(reader: &mut impl BufRead, shared: &Shared) -> io::Result<&'static str> {
+    let Some(request_line) = read_line(reader)? else {
+        return Ok("414 URI Too Long");
+    };
+
+    let mut content_length = 0;
+    let mut headers = 0;
+    loop {
+        let Some(line) = read_line(reader)? else {
+            return Ok("431 Request Header Fields Too Large");
+        };
+        if line.trim().is_empty() {
+            break;
+        }
+        headers += 1;
+        if headers > MAX_HEADERS {
+            return Ok("431 Request Header Fields Too Large");
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("");
+    let path = parts.next().unwrap_or("");
+
+    Ok(match path.strip_prefix("/inputs/") {
+        Some(name) if !name.is_empty() => {
+            if method != "PUT" && method != "POST" {
+                "405 Method Not Allowed"
+            } else if content_length > MAX_BODY {
+                "413 Payload Too Large"
+            } else {
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body)?;
+                store(shared, name, &body)
+            }
+        }
+        _ => "404 Not Found",
+    })
+}
+
+// None when the line is longer than MAX_LINE, the rest is left unread
+fn read_line(reader: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut line = String::new();
+    reader.by_ref().take(MAX_LINE as u64).read_line(&mut line)?;
+    Ok((line.len() < MAX_LINE || line.ends_with('\n')).then_some(line))
+}
+
+fn store(shared: &Shared, name: &str, body: &[u8]) -> &'static str {
+    let value = serde_json::from_slice::<serde_json::Value>(body)
+        .ok()
+        .and_then(|x| value_from_json(&x));
+
+    match value {
+        Some(value) => {
+            let serial = shared.serial.fetch_add(1, Ordering::Relaxed) + 1;
+            shared
+                .values
+                .lock()
+                .unwrap()
+                .insert(name.to_string(), HttpInput { value, serial });
+            "204 No Content"
+        }
+        None => "400 Bad Request",
+    }
+}
@@ -1,14 +1,39 @@
+use std::net::{Ipv4Addr, SocketAddr};
+
 // Tauri Imports
 use tauri::async_runtime::Mutex;
-use tauri::Manager;
+use tauri::{Emitter, Manager};
 
+use crate::artnet::packet::ARTNET_PORT;
+use crate::artnet::receiver::ArtnetReceiver;
+use crate::http::server::{HttpServer, HTTP_INPUT_ADDR_VAR, HTTP_INPUT_PORT};
+use crate::managers::cues_manager::CuesManager;
+use crate::managers::fixtures_manager::FixturesManager;
 use crate::managers::graph_manager::GraphManager;
+use crate::managers::inputs_manager::InputsManager;
+use crate::managers::outputs_manager::OutputsManager;
+use crate::midi::device::open_devices;
+use crate::midi::port::SharedPort;
+use crate::osc::server::{OscClient, OscServer, OSC_IN_PORT, OSC_OUT_PORT};
 
 pub mod api;
+pub mod artnet;
 pub mod core;
+pub mod cues;
+pub mod dmx;
 pub mod dto;
+pub mod expression;
+pub mod fixtures;
+pub mod http;
 pub mod managers;
+pub mod midi;
 pub mod nodes;
+pub mod osc;
+pub mod outputs;
+pub mod project;
+pub mod runtime;
+pub mod sacn;
+pub mod timeline;
 pub mod types;
 
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
@@ -24,10 +49,131 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             greet,
             api::core::add_graph,
-            api::core::get_graph_dto
+            api::core::get_graph_dto,
+            api::core::set_node_text,
+            api::core::edit_graph,
+            api::core::begin_graph_transaction,
+            api::core::end_graph_transaction,
+            api::core::undo,
+            api::core::redo,
+            api::core::copy_nodes,
+            api::core::paste_nodes,
+            api::core::duplicate_nodes,
+            api::artnet::get_artnet_nodes,
+            api::artnet::poll_artnet_nodes,
+            api::osc::get_osc_target,
+            api::osc::set_osc_target,
+            api::midi::get_midi_ports,
+            api::midi::midi_learn,
+            api::midi::cancel_midi_learn,
+            api::inputs::get_input_channels,
+            api::outputs::get_outputs,
+            api::outputs::add_output_interface,
+            api::outputs::remove_output_interface,
+            api::outputs::set_output_route,
+            api::outputs::remove_output_route,
+            api::outputs::set_universe_merge,
+            api::outputs::remove_universe_merge,
+            api::outputs::get_channel_info,
+            api::fixtures::get_fixture_profiles,
+            api::fixtures::import_ofl_fixture,
+            api::fixtures::get_patch,
+            api::fixtures::patch_fixture,
+            api::fixtures::unpatch_fixture,
+            api::fixtures::move_fixture,
+            api::fixtures::add_fixture_group,
+            api::fixtures::set_fixture_group,
+            api::fixtures::remove_fixture_group,
+            api::cues::get_cue_lists,
+            api::cues::add_cue_list,
+            api::cues::remove_cue_list,
+            api::cues::set_cue,
+            api::cues::remove_cue,
+            api::cues::cue_go,
+            api::cues::cue_back,
+            api::cues::cue_goto,
+            api::cues::cue_release,
+            api::snapshots::get_snapshots,
+            api::snapshots::capture_snapshot,
+            api::snapshots::recall_snapshot,
+            api::snapshots::remove_snapshot,
+            api::timeline::get_timeline,
+            api::timeline::set_keyframe,
+            api::timeline::remove_keyframe,
+            api::timeline::remove_timeline_track,
+            api::timeline::timeline_play,
+            api::timeline::timeline_pause,
+            api::timeline::timeline_seek,
+            api::timeline::set_timeline_loop,
+            api::compounds::get_compounds,
+            api::compounds::add_compound,
+            api::compounds::set_compound,
+            api::compounds::rename_compound,
+            api::compounds::remove_compound,
+            api::compounds::group_nodes,
+            api::compounds::expand_compound,
+            api::project::save_project,
+            api::project::load_project
         ])
         .setup(|app| {
             app.manage(Mutex::new(GraphManager::new()));
+
+            let mut inputs = InputsManager::new();
+
+            // Another program may already own the Art-Net port, run without input then
+            match ArtnetReceiver::bind(("0.0.0.0", ARTNET_PORT)) {
+                Ok(receiver) => {
+                    let handle = app.handle().clone();
+                    receiver.set_nodes_listener(Box::new(move |nodes| {
+                        let nodes: Vec<_> = nodes.iter().map(|x| x.to_dto()).collect();
+                        let _ = handle.emit("artnet-nodes", nodes);
+                    }));
+
+                    if let Err(e) = receiver
+                        .start_discovery(SocketAddr::from((Ipv4Addr::BROADCAST, ARTNET_PORT)))
+                    {
+                        eprintln!("Art-Net discovery failed: {}", e);
+                    }
+
+                    inputs.set_artnet(receiver);
+                }
+                Err(e) => eprintln!("Art-Net input disabled, port {}: {}", ARTNET_PORT, e),
+            }
+
+            match OscServer::bind(("0.0.0.0", OSC_IN_PORT)) {
+                Ok(server) => inputs.set_osc(server),
+                Err(e) => eprintln!("OSC input disabled, port {}: {}", OSC_IN_PORT, e),
+            }
+
+            // Only this machine by default, e.g. WOVMOH_HTTP_INPUT=0.0.0.0:8088 opens it up
+            let http_addr = std::env::var(HTTP_INPUT_ADDR_VAR)
+                .unwrap_or_else(|_| format!("127.0.0.1:{}", HTTP_INPUT_PORT));
+            match HttpServer::bind(http_addr.as_str()) {
+                Ok(server) => inputs.set_http(server),
+                Err(e) => eprintln!("HTTP input disabled, {}: {}", http_addr, e),
+            }
+
+            // MIDI devices connected at startup, read by the inputs and sent to
+            // by MIDI nodes and MIDI output interfaces
+            let mut outputs = OutputsManager::new();
+            for device in open_devices() {
+                let has_output = device.has_output();
+                let port = SharedPort::new(Box::new(device));
+                inputs.midi_mut().add_port(Box::new(port.clone()));
+                if has_output {
+                    outputs.add_midi_port(Box::new(port));
+                }
+            }
+
+            app.manage(Mutex::new(inputs));
+            app.manage(Mutex::new(outputs));
+            app.manage(Mutex::new(FixturesManager::new()));
+            app.manage(Mutex::new(CuesManager::new()));
+
+            let target = SocketAddr::from((Ipv4Addr::LOCALHOST, OSC_OUT_PORT));
+            app.manage(Mutex::new(OscClient::new(target)?));
+
+            runtime::start(app.handle().clone());
             Ok(())
         })
         .run(tauri::generate_context!())
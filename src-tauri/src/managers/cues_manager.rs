@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+use crate::cues::playback::{CueOutputs, Playback};
+use crate::cues::{Cue, CueList, CueListId};
+use crate::dto::cue_list_dto::CueListDto;
+
+// Cue lists and their playbacks. Ticked every frame before the graphs, which
+// read the output through CueList nodes.
+#[derive(Default)]
+pub struct CuesManager {
+    lists: Vec<CueList>,
+    playbacks: HashMap<CueListId, Playback>,
+    next_id: CueListId,
+}
+
+impl CuesManager {
+    pub fn new() -> CuesManager {
+        CuesManager::default()
+    }
+
+    pub fn add_list(&mut self, name: &str) -> CueListId {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.lists.push(CueList::new(id, name));
+        self.playbacks.insert(id, Playback::new());
+        id
+    }
+
+    pub fn remove_list(&mut self, id: CueListId) {
+        self.lists.retain(|x| x.id != id);
+        self.playbacks.remove(&id);
+    }
+
+    pub fn list(&self, id: CueListId) -> Option<&CueList> {
+        self.lists.iter().find(|x| x.id == id)
+    }
+
+    // Replaces the cue with the same number, false for an unknown list
+    pub fn set_cue(&mut self, list: CueListId, cue: Cue) -> bool {
+        match self.lists.iter_mut().find(|x| x.id == list) {
+            Some(list) => {
+                list.set_cue(cue);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn remove_cue(&mut self, list: CueListId, number: f64) -> bool {
+        self.lists
+            .iter_mut()
+            .find(|x| x.id == list)
+            .is_some_and(|x| x.remove_cue(number))
+    }
+
+    pub fn go(&mut self, list: CueListId) -> bool {
+        self.with_playback(list, |playback, list| playback.go(list))
+    }
+
+    pub fn back(&mut self, list: CueListId) -> bool {
+        self.with_playback(list, |playback, list| playback.back(list))
+    }
+
+    pub fn goto(&mut self, list: CueListId, number: f64) -> bool {
+        self.with_playback(list, |playback, list| playback.goto(list, number))
+    }
+
+    pub fn release(&mut self, list: CueListId) -> bool {
+        self.with_playback(list, |playback, list| {
+            playback.release(list);
+            true
+        })
+    }
+
+    pub fn tick(&mut self, delta: f64) {
+        for list in &self.lists {
+            if let Some(playback) = self.playbacks.get_mut(&list.id) {
+                playback.tick(list, delta);
+            }
+        }
+    }
+
+    pub fn output(&self) -> CueOutputs {
+        self.playbacks
+            .iter()
+            .map(|(id, playback)| (*id, playback.output()))
+            .collect()
+    }
+
+    pub fn config(&self) -> Vec<CueList> {
+        self.lists.clone()
+    }
+
+    // Every playback starts released
+    pub fn set_config(&mut self, lists: Vec<CueList>) {
+        self.next_id = lists.iter().map(|x| x.id + 1).max().unwrap_or(0);
+        self.playbacks = lists.iter().map(|x| (x.id, Playback::new())).collect();
+        self.lists = lists;
+    }
+
+    pub fn to_dto(&self) -> Vec<CueListDto> {
+        self.lists
+            .iter()
+            .map(|x| {
+                let output = self.playbacks.get(&x.id).map(|x| x.output());
+                CueListDto {
+                    id: x.id,
+                    name: x.name.clone(),
+                    cues: x.cues.clone(),
+                    current: output.as_ref().and_then(|x| x.cue),
+                    fading: output.is_some_and(|x| x.fading),
+                }
+            })
+            .collect()
+    }
+
+    fn with_playback(
+        &mut self,
+        list: CueListId,
+        f: impl FnOnce(&mut Playback, &CueList) -> bool,
+    ) -> bool {
+        match (
+            self.lists.iter().find(|x| x.id == list),
+            self.playbacks.get_mut(&list),
+        ) {
+            (Some(list), Some(playback)) => f(playback, list),
+            _ => false,
+        }
+    }
+}
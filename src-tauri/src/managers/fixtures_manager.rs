@@ -0,0 +1,205 @@
+use crate::dmx::PortAddress;
+use crate::dto::fixtures_dto::PatchDto;
+use crate::fixtures::group::{FixtureGroup, GroupId};
+use crate::fixtures::patch::{FixtureId, PatchError, PatchTable};
+use crate::fixtures::profile::FixtureProfile;
+use crate::fixtures::{FixtureConfig, FixtureLibrary, FixtureMap, ResolvedFixture};
+
+// Known fixture profiles and where each fixture instance is patched. Graphs
+// see the patch through the FixtureMap pushed into the GraphManager.
+#[derive(Default)]
+pub struct FixturesManager {
+    library: FixtureLibrary,
+    patch: PatchTable,
+    groups: Vec<FixtureGroup>,
+    next_group_id: GroupId,
+}
+
+impl FixturesManager {
+    pub fn new() -> FixturesManager {
+        FixturesManager::default()
+    }
+
+    pub fn library(&self) -> &FixtureLibrary {
+        &self.library
+    }
+
+    // Replaces the profile with the same key, its patched fixtures take the
+    // new footprints. Returns what that broke: fixtures that now overlap and
+    // fixtures whose mode is gone.
+    pub fn add_profile(&mut self, profile: FixtureProfile) -> Vec<String> {
+        let overlaps = self.patch.overlaps();
+        let missing = self.patch.update_profile(&profile);
+        self.library.add(profile);
+
+        let mut warnings: Vec<String> = missing
+            .iter()
+            .map(|id| format!("Fixture {} lost its mode and isn't output", id))
+            .collect();
+        for (a, b) in self.patch.overlaps() {
+            if !overlaps.contains(&(a, b)) {
+                warnings.push(format!("Fixture {} now overlaps fixture {}", a, b));
+            }
+        }
+        warnings
+    }
+
+    pub fn patch_table(&self) -> &PatchTable {
+        &self.patch
+    }
+
+    pub fn patch(
+        &mut self,
+        name: &str,
+        profile: &str,
+        mode: &str,
+        universe: PortAddress,
+        address: u16,
+    ) -> Result<FixtureId, PatchError> {
+        let profile = self
+            .library
+            .get(profile)
+            .ok_or_else(|| PatchError::UnknownProfile(profile.to_string()))?;
+        self.patch.patch(name, profile, mode, universe, address)
+    }
+
+    // Also leaves every group
+    pub fn unpatch(&mut self, id: FixtureId) {
+        self.patch.unpatch(id);
+        for group in &mut self.groups {
+            group.fixtures.retain(|x| *x != id);
+        }
+    }
+
+    pub fn move_to(
+        &mut self,
+        id: FixtureId,
+        universe: PortAddress,
+        address: u16,
+    ) -> Result<(), PatchError> {
+        self.patch.move_to(id, universe, address)
+    }
+
+    pub fn groups(&self) -> &[FixtureGroup] {
+        &self.groups
+    }
+
+    // Members in order, unpatched ids are rejected
+    pub fn add_group(
+        &mut self,
+        name: &str,
+        fixtures: Vec<FixtureId>,
+    ) -> Result<GroupId, PatchError> {
+        self.check_members(&fixtures)?;
+
+        let id = self.next_group_id;
+        self.next_group_id += 1;
+        self.groups.push(FixtureGroup {
+            id,
+            name: name.to_string(),
+            fixtures,
+        });
+        Ok(id)
+    }
+
+    // Replaces the members, e.g. after reordering them
+    pub fn set_group(&mut self, id: GroupId, fixtures: Vec<FixtureId>) -> Result<(), PatchError> {
+        self.check_members(&fixtures)?;
+
+        let group = self
+            .groups
+            .iter_mut()
+            .find(|x| x.id == id)
+            .ok_or(PatchError::UnknownGroup(id))?;
+        group.fixtures = fixtures;
+        Ok(())
+    }
+
+    pub fn remove_group(&mut self, id: GroupId) {
+        self.groups.retain(|x| x.id != id);
+    }
+
+    fn check_members(&self, fixtures: &[FixtureId]) -> Result<(), PatchError> {
+        match fixtures.iter().find(|x| self.patch.get(**x).is_none()) {
+            Some(id) => Err(PatchError::UnknownFixture(*id)),
+            None => Ok(()),
+        }
+    }
+
+    // Patched fixtures whose profile or mode is missing, see fixture_map
+    pub fn unresolved(&self) -> Vec<FixtureId> {
+        self.patch
+            .fixtures()
+            .iter()
+            .filter(|x| {
+                let profile = self.library.get(&x.profile);
+                profile.and_then(|profile| profile.mode(&x.mode)).is_none()
+            })
+            .map(|x| x.id)
+            .collect()
+    }
+
+    // What Fixture and Group nodes write through. Fixtures whose profile or
+    // mode is missing are left out, see unresolved, as are fixtures out of
+    // range.
+    pub fn fixture_map(&self) -> FixtureMap {
+        let fixtures = self
+            .patch
+            .fixtures()
+            .iter()
+            .filter(|x| x.in_range())
+            .filter_map(|x| {
+                let profile = self.library.get(&x.profile)?;
+                ResolvedFixture::resolve(x, profile).map(|resolved| (x.id, resolved))
+            })
+            .collect();
+        let groups = self
+            .groups
+            .iter()
+            .map(|x| (x.id, x.fixtures.clone()))
+            .collect();
+
+        FixtureMap { fixtures, groups }
+    }
+
+    pub fn config(&self) -> FixtureConfig {
+        let mut profiles: Vec<FixtureProfile> = Vec::new();
+        for fixture in self.patch.fixtures() {
+            if let Some(profile) = self.library.get(&fixture.profile) {
+                if !profiles.contains(profile) {
+                    profiles.push(profile.clone());
+                }
+            }
+        }
+
+        FixtureConfig {
+            profiles,
+            patch: self.patch.fixtures().to_vec(),
+            groups: self.groups.clone(),
+        }
+    }
+
+    // Profiles in the config replace the library's ones with the same key
+    pub fn set_config(&mut self, config: FixtureConfig) {
+        for profile in config.profiles {
+            self.library.add(profile);
+        }
+        self.patch.set_fixtures(config.patch);
+        // Footprints from the modes, not from what the file says
+        for profile in self.library.profiles() {
+            self.patch.update_profile(profile);
+        }
+        self.next_group_id = config.groups.iter().map(|x| x.id + 1).max().unwrap_or(0);
+        self.groups = config.groups;
+    }
+
+    pub fn to_dto(&self) -> PatchDto {
+        PatchDto {
+            fixtures: self.patch.fixtures().to_vec(),
+            groups: self.groups.clone(),
+            overlaps: self.patch.overlaps(),
+            unresolved: self.unresolved(),
+            out_of_range: self.patch.out_of_range(),
+        }
+    }
+}
@@ -1,16 +1,42 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::core::compound::{CompoundDef, CompoundId, CompoundLibrary};
+use crate::core::fragment::Fragment;
+use crate::core::frame_io::ExternalInputs;
 use crate::core::graph::*;
+use crate::core::node::NodeId;
+use crate::core::snapshot::{Snapshot, SnapshotId};
+use crate::cues::playback::CueOutputs;
+use crate::dmx::{DmxInputs, DmxOutputs};
+use crate::fixtures::FixtureMap;
+use crate::midi::message::MidiMessage;
+use crate::midi::MidiInputs;
+use crate::osc::codec::OscMessage;
+use crate::osc::OscInputs;
 
 pub struct GraphManager {
     graphs: Vec<Graph>,
+    fixtures: Arc<FixtureMap>,
+    next_snapshot_id: SnapshotId,
+    compounds: Arc<CompoundLibrary>,
 }
 
 impl GraphManager {
     pub fn new() -> GraphManager {
-        GraphManager { graphs: Vec::new() }
+        GraphManager {
+            graphs: Vec::new(),
+            fixtures: Arc::new(FixtureMap::default()),
+            next_snapshot_id: 0,
+            compounds: Arc::new(CompoundLibrary::default()),
+        }
     }
 
     pub fn add_graph(&mut self) {
-        self.graphs.push(Graph::new(self.graphs.len()));
+        let mut graph = Graph::new(self.graphs.len());
+        graph.set_fixtures(self.fixtures.clone());
+        graph.set_compounds(self.compounds.clone());
+        self.graphs.push(graph);
     }
 
     pub fn get_graph(&self, id: GraphId) -> &Graph {
@@ -20,4 +46,217 @@ impl GraphManager {
     pub fn get_graph_mut(&mut self, id: GraphId) -> &mut Graph {
         &mut self.graphs[id]
     }
+
+    // Of every graph, by id
+    pub fn snapshots(&self) -> Vec<Snapshot> {
+        let mut snapshots: Vec<Snapshot> = self
+            .graphs
+            .iter()
+            .flat_map(|x| x.snapshots().iter().cloned())
+            .collect();
+        snapshots.sort_by_key(|x| x.id);
+        snapshots
+    }
+
+    // Input values of the graph, of the given nodes or all of them. None for
+    // an unknown graph.
+    pub fn capture_snapshot(
+        &mut self,
+        graph: GraphId,
+        name: &str,
+        nodes: Option<&[NodeId]>,
+    ) -> Option<SnapshotId> {
+        let id = self.next_snapshot_id;
+        let target = self.graphs.get_mut(graph)?;
+        let values = target.capture_snapshot(nodes);
+        target.add_snapshot(Snapshot {
+            id,
+            name: name.to_string(),
+            graph,
+            values,
+        });
+        self.next_snapshot_id += 1;
+        Some(id)
+    }
+
+    // Fade in seconds, 0 for at once. False for an unknown snapshot.
+    pub fn recall_snapshot(&mut self, id: SnapshotId, fade: f64) -> bool {
+        self.graphs
+            .iter_mut()
+            .any(|x| x.recall_stored_snapshot(id, fade))
+    }
+
+    pub fn remove_snapshot(&mut self, id: SnapshotId) {
+        for graph in &mut self.graphs {
+            graph.remove_snapshot(id);
+        }
+    }
+
+    // From a project, after its graphs. Snapshots of graphs that don't exist
+    // are left out.
+    pub fn set_snapshots(&mut self, snapshots: Vec<Snapshot>) {
+        self.next_snapshot_id = snapshots.iter().map(|x| x.id + 1).max().unwrap_or(0);
+        for (id, graph) in self.graphs.iter_mut().enumerate() {
+            graph.set_snapshots(
+                snapshots
+                    .iter()
+                    .filter(|x| x.graph == id)
+                    .cloned()
+                    .collect(),
+            );
+        }
+    }
+
+    // Every graph as a fragment of all its nodes, for a project
+    pub fn graphs_config(&self) -> Vec<GraphConfig> {
+        self.graphs.iter().map(|x| x.config()).collect()
+    }
+
+    // From a project, replacing the open graphs. The nodes get the ids they
+    // were saved with, so snapshots still fit. Projects saved before graphs
+    // were leave the open ones alone.
+    pub fn set_graphs(&mut self, graphs: Vec<GraphConfig>) {
+        if graphs.is_empty() {
+            return;
+        }
+        self.graphs.clear();
+        for config in graphs {
+            self.add_graph();
+            let graph = self.graphs.last_mut().unwrap();
+            // A broken graph loads empty rather than not at all
+            let _ = graph.insert_fragment(&config.nodes, (0.0, 0.0));
+            graph.clear_history();
+            *graph.timeline_mut() = config.timeline;
+        }
+    }
+
+    pub fn compounds(&self) -> &CompoundLibrary {
+        &self.compounds
+    }
+
+    pub fn add_compound(&mut self, name: &str, fragment: Fragment) -> Result<CompoundId, String> {
+        let id = Arc::make_mut(&mut self.compounds).add(name, fragment)?;
+        self.share_compounds();
+        Ok(id)
+    }
+
+    // Every Compound node using it picks the edit up on its next frame
+    pub fn set_compound(&mut self, id: CompoundId, fragment: Fragment) -> Result<(), String> {
+        Arc::make_mut(&mut self.compounds).set(id, fragment)?;
+        self.share_compounds();
+        Ok(())
+    }
+
+    pub fn rename_compound(&mut self, id: CompoundId, name: &str) -> bool {
+        let renamed = Arc::make_mut(&mut self.compounds).rename(id, name);
+        self.share_compounds();
+        renamed
+    }
+
+    pub fn remove_compound(&mut self, id: CompoundId) {
+        Arc::make_mut(&mut self.compounds).remove(id);
+        self.share_compounds();
+    }
+
+    // From a project
+    pub fn set_compounds(&mut self, defs: Vec<CompoundDef>) {
+        Arc::make_mut(&mut self.compounds).set_config(defs);
+        self.share_compounds();
+    }
+
+    // Makes a compound of the nodes and puts a Compound node using it in
+    // their place. The graph is left as it was when the library rejects it.
+    pub fn group_nodes(
+        &mut self,
+        graph: GraphId,
+        nodes: &[NodeId],
+        name: &str,
+    ) -> Result<CompoundId, String> {
+        let id = self.compounds.next_id();
+        let Some(graph) = self.graphs.get_mut(graph) else {
+            return Err(format!("Unknown graph {}", graph));
+        };
+        let fragment = graph.group_nodes(nodes, id, Instant::now())?;
+        self.add_compound(name, fragment)
+    }
+
+    fn share_compounds(&mut self) {
+        for graph in &mut self.graphs {
+            graph.set_compounds(self.compounds.clone());
+        }
+    }
+
+    // Advances and processes every graph
+    pub fn tick(&mut self, delta: f64) {
+        for graph in &mut self.graphs {
+            graph.tick(delta);
+        }
+    }
+
+    pub fn set_external_input(&mut self, inputs: ExternalInputs) {
+        let inputs = Arc::new(inputs);
+        for graph in &mut self.graphs {
+            graph.set_external_input(inputs.clone());
+        }
+    }
+
+    // Shared, the OutputsManager merges the same universes after the frame
+    pub fn set_dmx_input(&mut self, dmx_in: Arc<DmxInputs>) {
+        for graph in &mut self.graphs {
+            graph.set_dmx_input(dmx_in.clone());
+        }
+    }
+
+    // Levels from the DmxOutput nodes of every graph, indexed by graph id
+    pub fn take_dmx_output(&mut self) -> Vec<DmxOutputs> {
+        self.graphs
+            .iter_mut()
+            .map(|x| x.take_dmx_output())
+            .collect()
+    }
+
+    // Patch shared by every graph, new graphs included
+    pub fn set_fixtures(&mut self, fixtures: FixtureMap) {
+        self.fixtures = Arc::new(fixtures);
+        for graph in &mut self.graphs {
+            graph.set_fixtures(self.fixtures.clone());
+        }
+    }
+
+    pub fn set_cues(&mut self, cues: CueOutputs) {
+        let cues = Arc::new(cues);
+        for graph in &mut self.graphs {
+            graph.set_cues(cues.clone());
+        }
+    }
+
+    pub fn set_osc_input(&mut self, osc_in: OscInputs) {
+        let osc_in = Arc::new(osc_in);
+        for graph in &mut self.graphs {
+            graph.set_osc_input(osc_in.clone());
+        }
+    }
+
+    // Messages from the OscOut nodes of every graph
+    pub fn take_osc_output(&mut self) -> Vec<OscMessage> {
+        self.graphs
+            .iter_mut()
+            .flat_map(|x| x.take_osc_output())
+            .collect()
+    }
+
+    pub fn set_midi_input(&mut self, midi_in: MidiInputs) {
+        let midi_in = Arc::new(midi_in);
+        for graph in &mut self.graphs {
+            graph.set_midi_input(midi_in.clone());
+        }
+    }
+
+    // Messages from the MidiCCOut nodes of every graph
+    pub fn take_midi_output(&mut self) -> Vec<MidiMessage> {
+        self.graphs
+            .iter_mut()
+            .flat_map(|x| x.take_midi_output())
+            .collect()
+    }
 }
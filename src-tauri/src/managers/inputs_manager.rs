@@ -0,0 +1,270 @@
+use std::collections::HashMap;
+use std::time::Instant;
+
+use crate::artnet::receiver::{ArtnetReceiver, STALE_TIMEOUT};
+use crate::core::frame_io::{ExternalInputs, ExternalValue};
+use crate::dmx::{DmxInputs, PortAddress, UNIVERSE_SIZE};
+use crate::dto::input_channel_dto::InputChannelDto;
+use crate::http::server::HttpServer;
+use crate::managers::midi_manager::MidiManager;
+use crate::midi::message::MidiMessage;
+use crate::midi::MidiInputs;
+use crate::osc::codec::OscArg;
+use crate::osc::server::OscServer;
+use crate::osc::OscInputs;
+use crate::types::curve;
+use crate::types::data_type::DataValue;
+use crate::types::vector::Vec3;
+
+pub struct InputChannel {
+    pub value: DataValue,
+    // See ExternalValue::serial
+    pub serial: u64,
+    pub last_received: Instant,
+}
+
+// Owns every input source and turns what they receive into named channels:
+//   artnet/<port address>/<channel 1-512>    Number 0..1
+//   osc/<address>                            Number, Boolean, Vector3 or Trigger
+//   midi/<channel 1-16>/note/<note>          Boolean, held
+//   midi/<channel 1-16>/cc/<controller>      Number 0..1
+//   midi/<channel 1-16>/program              Number
+//   midi/<channel 1-16>/pitchbend            Number -1..1
+//   midi/start, midi/continue, midi/stop     Trigger
+//   http/<name>                              see HttpServer
+#[derive(Default)]
+pub struct InputsManager {
+    artnet: Option<ArtnetReceiver>,
+    osc: Option<OscServer>,
+    http: Option<HttpServer>,
+    midi: MidiManager,
+
+    channels: HashMap<String, InputChannel>,
+    serial: u64,
+
+    // What was already taken from each source
+    artnet_seen: HashMap<PortAddress, Instant>,
+    // Channel names of every universe received, made once
+    artnet_names: HashMap<PortAddress, Vec<String>>,
+    osc_seen: u64,
+    http_seen: u64,
+}
+
+impl InputsManager {
+    pub fn new() -> InputsManager {
+        InputsManager::default()
+    }
+
+    pub fn set_artnet(&mut self, receiver: ArtnetReceiver) {
+        self.artnet = Some(receiver);
+    }
+
+    pub fn artnet(&self) -> Option<&ArtnetReceiver> {
+        self.artnet.as_ref()
+    }
+
+    pub fn set_osc(&mut self, server: OscServer) {
+        self.osc = Some(server);
+    }
+
+    pub fn set_http(&mut self, server: HttpServer) {
+        self.http = Some(server);
+    }
+
+    pub fn midi(&self) -> &MidiManager {
+        &self.midi
+    }
+
+    pub fn midi_mut(&mut self) -> &mut MidiManager {
+        &mut self.midi
+    }
+
+    // Takes everything new from the sources, called at the start of each frame
+    pub fn poll(&mut self) {
+        let now = Instant::now();
+
+        if let Some(artnet) = &self.artnet {
+            for (port_address, frame) in artnet.frames() {
+                if self.artnet_seen.get(&port_address) == Some(&frame.received) {
+                    continue;
+                }
+                self.artnet_seen.insert(port_address, frame.received);
+
+                let names = self.artnet_names.entry(port_address).or_insert_with(|| {
+                    (1..=UNIVERSE_SIZE)
+                        .map(|x| format!("artnet/{}/{}", port_address, x))
+                        .collect()
+                });
+                for (name, level) in names.iter().zip(frame.data) {
+                    self.serial += 1;
+                    let channel = InputChannel {
+                        value: DataValue::Number(curve::from_u8(level)),
+                        serial: self.serial,
+                        last_received: now,
+                    };
+                    // Every packet sets every channel, without a new name each time
+                    match self.channels.get_mut(name) {
+                        Some(x) => *x = channel,
+                        None => {
+                            self.channels.insert(name.clone(), channel);
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(osc) = &self.osc {
+            let mut messages: Vec<(String, Vec<OscArg>, u64)> = osc
+                .snapshot()
+                .into_iter()
+                .filter(|(_, input)| input.serial > self.osc_seen)
+                .map(|(address, input)| (address, input.args, input.serial))
+                .collect();
+            // Oldest first, so serials keep the order things arrived in
+            messages.sort_by_key(|x| x.2);
+
+            for (address, args, serial) in messages {
+                self.osc_seen = self.osc_seen.max(serial);
+                if let Some(value) = osc_value(&args) {
+                    self.set(format!("osc{}", address), value, now);
+                }
+            }
+        }
+
+        if let Some(http) = &self.http {
+            let mut values: Vec<(String, DataValue, u64)> = http
+                .snapshot()
+                .into_iter()
+                .filter(|(_, input)| input.serial > self.http_seen)
+                .map(|(name, input)| (name, input.value, input.serial))
+                .collect();
+            values.sort_by_key(|x| x.2);
+
+            for (name, value, serial) in values {
+                self.http_seen = self.http_seen.max(serial);
+                self.set(format!("http/{}", name), value, now);
+            }
+        }
+
+        for message in self.midi.poll() {
+            if let Some((name, value)) = midi_channel(&message) {
+                self.set(name, value, now);
+            }
+        }
+    }
+
+    // Sets a channel directly, e.g. from the UI
+    pub fn set(&mut self, name: String, value: DataValue, now: Instant) {
+        self.serial += 1;
+        self.channels.insert(
+            name,
+            InputChannel {
+                value,
+                serial: self.serial,
+                last_received: now,
+            },
+        );
+    }
+
+    pub fn channel(&self, name: &str) -> Option<&InputChannel> {
+        self.channels.get(name)
+    }
+
+    // Values for ExternalInput nodes
+    pub fn external_inputs(&self) -> ExternalInputs {
+        self.channels
+            .iter()
+            .map(|(name, channel)| {
+                (
+                    name.clone(),
+                    ExternalValue {
+                        value: channel.value,
+                        serial: channel.serial,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    pub fn dmx_inputs(&self) -> DmxInputs {
+        match &self.artnet {
+            Some(artnet) => artnet.snapshot(STALE_TIMEOUT),
+            None => DmxInputs::new(),
+        }
+    }
+
+    pub fn osc_inputs(&self) -> OscInputs {
+        match &self.osc {
+            Some(osc) => osc.snapshot(),
+            None => OscInputs::new(),
+        }
+    }
+
+    pub fn midi_inputs(&self) -> &MidiInputs {
+        self.midi.inputs()
+    }
+
+    // Monitor view, sorted by name
+    pub fn to_dto(&self) -> Vec<InputChannelDto> {
+        let mut channels: Vec<InputChannelDto> = self
+            .channels
+            .iter()
+            .map(|(name, channel)| InputChannelDto {
+                name: name.clone(),
+                value: channel.value,
+                last_received: channel.last_received.elapsed().as_millis() as u64,
+            })
+            .collect();
+        channels.sort_by(|a, b| a.name.cmp(&b.name));
+        channels
+    }
+}
+
+// No arguments is a Trigger, a single Bool a Boolean, three numbers a Vector3,
+// otherwise the first number. Messages with nothing numeric are skipped.
+fn osc_value(args: &[OscArg]) -> Option<DataValue> {
+    let numbers: Vec<f64> = args.iter().filter_map(|x| x.as_f64()).collect();
+
+    match args {
+        [] => Some(DataValue::Trigger(true)),
+        [OscArg::Bool(b)] => Some(DataValue::Boolean(*b)),
+        _ if numbers.len() == 3 => Some(DataValue::Vector3(Vec3::new(
+            numbers[0], numbers[1], numbers[2],
+        ))),
+        _ => numbers.first().map(|x| DataValue::Number(*x)),
+    }
+}
+
+fn midi_channel(message: &MidiMessage) -> Option<(String, DataValue)> {
+    let channel = message.channel().map(|x| x + 1);
+
+    match *message {
+        MidiMessage::NoteOn { note, .. } => Some((
+            format!("midi/{}/note/{}", channel?, note),
+            DataValue::Boolean(true),
+        )),
+        MidiMessage::NoteOff { note, .. } => Some((
+            format!("midi/{}/note/{}", channel?, note),
+            DataValue::Boolean(false),
+        )),
+        MidiMessage::ControlChange {
+            controller, value, ..
+        } => Some((
+            format!("midi/{}/cc/{}", channel?, controller),
+            DataValue::Number(value as f64 / 127.0),
+        )),
+        MidiMessage::ProgramChange { program, .. } => Some((
+            format!("midi/{}/program", channel?),
+            DataValue::Number(program as f64),
+        )),
+        MidiMessage::PitchBend { value, .. } => Some((
+            format!("midi/{}/pitchbend", channel?),
+            DataValue::Number((value as f64 - 8192.0) / 8192.0),
+        )),
+        MidiMessage::Start => Some(("midi/start".to_string(), DataValue::Trigger(true))),
+        MidiMessage::Continue => Some(("midi/continue".to_string(), DataValue::Trigger(true))),
+        MidiMessage::Stop => Some(("midi/stop".to_string(), DataValue::Trigger(true))),
+        // 24 per quarter note, too busy to be a channel
+        MidiMessage::Clock => None,
+    }
+}
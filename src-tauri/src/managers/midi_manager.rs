@@ -0,0 +1,201 @@
+use crate::core::graph::{Graph, GraphId};
+use crate::core::node::{NodeId, NodeKind};
+use crate::midi::message::{MidiMessage, MidiParser};
+use crate::midi::port::MidiPort;
+use crate::midi::MidiInputs;
+use crate::types::data_type::DataValue;
+
+struct PortEntry {
+    port: Box<dyn MidiPort>,
+    parser: MidiParser,
+    // Last receive or send failure, cleared when the port works again
+    error: Option<String>,
+}
+
+// Node waiting to be bound to the next suitable message
+#[derive(Copy, Clone)]
+pub struct LearnTarget {
+    pub graph_id: GraphId,
+    pub node_id: NodeId,
+    pub kind: NodeKind,
+    // Graph::renumbered when learn started
+    pub renumbered: u64,
+}
+
+impl LearnTarget {
+    // None for a node the graph doesn't have
+    pub fn new(graph: &Graph, graph_id: GraphId, node_id: NodeId) -> Option<LearnTarget> {
+        graph.has_node(node_id).then(|| LearnTarget {
+            graph_id,
+            node_id,
+            kind: graph.get_node(node_id).kind,
+            renumbered: graph.renumbered(),
+        })
+    }
+
+    // Still the node learn started on. Removing any node shifts the ids after
+    // it, so the id may now be another node or none at all.
+    pub fn is_valid(&self, graph: &Graph) -> bool {
+        graph.renumbered() == self.renumbered
+            && graph.has_node(self.node_id)
+            && graph.get_node(self.node_id).kind == self.kind
+    }
+}
+
+pub struct Learned {
+    pub target: LearnTarget,
+    pub message: MidiMessage,
+}
+
+#[derive(Default)]
+pub struct MidiManager {
+    ports: Vec<PortEntry>,
+    inputs: MidiInputs,
+    learn: Option<LearnTarget>,
+    learned: Option<Learned>,
+}
+
+impl MidiManager {
+    pub fn new() -> MidiManager {
+        MidiManager::default()
+    }
+
+    pub fn add_port(&mut self, port: Box<dyn MidiPort>) -> usize {
+        self.ports.push(PortEntry {
+            port,
+            parser: MidiParser::new(),
+            error: None,
+        });
+        self.ports.len() - 1
+    }
+
+    pub fn port_names(&self) -> Vec<String> {
+        self.ports
+            .iter()
+            .map(|x| x.port.name().to_string())
+            .collect()
+    }
+
+    pub fn port_error(&self, port: usize) -> Option<&String> {
+        self.ports[port].error.as_ref()
+    }
+
+    pub fn inputs(&self) -> &MidiInputs {
+        &self.inputs
+    }
+
+    // Only MIDI nodes can learn, returns false for anything else
+    pub fn learn(&mut self, target: LearnTarget) -> bool {
+        if !matches!(
+            target.kind,
+            NodeKind::MidiNoteIn | NodeKind::MidiCCIn | NodeKind::MidiCCOut
+        ) {
+            return false;
+        }
+        self.learn = Some(target);
+        true
+    }
+
+    pub fn cancel_learn(&mut self) {
+        self.learn = None;
+    }
+
+    // Ends learn mode once its node is gone, called with the graph of the
+    // target before every poll()
+    pub fn check_learn(&mut self, graph: &Graph) {
+        if self.learn.is_some_and(|x| !x.is_valid(graph)) {
+            self.learn = None;
+        }
+    }
+
+    pub fn learning(&self) -> Option<LearnTarget> {
+        self.learn
+    }
+
+    // Binding made by the last poll() for the node in learn mode
+    pub fn take_learned(&mut self) -> Option<Learned> {
+        self.learned.take()
+    }
+
+    // Reads every port into inputs(), returns the messages received
+    pub fn poll(&mut self) -> Vec<MidiMessage> {
+        let mut received = Vec::new();
+        self.inputs.clear_frame();
+
+        for entry in &mut self.ports {
+            let bytes = match entry.port.receive() {
+                Ok(bytes) => {
+                    entry.error = None;
+                    bytes
+                }
+                Err(e) => {
+                    entry.error = Some(e.to_string());
+                    continue;
+                }
+            };
+
+            for message in entry.parser.feed(&bytes) {
+                self.inputs.apply(&message);
+
+                if let Some(target) = self.learn {
+                    if accepts(target.kind, &message) {
+                        self.learn = None;
+                        self.learned = Some(Learned { target, message });
+                    }
+                }
+
+                received.push(message);
+            }
+        }
+
+        received
+    }
+
+    // Sends to every port
+    pub fn send(&mut self, messages: &[MidiMessage]) {
+        if messages.is_empty() {
+            return;
+        }
+
+        let bytes: Vec<u8> = messages.iter().flat_map(|x| x.encode()).collect();
+        for entry in &mut self.ports {
+            entry.error = entry.port.send(&bytes).err().map(|e| e.to_string());
+        }
+    }
+}
+
+fn accepts(kind: NodeKind, message: &MidiMessage) -> bool {
+    match kind {
+        NodeKind::MidiNoteIn => matches!(
+            message,
+            MidiMessage::NoteOn { .. } | MidiMessage::NoteOff { .. }
+        ),
+        NodeKind::MidiCCIn | NodeKind::MidiCCOut => {
+            matches!(message, MidiMessage::ControlChange { .. })
+        }
+        _ => false,
+    }
+}
+
+impl Learned {
+    // Sets the node's Channel and Note / Controller inputs from the message,
+    // unless the node is gone
+    pub fn apply(&self, graph: &mut Graph) {
+        if !self.target.is_valid(graph) {
+            return;
+        }
+        let number = match self.message {
+            MidiMessage::NoteOn { note, .. } | MidiMessage::NoteOff { note, .. } => note,
+            MidiMessage::ControlChange { controller, .. } => controller,
+            _ => return,
+        };
+        let channel = match self.message.channel() {
+            Some(channel) => channel,
+            None => return,
+        };
+
+        let inputs = graph.get_node(self.target.node_id).inputs.clone();
+        graph.set_input_value(inputs[0], DataValue::Number(channel as f64 + 1.0));
+        graph.set_input_value(inputs[1], DataValue::Number(number as f64));
+    }
+}
@@ -1 +1,443 @@
+pub mod cues_manager;
+pub mod fixtures_manager;
 pub mod graph_manager;
+pub mod inputs_manager;
+pub mod midi_manager;
+pub mod outputs_manager;
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use std::net::UdpSocket;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use super::cues_manager::CuesManager;
+    use super::graph_manager::GraphManager;
+    use super::inputs_manager::InputsManager;
+    use super::outputs_manager::OutputsManager;
+    use crate::core::graph::Graph;
+    use crate::core::history::GraphEdit;
+    use crate::core::node::{NodeKind, NodeUIState};
+    use crate::dmx::merge::{MergePolicy, MergeSource, UniverseMerge};
+    use crate::dmx::{port_address, DmxInput, DmxInputs, DmxOutputs, UNIVERSE_SIZE};
+    use crate::midi::port::VirtualPort;
+    use crate::osc::codec::{OscArg, OscMessage, OscPacket};
+    use crate::osc::server::OscServer;
+    use crate::outputs::serial::{ByteSink, MemorySink};
+    use crate::outputs::{InterfaceKind, InterfaceStatus, Route};
+    use crate::project::Project;
+    use crate::runtime::run_frame;
+    use crate::types::data_type::DataValue::*;
+    use crate::types::vector::Vec3;
+
+    #[test]
+    fn inputs_named_channels() {
+        let port = VirtualPort::new("Controller");
+        let mut inputs = InputsManager::new();
+        inputs.midi_mut().add_port(Box::new(port.clone()));
+
+        // Note on ch 1, CC 7 on ch 2, pitch bend centre on ch 1, start
+        port.inject(&[0x90, 64, 100, 0xB1, 7, 127, 0xE0, 0, 64, 0xFA]);
+        inputs.poll();
+        assert_eq!(
+            Boolean(true),
+            inputs.channel("midi/1/note/64").unwrap().value
+        );
+        assert_eq!(Number(1.0), inputs.channel("midi/2/cc/7").unwrap().value);
+        assert_eq!(
+            Number(0.0),
+            inputs.channel("midi/1/pitchbend").unwrap().value
+        );
+        assert_eq!(Trigger(true), inputs.channel("midi/start").unwrap().value);
+
+        // Note off keeps the channel, now false
+        let serial = inputs.channel("midi/1/note/64").unwrap().serial;
+        port.inject(&[0x80, 64, 0]);
+        inputs.poll();
+        let note = inputs.channel("midi/1/note/64").unwrap();
+        assert_eq!(Boolean(false), note.value);
+        assert!(note.serial > serial);
+        let serial = note.serial;
+
+        // Nothing new, nothing changes
+        inputs.poll();
+        assert_eq!(serial, inputs.channel("midi/1/note/64").unwrap().serial);
+
+        let names: Vec<String> = inputs.to_dto().into_iter().map(|x| x.name).collect();
+        assert_eq!(
+            vec![
+                "midi/1/note/64",
+                "midi/1/pitchbend",
+                "midi/2/cc/7",
+                "midi/start"
+            ],
+            names
+        );
+    }
+
+    #[test]
+    fn inputs_osc_channels() {
+        let server = OscServer::bind("127.0.0.1:0").unwrap();
+        let addr = server.local_addr();
+        let mut inputs = InputsManager::new();
+        inputs.set_osc(server);
+
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        for message in [
+            OscMessage::new("/1/fader1", vec![OscArg::Int(1)]),
+            OscMessage::new("/1/toggle1", vec![OscArg::Bool(true)]),
+            OscMessage::new(
+                "/xyz",
+                vec![OscArg::Float(1.0), OscArg::Float(2.0), OscArg::Float(3.0)],
+            ),
+            OscMessage::new("/go", vec![]),
+            OscMessage::new("/label", vec![OscArg::String("text".to_string())]),
+        ] {
+            socket
+                .send_to(&OscPacket::Message(message).encode(), addr)
+                .unwrap();
+        }
+
+        let start = Instant::now();
+        while inputs.channel("osc/go").is_none() {
+            assert!(
+                start.elapsed() < Duration::from_secs(2),
+                "No messages received"
+            );
+            thread::sleep(Duration::from_millis(5));
+            inputs.poll();
+        }
+
+        assert_eq!(Number(1.0), inputs.channel("osc/1/fader1").unwrap().value);
+        assert_eq!(
+            Boolean(true),
+            inputs.channel("osc/1/toggle1").unwrap().value
+        );
+        assert_eq!(
+            Vector3(Vec3::new(1.0, 2.0, 3.0)),
+            inputs.channel("osc/xyz").unwrap().value
+        );
+        assert_eq!(Trigger(true), inputs.channel("osc/go").unwrap().value);
+        assert!(inputs.channel("osc/label").is_none());
+    }
+
+    #[test]
+    fn frame_feeds_external_inputs() {
+        let ui_state = NodeUIState {
+            position: (0.0, 0.0),
+        };
+        let mut graphs = GraphManager::new();
+        graphs.add_graph();
+        let graph = graphs.get_graph_mut(0);
+        graph.add_node(NodeKind::ExternalInput, ui_state.clone());
+        graph.add_node(NodeKind::Counter, ui_state);
+        // Received -> Counter
+        graph.connect(1, 0);
+        graph.set_node_text(0, "ui/master");
+
+        let mut inputs = InputsManager::new();
+        let mut outputs = OutputsManager::new();
+        let mut cues = CuesManager::new();
+        run_frame(&mut graphs, &mut inputs, &mut outputs, &mut cues, 0.025);
+        assert_eq!(Number(0.0), graphs.get_graph(0).get_output_value(2));
+
+        inputs.set("ui/master".to_string(), Number(0.8), Instant::now());
+        run_frame(&mut graphs, &mut inputs, &mut outputs, &mut cues, 0.025);
+        run_frame(&mut graphs, &mut inputs, &mut outputs, &mut cues, 0.025);
+        assert_eq!(Number(0.8), graphs.get_graph(0).get_output_value(0));
+        assert_eq!(Number(1.0), graphs.get_graph(0).get_output_value(2));
+    }
+
+    #[test]
+    fn snapshots_follow_ids() {
+        let mut graphs = GraphManager::new();
+        graphs.add_graph();
+        let graph = graphs.get_graph_mut(0);
+        for value in [1.0, 2.0] {
+            let position = (0.0, 0.0);
+            let kind = NodeKind::ConstantNumber;
+            graph
+                .edit(GraphEdit::AddNode { kind, position }, Instant::now())
+                .unwrap();
+            let input = graph.node_count() - 1;
+            let value = Number(value);
+            graph
+                .edit(GraphEdit::SetInputValue { input, value }, Instant::now())
+                .unwrap();
+        }
+        let id = graphs.capture_snapshot(0, "Both", None).unwrap();
+
+        // The second node moves into the first's ids and keeps its value
+        let graph = graphs.get_graph_mut(0);
+        graph
+            .edit(GraphEdit::RemoveNode { node: 0 }, Instant::now())
+            .unwrap();
+        graph.set_input_value(0, Number(0.0));
+        assert!(graphs.recall_snapshot(id, 0.0));
+        assert_eq!(Number(2.0), graphs.get_graph(0).get_input_value(0));
+        assert_eq!(1, graphs.snapshots()[0].values.len());
+
+        // Undone, the first one's value is back in the snapshot
+        let graph = graphs.get_graph_mut(0);
+        assert!(graph.undo());
+        graph.set_input_value(0, Number(0.0));
+        graph.set_input_value(1, Number(0.0));
+        assert!(graphs.recall_snapshot(id, 0.0));
+        assert_eq!(Number(1.0), graphs.get_graph(0).get_input_value(0));
+        assert_eq!(Number(2.0), graphs.get_graph(0).get_input_value(1));
+
+        // Saved with the graphs they refer to
+        let project = Project {
+            graphs: graphs.graphs_config(),
+            snapshots: graphs.snapshots(),
+            ..Project::default()
+        };
+        let project = Project::from_json(&project.to_json().unwrap()).unwrap();
+        let mut loaded = GraphManager::new();
+        loaded.set_graphs(project.graphs);
+        loaded.set_snapshots(project.snapshots);
+        assert_eq!(2, loaded.get_graph(0).node_count());
+        assert!(!loaded.get_graph(0).history().can_undo());
+        loaded.get_graph_mut(0).set_input_value(1, Number(0.5));
+        assert!(loaded.recall_snapshot(id, 0.0));
+        assert_eq!(Number(2.0), loaded.get_graph(0).get_input_value(1));
+        assert_eq!(Some(id + 1), loaded.capture_snapshot(0, "Next", None));
+    }
+
+    // DmxOutput nodes writing (channel, value) to universe 1
+    fn add_dmx_outputs(graph: &mut Graph, levels: &[(f64, f64)]) {
+        for (i, (channel, value)) in levels.iter().enumerate() {
+            graph.add_node(
+                NodeKind::DmxOutput,
+                NodeUIState {
+                    position: (0.0, 0.0),
+                },
+            );
+            graph.set_input_value(i * 4, Number(1.0));
+            graph.set_input_value(i * 4 + 1, Number(*channel));
+            graph.set_input_value(i * 4 + 2, Number(*value));
+        }
+    }
+
+    #[test]
+    fn outputs_merge_and_route() {
+        let mut graphs = GraphManager::new();
+        graphs.add_graph();
+        graphs.add_graph();
+        add_dmx_outputs(graphs.get_graph_mut(0), &[(1.0, 1.0), (2.0, 0.2)]);
+        add_dmx_outputs(graphs.get_graph_mut(1), &[(2.0, 0.6), (3.0, 0.4)]);
+
+        let port = VirtualPort::new("Faders");
+        let mut outputs = OutputsManager::new();
+        outputs.add_midi_port(Box::new(port.clone()));
+        let faders = outputs.add_interface(
+            "Faders",
+            InterfaceKind::Midi {
+                port: "Faders".to_string(),
+            },
+        );
+        let missing = outputs.add_interface(
+            "Missing",
+            InterfaceKind::Midi {
+                port: "Missing".to_string(),
+            },
+        );
+        assert_eq!(Some(&InterfaceStatus::Idle), outputs.status(faders));
+        assert!(matches!(
+            outputs.status(missing),
+            Some(InterfaceStatus::Error(_))
+        ));
+
+        assert!(outputs.set_route(Route {
+            universe: 1,
+            interface: faders,
+            destination: 1,
+        }));
+        assert!(!outputs.set_route(Route {
+            universe: 1,
+            interface: 99,
+            destination: 1,
+        }));
+
+        graphs.tick(0.025);
+        outputs.process(graphs.take_dmx_output(), &DmxInputs::new());
+
+        // Highest level wins across graphs
+        let universe = outputs.universe(1).unwrap();
+        assert_eq!(&[255, 153, 102, 0], &universe[0..4]);
+        assert_eq!(Some(&InterfaceStatus::Ok), outputs.status(faders));
+        let sent = port.take_sent();
+        assert_eq!(&[0xB0, 0, 127, 0xB0, 1, 76, 0xB0, 2, 51], &sent[0..9]);
+
+        // Unrouted universes aren't sent, routed ones nothing wrote go dark
+        outputs.remove_route(1, faders);
+        outputs.set_route(Route {
+            universe: 2,
+            interface: faders,
+            destination: 1,
+        });
+        graphs.tick(0.025);
+        outputs.process(graphs.take_dmx_output(), &DmxInputs::new());
+        assert_eq!(vec![0xB0, 0, 0, 0xB0, 1, 0, 0xB0, 2, 0], port.take_sent());
+    }
+
+    #[test]
+    fn outputs_config_reopens_interfaces() {
+        let port = VirtualPort::new("Faders");
+        let mut outputs = OutputsManager::new();
+        outputs.add_midi_port(Box::new(port.clone()));
+        let faders = outputs.add_interface(
+            "Faders",
+            InterfaceKind::Midi {
+                port: "Faders".to_string(),
+            },
+        );
+        outputs.set_route(Route {
+            universe: 1,
+            interface: faders,
+            destination: 1,
+        });
+
+        let config = outputs.config();
+        outputs.remove_interface(faders);
+        assert!(outputs.config().routes.is_empty());
+
+        outputs.set_config(config.clone());
+        assert_eq!(config, outputs.config());
+        assert_eq!(Some(&InterfaceStatus::Idle), outputs.status(faders));
+        // Ids keep increasing past the loaded ones
+        let other = outputs.add_interface("Other", InterfaceKind::Sacn { target: None });
+        assert!(other > faders);
+
+        outputs.process(Vec::new(), &DmxInputs::new());
+        assert_eq!(UNIVERSE_SIZE * 3, port.take_sent().len());
+    }
+
+    #[test]
+    fn outputs_merge_received_universes() {
+        let mut graphs = GraphManager::new();
+        graphs.add_graph();
+        add_dmx_outputs(graphs.get_graph_mut(0), &[(1.0, 0.2), (2.0, 1.0)]);
+
+        let mut outputs = OutputsManager::new();
+        let mut merge = UniverseMerge::new(1, MergePolicy::Htp);
+        merge.input = Some(port_address(0, 0, 3));
+        outputs.set_merge(merge);
+
+        let mut data = [0; UNIVERSE_SIZE];
+        data[0] = 255;
+        data[2] = 10;
+        let mut dmx_in = DmxInputs::new();
+        dmx_in.insert(port_address(0, 0, 3), DmxInput { data, stale: false });
+
+        graphs.tick(0.025);
+        outputs.process(graphs.take_dmx_output(), &dmx_in);
+        assert_eq!(&[255, 255, 10], &outputs.universe(1).unwrap()[0..3]);
+        let info = outputs.inspect(1, 1);
+        assert_eq!(MergeSource::Input(3), info.sources[1].source);
+        assert!(info.sources[1].owner);
+
+        // Stale sources drop out
+        dmx_in.get_mut(&3).unwrap().stale = true;
+        graphs.tick(0.025);
+        outputs.process(graphs.take_dmx_output(), &dmx_in);
+        assert_eq!(&[51, 255, 0], &outputs.universe(1).unwrap()[0..3]);
+
+        outputs.remove_merge(1);
+        assert!(outputs.config().merge.is_empty());
+    }
+
+    #[test]
+    fn outputs_report_device_errors() {
+        let sink = MemorySink::new();
+        let plugged = Arc::new(AtomicBool::new(false));
+        let mut outputs = OutputsManager::new();
+        {
+            let sink = sink.clone();
+            let plugged = plugged.clone();
+            outputs.set_serial_opener(Box::new(move |port| match port {
+                "usb0" => Ok(Box::new(sink.clone()) as Box<dyn ByteSink>),
+                "usb1" if plugged.load(Ordering::Relaxed) => {
+                    Ok(Box::new(MemorySink::new()) as Box<dyn ByteSink>)
+                }
+                _ => Err(io::Error::from(io::ErrorKind::NotFound)),
+            }));
+        }
+        let widget = outputs.add_interface(
+            "Widget",
+            InterfaceKind::EnttecPro {
+                port: "usb0".to_string(),
+            },
+        );
+        let unplugged = outputs.add_interface(
+            "Unplugged",
+            InterfaceKind::OpenDmx {
+                port: "usb1".to_string(),
+            },
+        );
+        assert!(matches!(
+            outputs.status(unplugged),
+            Some(InterfaceStatus::Error(_))
+        ));
+        outputs.set_route(Route {
+            universe: 1,
+            interface: widget,
+            destination: 0,
+        });
+
+        // Written on the interface's own thread
+        outputs.process(Vec::new(), &DmxInputs::new());
+        let start = Instant::now();
+        while sink.take_events().is_empty() {
+            assert!(start.elapsed() < Duration::from_secs(2), "Nothing written");
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(Some(&InterfaceStatus::Ok), outputs.status(widget));
+
+        // Pulled out mid show, reported by a later frame
+        sink.set_error(Some(io::ErrorKind::BrokenPipe));
+        let mut graphs = GraphManager::new();
+        graphs.add_graph();
+        add_dmx_outputs(graphs.get_graph_mut(0), &[(1.0, 1.0)]);
+        graphs.tick(0.025);
+        let dmx_out = graphs.take_dmx_output();
+        let error = InterfaceStatus::Error(io::Error::from(io::ErrorKind::BrokenPipe).to_string());
+        process_until(&mut outputs, &dmx_out, widget, &error);
+
+        // Closed, and opened again a while later
+        sink.set_error(None);
+        outputs.process(dmx_out.to_vec(), &DmxInputs::new());
+        assert_eq!(Some(&error), outputs.status(widget));
+        outputs.reopen(Instant::now() + Duration::from_secs(5));
+        process_until(&mut outputs, &dmx_out, widget, &InterfaceStatus::Ok);
+
+        // Plugged in after it failed to open
+        plugged.store(true, Ordering::Relaxed);
+        outputs.reopen(Instant::now());
+        assert!(matches!(
+            outputs.status(unplugged),
+            Some(InterfaceStatus::Error(_))
+        ));
+        // Its last try was with the widget's
+        outputs.reopen(Instant::now() + Duration::from_secs(10));
+        assert_eq!(Some(&InterfaceStatus::Idle), outputs.status(unplugged));
+    }
+
+    fn process_until(
+        outputs: &mut OutputsManager,
+        dmx_out: &[DmxOutputs],
+        id: u32,
+        status: &InterfaceStatus,
+    ) {
+        let start = Instant::now();
+        while outputs.status(id) != Some(status) {
+            let now = outputs.status(id);
+            assert!(start.elapsed() < Duration::from_secs(2), "Still {:?}", now);
+            thread::sleep(Duration::from_millis(1));
+            outputs.process(dmx_out.to_vec(), &DmxInputs::new());
+        }
+    }
+}
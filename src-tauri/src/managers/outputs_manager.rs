@@ -0,0 +1,299 @@
+use std::collections::HashMap;
+use std::io;
+use std::time::{Duration, Instant};
+
+use crate::dmx::merge::{MergeSource, Merger, UniverseMerge};
+use crate::dmx::{DmxInputs, DmxOutput, DmxOutputs, PortAddress, UNIVERSE_SIZE};
+use crate::dto::channel_info_dto::ChannelInfoDto;
+use crate::dto::outputs_dto::{OutputInterfaceDto, OutputsDto};
+use crate::midi::port::{MidiPort, SharedPort};
+use crate::outputs::artnet::ArtnetOutput;
+use crate::outputs::enttec::EnttecProOutput;
+use crate::outputs::midi::MidiOutput;
+use crate::outputs::open_dmx::OpenDmxOutput;
+use crate::outputs::sacn::SacnOutput;
+use crate::outputs::serial::{open_serial_sink, ByteSink, SinkOpener};
+use crate::outputs::threaded::ThreadedOutput;
+use crate::outputs::{
+    HardwareInterface, InterfaceConfig, InterfaceId, InterfaceKind, InterfaceStatus, OutputConfig,
+    Route,
+};
+
+// How often an interface that failed is opened again
+const REOPEN_INTERVAL: Duration = Duration::from_secs(1);
+
+struct Interface {
+    config: InterfaceConfig,
+    // None when it couldn't be opened or its last send failed, the status
+    // says why
+    device: Option<Box<dyn HardwareInterface>>,
+    status: InterfaceStatus,
+    // When to try opening it again while there's no device
+    reopen_at: Instant,
+}
+
+// Mirror of the InputsManager: merges what the DmxOutput nodes of every graph
+// (and any received universes) wrote into universes, then sends them through
+// the routed interfaces.
+#[derive(Default)]
+pub struct OutputsManager {
+    interfaces: Vec<Interface>,
+    routes: Vec<Route>,
+    next_id: InterfaceId,
+
+    // MIDI ports that Midi interfaces can open, by name
+    midi_ports: HashMap<String, SharedPort>,
+    // Opens OpenDMX and Enttec ports, a SerialSink if None
+    serial_opener: Option<SinkOpener>,
+
+    merger: Merger,
+}
+
+impl OutputsManager {
+    pub fn new() -> OutputsManager {
+        OutputsManager::default()
+    }
+
+    pub fn add_midi_port(&mut self, port: Box<dyn MidiPort>) {
+        self.midi_ports
+            .insert(port.name().to_string(), SharedPort::new(port));
+    }
+
+    // How serial ports are opened from now on, e.g. open_file_sink for an
+    // adapter driven as a plain device
+    pub fn set_serial_opener(&mut self, opener: SinkOpener) {
+        self.serial_opener = Some(opener);
+    }
+
+    pub fn add_interface(&mut self, name: &str, kind: InterfaceKind) -> InterfaceId {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let config = InterfaceConfig {
+            id,
+            name: name.to_string(),
+            kind,
+        };
+        self.open(config);
+        id
+    }
+
+    // Also removes its routes
+    pub fn remove_interface(&mut self, id: InterfaceId) {
+        self.interfaces.retain(|x| x.config.id != id);
+        self.routes.retain(|x| x.interface != id);
+    }
+
+    // Replaces the route between the same universe and interface
+    pub fn set_route(&mut self, route: Route) -> bool {
+        if !self
+            .interfaces
+            .iter()
+            .any(|x| x.config.id == route.interface)
+        {
+            return false;
+        }
+
+        self.remove_route(route.universe, route.interface);
+        self.routes.push(route);
+        true
+    }
+
+    pub fn remove_route(&mut self, universe: PortAddress, interface: InterfaceId) {
+        self.routes
+            .retain(|x| x.universe != universe || x.interface != interface);
+    }
+
+    // Replaces the merge of the same universe
+    pub fn set_merge(&mut self, merge: UniverseMerge) {
+        self.merger.set_config(merge);
+    }
+
+    // Back to HTP
+    pub fn remove_merge(&mut self, universe: PortAddress) {
+        self.merger.remove_config(universe);
+    }
+
+    // Who owns a channel (from 1) after the last process()
+    pub fn inspect(&self, universe: PortAddress, channel: u16) -> ChannelInfoDto {
+        self.merger.inspect(universe, channel)
+    }
+
+    pub fn config(&self) -> OutputConfig {
+        OutputConfig {
+            interfaces: self.interfaces.iter().map(|x| x.config.clone()).collect(),
+            routes: self.routes.clone(),
+            merge: self.merger.configs().to_vec(),
+        }
+    }
+
+    // Closes every interface and opens the ones in config, e.g. on project load
+    pub fn set_config(&mut self, config: OutputConfig) {
+        self.interfaces.clear();
+        for interface in config.interfaces {
+            self.next_id = self.next_id.max(interface.id + 1);
+            self.open(interface);
+        }
+
+        self.routes.clear();
+        for route in config.routes {
+            self.set_route(route);
+        }
+
+        self.merger.set_configs(config.merge);
+    }
+
+    pub fn status(&self, id: InterfaceId) -> Option<&InterfaceStatus> {
+        self.interfaces
+            .iter()
+            .find(|x| x.config.id == id)
+            .map(|x| &x.status)
+    }
+
+    // Levels of a universe after the last process()
+    pub fn universe(&self, universe: PortAddress) -> Option<&[u8; UNIVERSE_SIZE]> {
+        self.merger.universe(universe)
+    }
+
+    // Called after every frame with the output of every graph, indexed by
+    // graph id, and the received universes. See UniverseMerge for how
+    // channels written more than once are merged.
+    pub fn process(&mut self, outputs: Vec<DmxOutputs>, dmx_in: &DmxInputs) {
+        let now = Instant::now();
+        self.reopen(now);
+
+        let mut writes = Vec::new();
+        for (graph_id, dmx_out) in outputs.into_iter().enumerate() {
+            for (universe, output) in dmx_out {
+                writes.push((MergeSource::Graph(graph_id), universe, output));
+            }
+        }
+
+        // Received universes go into the outputs that take them, until they go stale
+        for merge in self.merger.configs() {
+            let Some(input) = merge.input else {
+                continue;
+            };
+            if let Some(received) = dmx_in.get(&input).filter(|x| !x.stale) {
+                let mut output = DmxOutput::new();
+                for (i, level) in received.data.iter().enumerate() {
+                    output.set(i, *level);
+                }
+                writes.push((MergeSource::Input(input), merge.universe, output));
+            }
+        }
+
+        self.merger.merge(writes);
+
+        // Routed universes nothing wrote to are sent dark
+        let dark = [0; UNIVERSE_SIZE];
+        for route in &self.routes {
+            let data = self.merger.universe(route.universe).unwrap_or(&dark);
+
+            let Some(interface) = self
+                .interfaces
+                .iter_mut()
+                .find(|x| x.config.id == route.interface)
+            else {
+                continue;
+            };
+            let Some(device) = &mut interface.device else {
+                continue;
+            };
+
+            interface.status = match device.send(route.destination, data) {
+                Ok(()) => InterfaceStatus::Ok,
+                // Closed, e.g. a serial adapter unplugged and plugged back in
+                // is a new device
+                Err(e) => {
+                    interface.device = None;
+                    interface.reopen_at = now + REOPEN_INTERVAL;
+                    InterfaceStatus::Error(e.to_string())
+                }
+            };
+        }
+    }
+
+    // Opens the interfaces without a device that are due, called by process()
+    pub fn reopen(&mut self, now: Instant) {
+        for i in 0..self.interfaces.len() {
+            let interface = &self.interfaces[i];
+            if interface.device.is_some() || interface.reopen_at > now {
+                continue;
+            }
+
+            let result = self.open_device(&interface.config.kind);
+            let interface = &mut self.interfaces[i];
+            match result {
+                Ok(device) => {
+                    interface.device = Some(device);
+                    interface.status = InterfaceStatus::Idle;
+                }
+                Err(e) => {
+                    interface.status = InterfaceStatus::Error(e.to_string());
+                    interface.reopen_at = now + REOPEN_INTERVAL;
+                }
+            }
+        }
+    }
+
+    pub fn to_dto(&self) -> OutputsDto {
+        OutputsDto {
+            interfaces: self
+                .interfaces
+                .iter()
+                .map(|x| OutputInterfaceDto {
+                    id: x.config.id,
+                    name: x.config.name.clone(),
+                    kind: x.config.kind.clone(),
+                    status: x.status.clone(),
+                })
+                .collect(),
+            routes: self.routes.clone(),
+            merge: self.merger.configs().to_vec(),
+        }
+    }
+
+    fn open(&mut self, config: InterfaceConfig) {
+        let (device, status) = match self.open_device(&config.kind) {
+            Ok(device) => (Some(device), InterfaceStatus::Idle),
+            Err(e) => (None, InterfaceStatus::Error(e.to_string())),
+        };
+        self.interfaces.push(Interface {
+            config,
+            device,
+            status,
+            reopen_at: Instant::now() + REOPEN_INTERVAL,
+        });
+    }
+
+    fn open_device(&self, kind: &InterfaceKind) -> io::Result<Box<dyn HardwareInterface>> {
+        Ok(match kind {
+            InterfaceKind::ArtNet { target } => Box::new(ArtnetOutput::new(*target)?),
+            InterfaceKind::Sacn { target } => Box::new(SacnOutput::new(*target)?),
+            // Serial writes take most of a frame, so they run on their own thread
+            InterfaceKind::OpenDmx { port } => Box::new(ThreadedOutput::new(Box::new(
+                OpenDmxOutput::new(self.open_serial(port)?),
+            ))),
+            InterfaceKind::EnttecPro { port } => Box::new(ThreadedOutput::new(Box::new(
+                EnttecProOutput::new(self.open_serial(port)?),
+            ))),
+            InterfaceKind::Midi { port } => match self.midi_ports.get(port) {
+                Some(port) => Box::new(MidiOutput::new(Box::new(port.clone()))),
+                None => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!("MIDI port '{}' not found", port),
+                    ))
+                }
+            },
+        })
+    }
+
+    fn open_serial(&self, port: &str) -> io::Result<Box<dyn ByteSink>> {
+        match &self.serial_opener {
+            Some(opener) => opener(port),
+            None => open_serial_sink(port),
+        }
+    }
+}
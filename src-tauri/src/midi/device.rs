@@ -0,0 +1,131 @@
+use std::sync::{Arc, Mutex};
+
+use midir::{Ignore, MidiInput, MidiInputConnection, MidiOutput, MidiOutputConnection};
+
+use crate::midi::port::{MidiError, MidiPort};
+
+const CLIENT_NAME: &str = "wovmoh";
+
+// A MIDI device of the system, through midir. The input and output of a
+// device usually show up as two ports of the same name, they are joined.
+pub struct DevicePort {
+    name: String,
+    // Closed when dropped
+    input: Option<MidiInputConnection<()>>,
+    output: Option<MidiOutputConnection>,
+    // Filled by the input's callback thread, drained by receive()
+    received: Arc<Mutex<Vec<u8>>>,
+}
+
+impl DevicePort {
+    pub fn has_output(&self) -> bool {
+        self.output.is_some()
+    }
+}
+
+impl MidiPort for DevicePort {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn receive(&mut self) -> Result<Vec<u8>, MidiError> {
+        Ok(std::mem::take(&mut *self.received.lock().unwrap()))
+    }
+
+    // Nothing to do for a device that only sends
+    fn send(&mut self, bytes: &[u8]) -> Result<(), MidiError> {
+        match &mut self.output {
+            Some(output) => output
+                .send(bytes)
+                .map_err(|_| MidiError::Disconnected(self.name.clone())),
+            None => Ok(()),
+        }
+    }
+}
+
+// Opens every MIDI device connected now. Ports that fail to open are
+// skipped with a message, as is everything when there is no MIDI system.
+pub fn open_devices() -> Vec<DevicePort> {
+    let mut devices = open_inputs();
+
+    for (name, output) in open_outputs() {
+        match devices
+            .iter_mut()
+            .find(|x| x.name == name && x.output.is_none())
+        {
+            Some(device) => device.output = Some(output),
+            None => devices.push(DevicePort {
+                name,
+                input: None,
+                output: Some(output),
+                received: Arc::default(),
+            }),
+        }
+    }
+
+    devices
+}
+
+fn open_inputs() -> Vec<DevicePort> {
+    let ports = match MidiInput::new(CLIENT_NAME) {
+        Ok(midi) => midi.ports(),
+        Err(e) => {
+            eprintln!("MIDI input disabled: {}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut devices = Vec::new();
+    for port in ports {
+        // Connecting takes the MidiInput, so one per port
+        let Ok(mut midi) = MidiInput::new(CLIENT_NAME) else {
+            continue;
+        };
+        midi.ignore(Ignore::SysexAndActiveSense);
+        let Ok(name) = midi.port_name(&port) else {
+            continue;
+        };
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let buffer = received.clone();
+        let callback = move |_: u64, bytes: &[u8], _: &mut ()| {
+            buffer.lock().unwrap().extend_from_slice(bytes);
+        };
+        match midi.connect(&port, &name, callback, ()) {
+            Ok(input) => devices.push(DevicePort {
+                name,
+                input: Some(input),
+                output: None,
+                received,
+            }),
+            Err(e) => eprintln!("MIDI input '{}' not opened: {}", name, e),
+        }
+    }
+    devices
+}
+
+fn open_outputs() -> Vec<(String, MidiOutputConnection)> {
+    let ports = match MidiOutput::new(CLIENT_NAME) {
+        Ok(midi) => midi.ports(),
+        Err(e) => {
+            eprintln!("MIDI output disabled: {}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut outputs = Vec::new();
+    for port in ports {
+        let Ok(midi) = MidiOutput::new(CLIENT_NAME) else {
+            continue;
+        };
+        let Ok(name) = midi.port_name(&port) else {
+            continue;
+        };
+
+        match midi.connect(&port, &name) {
+            Ok(connection) => outputs.push((name, connection)),
+            Err(e) => eprintln!("MIDI output '{}' not opened: {}", name, e),
+        }
+    }
+    outputs
+}
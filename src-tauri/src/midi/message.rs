@@ -0,0 +1,158 @@
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MidiMessage {
+    // Channels are 0..15 here, shown as 1..16 in the UI
+    NoteOn {
+        channel: u8,
+        note: u8,
+        velocity: u8,
+    },
+    NoteOff {
+        channel: u8,
+        note: u8,
+        velocity: u8,
+    },
+    ControlChange {
+        channel: u8,
+        controller: u8,
+        value: u8,
+    },
+    ProgramChange {
+        channel: u8,
+        program: u8,
+    },
+    // 0..16383, 8192 is centered
+    PitchBend {
+        channel: u8,
+        value: u16,
+    },
+    Clock,
+    Start,
+    Continue,
+    Stop,
+}
+
+impl MidiMessage {
+    pub fn encode(&self) -> Vec<u8> {
+        match *self {
+            MidiMessage::NoteOn {
+                channel,
+                note,
+                velocity,
+            } => vec![0x90 | channel & 0x0F, note & 0x7F, velocity & 0x7F],
+            MidiMessage::NoteOff {
+                channel,
+                note,
+                velocity,
+            } => vec![0x80 | channel & 0x0F, note & 0x7F, velocity & 0x7F],
+            MidiMessage::ControlChange {
+                channel,
+                controller,
+                value,
+            } => vec![0xB0 | channel & 0x0F, controller & 0x7F, value & 0x7F],
+            MidiMessage::ProgramChange { channel, program } => {
+                vec![0xC0 | channel & 0x0F, program & 0x7F]
+            }
+            MidiMessage::PitchBend { channel, value } => vec![
+                0xE0 | channel & 0x0F,
+                (value & 0x7F) as u8,
+                ((value >> 7) & 0x7F) as u8,
+            ],
+            MidiMessage::Clock => vec![0xF8],
+            MidiMessage::Start => vec![0xFA],
+            MidiMessage::Continue => vec![0xFB],
+            MidiMessage::Stop => vec![0xFC],
+        }
+    }
+
+    pub fn channel(&self) -> Option<u8> {
+        match *self {
+            MidiMessage::NoteOn { channel, .. }
+            | MidiMessage::NoteOff { channel, .. }
+            | MidiMessage::ControlChange { channel, .. }
+            | MidiMessage::ProgramChange { channel, .. }
+            | MidiMessage::PitchBend { channel, .. } => Some(channel),
+            _ => None,
+        }
+    }
+}
+
+// Turns a raw MIDI byte stream into messages. Handles running status and
+// realtime messages in the middle of others, skips everything it doesn't know.
+#[derive(Default)]
+pub struct MidiParser {
+    status: Option<u8>,
+    data: Vec<u8>,
+}
+
+impl MidiParser {
+    pub fn new() -> MidiParser {
+        MidiParser::default()
+    }
+
+    pub fn feed(&mut self, bytes: &[u8]) -> Vec<MidiMessage> {
+        bytes.iter().filter_map(|x| self.feed_byte(*x)).collect()
+    }
+
+    fn feed_byte(&mut self, byte: u8) -> Option<MidiMessage> {
+        // Realtime messages don't disturb running status
+        if byte >= 0xF8 {
+            return match byte {
+                0xF8 => Some(MidiMessage::Clock),
+                0xFA => Some(MidiMessage::Start),
+                0xFB => Some(MidiMessage::Continue),
+                0xFC => Some(MidiMessage::Stop),
+                _ => None,
+            };
+        }
+
+        if byte & 0x80 != 0 {
+            // System common messages and SysEx cancel running status
+            self.status = if byte < 0xF0 { Some(byte) } else { None };
+            self.data.clear();
+            return None;
+        }
+
+        let status = self.status?;
+        self.data.push(byte);
+
+        let length = match status & 0xF0 {
+            0xC0 | 0xD0 => 1,
+            _ => 2,
+        };
+        if self.data.len() < length {
+            return None;
+        }
+
+        let channel = status & 0x0F;
+        let data = std::mem::take(&mut self.data);
+
+        match status & 0xF0 {
+            // Note on with velocity 0 is how most devices send note off
+            0x90 if data[1] > 0 => Some(MidiMessage::NoteOn {
+                channel,
+                note: data[0],
+                velocity: data[1],
+            }),
+            0x80 | 0x90 => Some(MidiMessage::NoteOff {
+                channel,
+                note: data[0],
+                velocity: data[1],
+            }),
+            0xB0 => Some(MidiMessage::ControlChange {
+                channel,
+                controller: data[0],
+                value: data[1],
+            }),
+            0xC0 => Some(MidiMessage::ProgramChange {
+                channel,
+                program: data[0],
+            }),
+            0xE0 => Some(MidiMessage::PitchBend {
+                channel,
+                value: (data[1] as u16) << 7 | data[0] as u16,
+            }),
+            // Aftertouch
+            _ => None,
+        }
+    }
+}
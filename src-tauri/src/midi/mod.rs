@@ -0,0 +1,189 @@
+pub mod device;
+pub mod message;
+pub mod port;
+
+use std::collections::HashMap;
+
+use crate::midi::message::MidiMessage;
+
+// Controller state built from everything received, read by the MIDI nodes
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MidiInputs {
+    // Velocity of held notes by (channel, note)
+    pub notes: HashMap<(u8, u8), u8>,
+    // Velocity of the notes struck since the last frame, released or not,
+    // so a tap shorter than a frame isn't lost. See clear_frame.
+    pub struck: HashMap<(u8, u8), u8>,
+    // Last value by (channel, controller)
+    pub controllers: HashMap<(u8, u8), u8>,
+    pub programs: HashMap<u8, u8>,
+    pub pitch_bend: HashMap<u8, u16>,
+    // Clock ticks (24 per quarter note) since the last Start
+    pub clock_ticks: u64,
+    pub playing: bool,
+}
+
+impl MidiInputs {
+    // Forgets what was struck, before reading the next frame's messages
+    pub fn clear_frame(&mut self) {
+        self.struck.clear();
+    }
+
+    pub fn apply(&mut self, message: &MidiMessage) {
+        match *message {
+            MidiMessage::NoteOn {
+                channel,
+                note,
+                velocity,
+            } => {
+                self.notes.insert((channel, note), velocity);
+                self.struck.insert((channel, note), velocity);
+            }
+            MidiMessage::NoteOff { channel, note, .. } => {
+                self.notes.remove(&(channel, note));
+            }
+            MidiMessage::ControlChange {
+                channel,
+                controller,
+                value,
+            } => {
+                self.controllers.insert((channel, controller), value);
+            }
+            MidiMessage::ProgramChange { channel, program } => {
+                self.programs.insert(channel, program);
+            }
+            MidiMessage::PitchBend { channel, value } => {
+                self.pitch_bend.insert(channel, value);
+            }
+            MidiMessage::Clock => {
+                if self.playing {
+                    self.clock_ticks += 1;
+                }
+            }
+            MidiMessage::Start => {
+                self.clock_ticks = 0;
+                self.playing = true;
+            }
+            MidiMessage::Continue => self.playing = true,
+            MidiMessage::Stop => self.playing = false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::message::*;
+    use super::MidiInputs;
+
+    #[test]
+    fn parse_channel_messages() {
+        let mut parser = MidiParser::new();
+
+        assert_eq!(
+            vec![
+                MidiMessage::NoteOn {
+                    channel: 0,
+                    note: 60,
+                    velocity: 100
+                },
+                // Running status, velocity 0 is a note off
+                MidiMessage::NoteOff {
+                    channel: 0,
+                    note: 60,
+                    velocity: 0
+                },
+                MidiMessage::ControlChange {
+                    channel: 15,
+                    controller: 7,
+                    value: 127
+                },
+                MidiMessage::ProgramChange {
+                    channel: 2,
+                    program: 5
+                },
+                MidiMessage::PitchBend {
+                    channel: 1,
+                    value: 8192
+                },
+            ],
+            parser.feed(&[0x90, 60, 100, 60, 0, 0xBF, 7, 127, 0xC2, 5, 0xE1, 0x00, 0x40])
+        );
+    }
+
+    #[test]
+    fn parse_split_and_interleaved() {
+        let mut parser = MidiParser::new();
+
+        // Messages can be split across reads, realtime bytes can land anywhere
+        assert_eq!(Vec::<MidiMessage>::new(), parser.feed(&[0xB0, 1]));
+        assert_eq!(
+            vec![
+                MidiMessage::Clock,
+                MidiMessage::ControlChange {
+                    channel: 0,
+                    controller: 1,
+                    value: 64
+                },
+            ],
+            parser.feed(&[0xF8, 64])
+        );
+
+        // SysEx is skipped and cancels running status
+        assert_eq!(
+            vec![MidiMessage::Start, MidiMessage::Stop],
+            parser.feed(&[0xF0, 1, 2, 3, 0xF7, 5, 6, 0xFA, 0xFC])
+        );
+
+        // Aftertouch is ignored
+        assert_eq!(
+            vec![MidiMessage::NoteOff {
+                channel: 3,
+                note: 1,
+                velocity: 2
+            }],
+            parser.feed(&[0xD3, 10, 0x83, 1, 2])
+        );
+    }
+
+    #[test]
+    fn encode_round_trip() {
+        let messages = vec![
+            MidiMessage::NoteOn {
+                channel: 9,
+                note: 36,
+                velocity: 1,
+            },
+            MidiMessage::ControlChange {
+                channel: 0,
+                controller: 74,
+                value: 3,
+            },
+            MidiMessage::PitchBend {
+                channel: 4,
+                value: 16383,
+            },
+            MidiMessage::Continue,
+        ];
+        let bytes: Vec<u8> = messages.iter().flat_map(|x| x.encode()).collect();
+
+        assert_eq!([0x99, 36, 1], bytes[0..3]);
+        assert_eq!(messages, MidiParser::new().feed(&bytes));
+    }
+
+    #[test]
+    fn inputs_track_state() {
+        let mut inputs = MidiInputs::default();
+        let messages = MidiParser::new().feed(&[
+            0x90, 60, 100, 62, 90, 0x80, 60, 0, 0xFA, 0xF8, 0xF8, 0xFC, 0xF8,
+        ]);
+        for message in &messages {
+            inputs.apply(message);
+        }
+
+        assert_eq!(None, inputs.notes.get(&(0, 60)));
+        assert_eq!(Some(&90), inputs.notes.get(&(0, 62)));
+        // Ticks while stopped don't count
+        assert_eq!(2, inputs.clock_ticks);
+        assert!(!inputs.playing);
+    }
+}
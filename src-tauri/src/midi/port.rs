@@ -0,0 +1,112 @@
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum MidiError {
+    Disconnected(String),
+}
+
+impl Display for MidiError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            MidiError::Disconnected(name) => write!(f, "MIDI port '{}' is disconnected", name),
+        }
+    }
+}
+
+// A MIDI device as seen by the MidiManager, implemented per backend
+pub trait MidiPort: Send {
+    fn name(&self) -> &str;
+
+    // Raw bytes received since the last call
+    fn receive(&mut self) -> Result<Vec<u8>, MidiError>;
+
+    fn send(&mut self, bytes: &[u8]) -> Result<(), MidiError>;
+}
+
+// In memory port. Clones share their buffers, so a test can keep one to
+// inject bytes and read back what was sent through the other.
+#[derive(Clone)]
+pub struct VirtualPort {
+    name: String,
+    incoming: Arc<Mutex<VecDeque<u8>>>,
+    sent: Arc<Mutex<Vec<u8>>>,
+    unplugged: Arc<AtomicBool>,
+}
+
+impl VirtualPort {
+    pub fn new(name: &str) -> VirtualPort {
+        VirtualPort {
+            name: name.to_string(),
+            incoming: Arc::new(Mutex::new(VecDeque::new())),
+            sent: Arc::new(Mutex::new(Vec::new())),
+            unplugged: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    // As if the device had sent bytes
+    pub fn inject(&self, bytes: &[u8]) {
+        self.incoming.lock().unwrap().extend(bytes);
+    }
+
+    // Everything sent to the device since the last call
+    pub fn take_sent(&self) -> Vec<u8> {
+        std::mem::take(&mut *self.sent.lock().unwrap())
+    }
+
+    // Sends fail while unplugged
+    pub fn set_unplugged(&self, unplugged: bool) {
+        self.unplugged.store(unplugged, Ordering::Relaxed);
+    }
+}
+
+impl MidiPort for VirtualPort {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn receive(&mut self) -> Result<Vec<u8>, MidiError> {
+        Ok(self.incoming.lock().unwrap().drain(..).collect())
+    }
+
+    fn send(&mut self, bytes: &[u8]) -> Result<(), MidiError> {
+        if self.unplugged.load(Ordering::Relaxed) {
+            return Err(MidiError::Disconnected(self.name.clone()));
+        }
+        self.sent.lock().unwrap().extend_from_slice(bytes);
+        Ok(())
+    }
+}
+
+// Lets several owners use one port, e.g. interfaces that are closed and
+// opened again when a project is loaded
+#[derive(Clone)]
+pub struct SharedPort {
+    name: String,
+    port: Arc<Mutex<Box<dyn MidiPort>>>,
+}
+
+impl SharedPort {
+    pub fn new(port: Box<dyn MidiPort>) -> SharedPort {
+        SharedPort {
+            name: port.name().to_string(),
+            port: Arc::new(Mutex::new(port)),
+        }
+    }
+}
+
+impl MidiPort for SharedPort {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn receive(&mut self) -> Result<Vec<u8>, MidiError> {
+        self.port.lock().unwrap().receive()
+    }
+
+    fn send(&mut self, bytes: &[u8]) -> Result<(), MidiError> {
+        self.port.lock().unwrap().send(bytes)
+    }
+}
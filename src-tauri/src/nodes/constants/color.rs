@@ -3,7 +3,11 @@ use crate::types::color::{Color, ColorValue};
 use crate::types::data_type::*;
 use std::sync::LazyLock;
 
-fn constant_color_process(inputs: Vec<DataValue>, outputs: &mut Vec<&mut DataValue>) {
+fn constant_color_process(
+    _ctx: &mut ProcessContext,
+    inputs: Vec<DataValue>,
+    outputs: &mut [&mut DataValue],
+) {
     let input_color = match inputs[0] {
         DataValue::Color(color) => color,
         _ => panic!("Expected Color"),
@@ -28,4 +32,6 @@ pub static CONSTANT_COLOR_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
         data_type: DataType::Color,
     }],
     process: constant_color_process,
+    state: &[],
+    volatile: false,
 };
@@ -1,7 +1,11 @@
 use crate::core::node::*;
 use crate::types::data_type::*;
 
-fn constant_number_process(inputs: Vec<DataValue>, outputs: &mut Vec<&mut DataValue>) {
+fn constant_number_process(
+    _ctx: &mut ProcessContext,
+    inputs: Vec<DataValue>,
+    outputs: &mut [&mut DataValue],
+) {
     let input_number = match inputs[0] {
         DataValue::Number(number) => number,
         _ => panic!("Expected Number"),
@@ -26,6 +30,8 @@ pub static CONSTANT_NUMBER_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
         data_type: DataType::Number,
     }],
     process: constant_number_process,
+    state: &[],
+    volatile: false,
 };
 
 // use crate::core;
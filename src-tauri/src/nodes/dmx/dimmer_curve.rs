@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+use crate::core::node::*;
+use crate::types::curve::{self, Curve};
+use crate::types::data_type::*;
+
+fn dimmer_curve_process(
+    ctx: &mut ProcessContext,
+    inputs: Vec<DataValue>,
+    outputs: &mut [&mut DataValue],
+) {
+    let (value, curve_index, gamma) = match (inputs[0], inputs[1], inputs[2]) {
+        (DataValue::Number(v), DataValue::Number(c), DataValue::Number(g)) => (v, c, g),
+        _ => panic!("Expected Number"),
+    };
+
+    // The table in the text, parsed once per text change
+    if ctx.cache.is_none() {
+        *ctx.cache = Some(Arc::new(Curve::parse_table(ctx.text)));
+    }
+    let table = match ctx
+        .cache
+        .as_ref()
+        .and_then(|x| x.downcast_ref::<Result<Vec<f64>, String>>())
+    {
+        Some(Ok(table)) => {
+            *ctx.error = None;
+            table.as_slice()
+        }
+        Some(Err(error)) => {
+            *ctx.error = Some(error.clone());
+            &[]
+        }
+        None => &[],
+    };
+
+    let curved = Curve::from_index(curve_index, gamma, table).apply(value);
+    let (coarse, fine) = curve::to_coarse_fine(curved);
+
+    if let DataValue::Number(ref mut n) = outputs[0] {
+        *n = curved;
+    };
+    if let DataValue::Number(ref mut n) = outputs[1] {
+        *n = coarse as f64;
+    };
+    if let DataValue::Number(ref mut n) = outputs[2] {
+        *n = fine as f64;
+    };
+}
+
+pub static DIMMER_CURVE_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "Dimmer Curve",
+    inputs: &[
+        InputDesc {
+            id: 0,
+            name: "Value",
+            data_type: DataType::Number,
+            default: DataValue::default(DataType::Number),
+        },
+        // 0 = Linear, 1 = Square, 2 = S-Curve, 3 = Gamma, 4 = the table in
+        // the text, linear without one
+        InputDesc {
+            id: 1,
+            name: "Curve",
+            data_type: DataType::Number,
+            default: DataValue::Number(0.0),
+        },
+        InputDesc {
+            id: 2,
+            name: "Gamma",
+            data_type: DataType::Number,
+            default: DataValue::Number(2.2),
+        },
+    ],
+    outputs: &[
+        OutputDesc {
+            id: 0,
+            name: "Value",
+            data_type: DataType::Number,
+        },
+        OutputDesc {
+            id: 1,
+            name: "Coarse",
+            data_type: DataType::Number,
+        },
+        OutputDesc {
+            id: 2,
+            name: "Fine",
+            data_type: DataType::Number,
+        },
+    ],
+    process: dimmer_curve_process,
+    state: &[],
+    volatile: false,
+};
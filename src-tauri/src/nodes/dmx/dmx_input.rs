@@ -0,0 +1,75 @@
+use crate::core::node::*;
+use crate::dmx::{PortAddress, UNIVERSE_SIZE};
+use crate::types::curve;
+use crate::types::data_type::*;
+
+fn dmx_input_process(
+    ctx: &mut ProcessContext,
+    inputs: Vec<DataValue>,
+    outputs: &mut [&mut DataValue],
+) {
+    let (universe, channel) = match (inputs[0], inputs[1]) {
+        (DataValue::Number(u), DataValue::Number(c)) => (u, c),
+        _ => panic!("Expected Number"),
+    };
+
+    let port_address = universe.round().clamp(0.0, 0x7FFF as f64) as PortAddress;
+    // Channels are numbered from 1
+    let index = (channel.round().clamp(1.0, UNIVERSE_SIZE as f64) - 1.0) as usize;
+
+    // Universes that have never been received read as 0
+    let (raw, active) = match ctx.io.dmx_in.get(&port_address) {
+        Some(input) => (input.data[index], !input.stale),
+        None => (0, false),
+    };
+
+    if let DataValue::Number(ref mut n) = outputs[0] {
+        *n = curve::from_u8(raw);
+    };
+    if let DataValue::Number(ref mut n) = outputs[1] {
+        *n = raw as f64;
+    };
+    if let DataValue::Boolean(ref mut b) = outputs[2] {
+        *b = active;
+    };
+}
+
+pub static DMX_INPUT_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "DMX Input",
+    inputs: &[
+        // Port address, net * 256 + subnet * 16 + universe
+        InputDesc {
+            id: 0,
+            name: "Universe",
+            data_type: DataType::Number,
+            default: DataValue::Number(0.0),
+        },
+        InputDesc {
+            id: 1,
+            name: "Channel",
+            data_type: DataType::Number,
+            default: DataValue::Number(1.0),
+        },
+    ],
+    outputs: &[
+        OutputDesc {
+            id: 0,
+            name: "Value",
+            data_type: DataType::Number,
+        },
+        OutputDesc {
+            id: 1,
+            name: "Raw",
+            data_type: DataType::Number,
+        },
+        // False while the source is missing or stale, the last levels are held
+        OutputDesc {
+            id: 2,
+            name: "Active",
+            data_type: DataType::Boolean,
+        },
+    ],
+    process: dmx_input_process,
+    state: &[],
+    volatile: true,
+};
@@ -0,0 +1,71 @@
+use crate::core::node::*;
+use crate::dmx::{PortAddress, UNIVERSE_SIZE};
+use crate::types::curve;
+use crate::types::data_type::*;
+
+fn dmx_output_process(
+    ctx: &mut ProcessContext,
+    inputs: Vec<DataValue>,
+    _outputs: &mut [&mut DataValue],
+) {
+    let (universe, channel, value) = match (inputs[0], inputs[1], inputs[2]) {
+        (DataValue::Number(u), DataValue::Number(c), DataValue::Number(v)) => (u, c, v),
+        _ => panic!("Expected Number"),
+    };
+    let fine = match inputs[3] {
+        DataValue::Boolean(b) => b,
+        _ => panic!("Expected Boolean"),
+    };
+
+    let port_address = universe.round().clamp(0.0, 0x7FFF as f64) as PortAddress;
+    // Channels are numbered from 1
+    let index = (channel.round().clamp(1.0, UNIVERSE_SIZE as f64) - 1.0) as usize;
+
+    let output = ctx.io.dmx_out.entry(port_address).or_default();
+
+    if fine {
+        // Coarse on Channel, fine on Channel + 1
+        let (coarse, fine) = curve::to_coarse_fine(value);
+        output.set(index, coarse);
+        output.set(index + 1, fine);
+    } else {
+        output.set(index, curve::to_u8(value));
+    }
+}
+
+pub static DMX_OUTPUT_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "DMX Output",
+    inputs: &[
+        // Port address, net * 256 + subnet * 16 + universe
+        InputDesc {
+            id: 0,
+            name: "Universe",
+            data_type: DataType::Number,
+            default: DataValue::Number(0.0),
+        },
+        InputDesc {
+            id: 1,
+            name: "Channel",
+            data_type: DataType::Number,
+            default: DataValue::Number(1.0),
+        },
+        // 0..1
+        InputDesc {
+            id: 2,
+            name: "Value",
+            data_type: DataType::Number,
+            default: DataValue::Number(0.0),
+        },
+        // 16 bit, also writes Channel + 1
+        InputDesc {
+            id: 3,
+            name: "Fine",
+            data_type: DataType::Boolean,
+            default: DataValue::Boolean(false),
+        },
+    ],
+    outputs: &[],
+    process: dmx_output_process,
+    state: &[],
+    volatile: true,
+};
@@ -0,0 +1,123 @@
+pub mod dimmer_curve;
+pub mod dmx_input;
+pub mod dmx_output;
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::core::graph::Graph;
+    use crate::core::node::{NodeKind, NodeUIState};
+    use crate::dmx::{port_address, DmxInput, DmxInputs, UNIVERSE_SIZE};
+    use crate::types::data_type::DataValue::*;
+
+    #[test]
+    fn dmx_input_reads_channels() {
+        let mut graph = Graph::new(0);
+        graph.add_node(
+            NodeKind::DmxInput,
+            NodeUIState {
+                position: (0.0, 0.0),
+            },
+        );
+        let universe = port_address(0, 0, 1);
+        graph.set_input_value(0, Number(universe as f64));
+        graph.set_input_value(1, Number(512.0));
+
+        // Nothing received yet
+        graph.process();
+        assert_eq!(Number(0.0), graph.get_output_value(0));
+        assert_eq!(Boolean(false), graph.get_output_value(2));
+
+        let mut data = [0u8; UNIVERSE_SIZE];
+        data[0] = 51;
+        data[511] = 255;
+        let mut dmx_in = DmxInputs::new();
+        dmx_in.insert(universe, DmxInput { data, stale: false });
+        graph.set_dmx_input(Arc::new(dmx_in.clone()));
+
+        graph.process();
+        assert_eq!(Number(1.0), graph.get_output_value(0));
+        assert_eq!(Number(255.0), graph.get_output_value(1));
+        assert_eq!(Boolean(true), graph.get_output_value(2));
+
+        graph.set_input_value(1, Number(1.0));
+        dmx_in.get_mut(&universe).unwrap().stale = true;
+        graph.set_dmx_input(Arc::new(dmx_in));
+
+        graph.process();
+        assert_eq!(Number(0.2), graph.get_output_value(0));
+        assert_eq!(Number(51.0), graph.get_output_value(1));
+        assert_eq!(Boolean(false), graph.get_output_value(2));
+    }
+
+    #[test]
+    fn dmx_output_writes_channels() {
+        let ui_state = NodeUIState {
+            position: (0.0, 0.0),
+        };
+        let mut graph = Graph::new(0);
+        graph.add_node(NodeKind::DmxOutput, ui_state.clone());
+        graph.add_node(NodeKind::DmxOutput, ui_state);
+        let universe = port_address(0, 1, 2);
+        for node in 0..2 {
+            graph.set_input_value(node * 4, Number(universe as f64));
+        }
+        graph.set_input_value(1, Number(10.0));
+        graph.set_input_value(2, Number(0.5));
+        // 16 bit pan on 511/512
+        graph.set_input_value(5, Number(511.0));
+        graph.set_input_value(6, Number(0.5));
+        graph.set_input_value(7, Boolean(true));
+
+        graph.process();
+        let dmx_out = graph.take_dmx_output();
+        let levels = dmx_out[&universe].levels;
+        assert_eq!(Some(128), levels[9]);
+        assert_eq!((Some(0x80), Some(0x00)), (levels[510], levels[511]));
+        assert_eq!(3, levels.iter().filter(|x| x.is_some()).count());
+
+        // Fine channel past 512 is dropped
+        graph.set_input_value(5, Number(512.0));
+        graph.process();
+        let levels = graph.take_dmx_output()[&universe].levels;
+        assert_eq!(Some(0x80), levels[511]);
+        assert_eq!(None, levels[510]);
+
+        // Written again every frame, nothing left from the last one
+        graph.set_input_value(4, Number(0.0));
+        graph.process();
+        let dmx_out = graph.take_dmx_output();
+        assert_eq!(2, dmx_out.len());
+        let levels = dmx_out[&universe].levels;
+        assert_eq!(1, levels.iter().filter(|x| x.is_some()).count());
+    }
+
+    #[test]
+    fn dimmer_curve_table_from_text() {
+        let mut graph = Graph::new(0);
+        graph.add_node(
+            NodeKind::DimmerCurve,
+            NodeUIState {
+                position: (0.0, 0.0),
+            },
+        );
+        graph.set_input_value(0, Number(0.25));
+        graph.set_input_value(1, Number(4.0));
+
+        // No table yet, linear
+        graph.process();
+        assert_eq!(Number(0.25), graph.get_output_value(0));
+
+        graph.set_node_text(0, "0, 0.1, 1");
+        graph.process();
+        assert_eq!(Number(0.05), graph.get_output_value(0));
+        assert_eq!(None, graph.get_node(0).error);
+
+        // A bad table is shown on the node and the curve is linear again
+        graph.set_node_text(0, "0, 10");
+        graph.process();
+        assert_eq!(Number(0.25), graph.get_output_value(0));
+        assert!(graph.get_node(0).error.is_some());
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::BTreeMap;
+
+use crate::core::node::*;
+use crate::fixtures::patch::FixtureId;
+use crate::fixtures::profile::Attribute;
+use crate::types::data_type::*;
+
+// Colours dimmed by the cue's intensity on fixtures without a dimmer channel
+const EMITTERS: [Attribute; 6] = [
+    Attribute::Red,
+    Attribute::Green,
+    Attribute::Blue,
+    Attribute::White,
+    Attribute::Amber,
+    Attribute::Uv,
+];
+
+// "<fixture id> <attribute>", e.g. "3 Intensity"
+fn parse_selection(text: &str) -> Result<(FixtureId, Attribute), String> {
+    let mut parts = text.split_whitespace();
+    let fixture = parts.next().and_then(|x| x.parse().ok());
+    let attribute = parts
+        .next()
+        .and_then(|x| serde_json::from_value(serde_json::Value::String(x.to_string())).ok());
+    match (fixture, attribute, parts.next()) {
+        (Some(fixture), Some(attribute), None) => Ok((fixture, attribute)),
+        _ => Err(format!(
+            "Expected a fixture id and an attribute, e.g. '3 Intensity', not '{}'",
+            text
+        )),
+    }
+}
+
+fn cue_list_process(
+    ctx: &mut ProcessContext,
+    inputs: Vec<DataValue>,
+    outputs: &mut [&mut DataValue],
+) {
+    let id = match inputs[0] {
+        DataValue::Number(id) => id.round().max(0.0) as u32,
+        _ => panic!("Expected Number"),
+    };
+
+    let cues = ctx.io.cues.clone();
+    let output = match cues.get(&id) {
+        Some(output) => output,
+        None => {
+            *ctx.error = Some(format!("Cue list {} doesn't exist", id));
+            *outputs[0] = DataValue::Number(0.0);
+            *outputs[1] = DataValue::Boolean(false);
+            *outputs[2] = DataValue::Number(0.0);
+            return;
+        }
+    };
+    *ctx.error = None;
+    *outputs[0] = DataValue::Number(output.cue.unwrap_or(0.0));
+    *outputs[1] = DataValue::Boolean(output.fading);
+
+    // With a fixture and attribute in the text, their crossfaded level goes
+    // into the graph instead of the cue list writing its fixtures
+    if !ctx.text.trim().is_empty() {
+        match parse_selection(ctx.text) {
+            Ok(key) => {
+                let level = output.levels.get(&key).copied().unwrap_or(0.0);
+                *outputs[2] = DataValue::Number(level);
+            }
+            Err(error) => {
+                *ctx.error = Some(error);
+                *outputs[2] = DataValue::Number(0.0);
+            }
+        }
+        return;
+    }
+    *outputs[2] = DataValue::Number(0.0);
+
+    // Fixture by fixture, in id order so overlapping fixtures write the same way every frame
+    let mut levels = BTreeMap::new();
+    for ((fixture, attribute), level) in &output.levels {
+        levels
+            .entry(*fixture)
+            .or_insert_with(Vec::new)
+            .push((*attribute, *level));
+    }
+
+    for (id, levels) in levels {
+        // Fixtures unpatched since the cue was recorded are skipped
+        let Some(fixture) = ctx.io.fixtures.get(id) else {
+            continue;
+        };
+        let dmx = ctx.io.dmx_out.entry(fixture.universe).or_default();
+        fixture.write_defaults(dmx);
+
+        let intensity = levels
+            .iter()
+            .find(|x| x.0 == Attribute::Intensity)
+            .map(|x| x.1);
+        let scale = match intensity {
+            Some(intensity) if !fixture.has(Attribute::Intensity) => intensity,
+            _ => 1.0,
+        };
+        for (attribute, level) in levels {
+            let level = if EMITTERS.contains(&attribute) {
+                level * scale
+            } else {
+                level
+            };
+            fixture.write(dmx, attribute, level);
+        }
+    }
+}
+
+pub static CUE_LIST_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "Cue List",
+    inputs: &[
+        // Cue list id
+        InputDesc {
+            id: 0,
+            name: "Cue List",
+            data_type: DataType::Number,
+            default: DataValue::Number(0.0),
+        },
+    ],
+    outputs: &[
+        // Number of the current cue, 0 when released
+        OutputDesc {
+            id: 0,
+            name: "Cue",
+            data_type: DataType::Number,
+        },
+        OutputDesc {
+            id: 1,
+            name: "Fading",
+            data_type: DataType::Boolean,
+        },
+        // Level of the fixture and attribute in the text, 0 when the cue
+        // doesn't hold one
+        OutputDesc {
+            id: 2,
+            name: "Level",
+            data_type: DataType::Number,
+        },
+    ],
+    process: cue_list_process,
+    state: &[],
+    volatile: true,
+};
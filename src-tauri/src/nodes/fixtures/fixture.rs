@@ -0,0 +1,108 @@
+use crate::core::node::*;
+use crate::fixtures::profile::Attribute;
+use crate::types::color::Color;
+use crate::types::data_type::*;
+use crate::types::vector::Vec3;
+
+fn fixture_process(
+    ctx: &mut ProcessContext,
+    inputs: Vec<DataValue>,
+    _outputs: &mut [&mut DataValue],
+) {
+    let (id, intensity) = match (inputs[0], inputs[1]) {
+        (DataValue::Number(id), DataValue::Number(intensity)) => (id, intensity),
+        _ => panic!("Expected Number"),
+    };
+    let color = match inputs[2] {
+        DataValue::Color(color) => color,
+        _ => panic!("Expected Color"),
+    };
+    let position = match inputs[3] {
+        DataValue::Vector3(position) => position,
+        _ => panic!("Expected Vector3"),
+    };
+
+    let id = id.round().max(0.0) as u32;
+    let fixture = match ctx.io.fixtures.get(id) {
+        Some(fixture) => fixture,
+        None => {
+            *ctx.error = Some(format!("Fixture {} isn't patched", id));
+            return;
+        }
+    };
+    *ctx.error = None;
+
+    let output = ctx.io.dmx_out.entry(fixture.universe).or_default();
+    // Channels without an input, e.g. the shutter, sit at their defaults
+    fixture.write_defaults(output);
+
+    // Without a dimmer channel the colour is dimmed instead
+    let (r, g, b) = color.rgb();
+    let scale = if fixture.has(Attribute::Intensity) {
+        fixture.write(output, Attribute::Intensity, intensity);
+        1.0
+    } else {
+        intensity
+    };
+    let (r, g, b) = (r * scale, g * scale, b * scale);
+
+    if fixture.has(Attribute::Red) {
+        // The part all three share goes to the white emitter when there is one
+        let white = if fixture.has(Attribute::White) {
+            r.min(g).min(b)
+        } else {
+            0.0
+        };
+        fixture.write(output, Attribute::Red, r - white);
+        fixture.write(output, Attribute::Green, g - white);
+        fixture.write(output, Attribute::Blue, b - white);
+        fixture.write(output, Attribute::White, white);
+    } else if fixture.has(Attribute::Cyan) {
+        // Subtractive mixing
+        fixture.write(output, Attribute::Cyan, 1.0 - r);
+        fixture.write(output, Attribute::Magenta, 1.0 - g);
+        fixture.write(output, Attribute::Yellow, 1.0 - b);
+    } else if fixture.has(Attribute::White) {
+        fixture.write(output, Attribute::White, r.max(g).max(b));
+    }
+
+    fixture.write(output, Attribute::Pan, position.x());
+    fixture.write(output, Attribute::Tilt, position.y());
+}
+
+pub static FIXTURE_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "Fixture",
+    inputs: &[
+        // Id in the patch
+        InputDesc {
+            id: 0,
+            name: "Fixture",
+            data_type: DataType::Number,
+            default: DataValue::Number(0.0),
+        },
+        // 0..1
+        InputDesc {
+            id: 1,
+            name: "Intensity",
+            data_type: DataType::Number,
+            default: DataValue::Number(0.0),
+        },
+        InputDesc {
+            id: 2,
+            name: "Color",
+            data_type: DataType::Color,
+            default: DataValue::Color(Color::default()),
+        },
+        // x is pan and y tilt, 0..1 over the whole range
+        InputDesc {
+            id: 3,
+            name: "Position",
+            data_type: DataType::Vector3,
+            default: DataValue::Vector3(Vec3::new(0.5, 0.5, 0.0)),
+        },
+    ],
+    outputs: &[],
+    process: fixture_process,
+    state: &[],
+    volatile: true,
+};
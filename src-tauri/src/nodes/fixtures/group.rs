@@ -0,0 +1,100 @@
+use crate::core::node::*;
+use crate::fixtures::group::{self, Spread};
+use crate::fixtures::profile::Attribute;
+use crate::types::data_type::*;
+
+// The text names the attribute written, Intensity when empty
+fn group_process(
+    ctx: &mut ProcessContext,
+    inputs: Vec<DataValue>,
+    _outputs: &mut [&mut DataValue],
+) {
+    let (id, value, amount, mode, seed) =
+        match (inputs[0], inputs[1], inputs[2], inputs[3], inputs[4]) {
+            (
+                DataValue::Number(id),
+                DataValue::Number(value),
+                DataValue::Number(amount),
+                DataValue::Number(mode),
+                DataValue::Number(seed),
+            ) => (id, value, amount, mode, seed),
+            _ => panic!("Expected Number"),
+        };
+
+    let attribute = match ctx.text.trim() {
+        "" => Attribute::Intensity,
+        name => match serde_json::from_value(serde_json::Value::String(name.to_string())) {
+            Ok(attribute) => attribute,
+            Err(_) => {
+                *ctx.error = Some(format!("Unknown attribute '{}'", name));
+                return;
+            }
+        },
+    };
+
+    let id = id.round().max(0.0) as u32;
+    let members = match ctx.io.fixtures.groups.get(&id) {
+        Some(members) => members,
+        None => {
+            *ctx.error = Some(format!("Group {} doesn't exist", id));
+            return;
+        }
+    };
+    *ctx.error = None;
+
+    let offsets = Spread::from_index(mode, seed).offsets(members.len());
+    let values = group::spread(value, amount, &offsets);
+
+    // Members without the attribute are skipped, nothing else of them is written
+    for (member, value) in members.iter().zip(values) {
+        if let Some(fixture) = ctx.io.fixtures.get(*member).filter(|x| x.has(attribute)) {
+            let output = ctx.io.dmx_out.entry(fixture.universe).or_default();
+            fixture.write(output, attribute, value);
+        }
+    }
+}
+
+pub static GROUP_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "Group",
+    inputs: &[
+        // Group id
+        InputDesc {
+            id: 0,
+            name: "Group",
+            data_type: DataType::Number,
+            default: DataValue::Number(0.0),
+        },
+        // 0..1, a ramp chases across the group
+        InputDesc {
+            id: 1,
+            name: "Value",
+            data_type: DataType::Number,
+            default: DataValue::Number(0.0),
+        },
+        // How far the last member lags the first, in cycles
+        InputDesc {
+            id: 2,
+            name: "Spread",
+            data_type: DataType::Number,
+            default: DataValue::Number(1.0),
+        },
+        // 0 linear, 1 reversed, 2 centre out, 3 random
+        InputDesc {
+            id: 3,
+            name: "Mode",
+            data_type: DataType::Number,
+            default: DataValue::Number(0.0),
+        },
+        // Order of the random mode
+        InputDesc {
+            id: 4,
+            name: "Seed",
+            data_type: DataType::Number,
+            default: DataValue::Number(0.0),
+        },
+    ],
+    outputs: &[],
+    process: group_process,
+    state: &[],
+    volatile: true,
+};
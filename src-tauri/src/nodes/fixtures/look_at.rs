@@ -0,0 +1,113 @@
+use crate::core::node::*;
+use crate::fixtures::look_at::{Mounting, PanTilt};
+use crate::types::data_type::*;
+use crate::types::vector::Vec3;
+
+// Pan/Tilt goes straight into the Position of a Fixture node
+fn look_at_process(
+    ctx: &mut ProcessContext,
+    inputs: Vec<DataValue>,
+    outputs: &mut [&mut DataValue],
+) {
+    let (target, position) = match (inputs[0], inputs[1]) {
+        (DataValue::Point3(target), DataValue::Point3(position)) => (target, position),
+        _ => panic!("Expected Point3"),
+    };
+    let (up, forward) = match (inputs[2], inputs[3]) {
+        (DataValue::Vector3(up), DataValue::Vector3(forward)) => (up, forward),
+        _ => panic!("Expected Vector3"),
+    };
+    let (pan_range, tilt_range) = match (inputs[4], inputs[5]) {
+        (DataValue::Number(pan), DataValue::Number(tilt)) => (pan, tilt),
+        _ => panic!("Expected Number"),
+    };
+    let current = match (ctx.state[0], ctx.state[1]) {
+        (DataValue::Number(pan), DataValue::Number(tilt)) => PanTilt { pan, tilt },
+        _ => panic!("Expected Number"),
+    };
+
+    if up.length_squared() == 0.0 || pan_range <= 0.0 || tilt_range <= 0.0 {
+        *ctx.error = Some("Up and the ranges can't be zero".to_string());
+        return;
+    }
+    *ctx.error = None;
+
+    let mounting = Mounting {
+        position,
+        up,
+        forward,
+        pan_range,
+        tilt_range,
+    };
+    let aim = mounting.look_at(target, current);
+    ctx.state[0] = DataValue::Number(aim.angles.pan);
+    ctx.state[1] = DataValue::Number(aim.angles.tilt);
+
+    // Through the 16 bit levels so the Fixture node writes exactly them
+    let (pan, tilt) = mounting.to_dmx(aim.angles);
+    *outputs[0] = DataValue::Vector3(Vec3::new(pan as f64 / 65535.0, tilt as f64 / 65535.0, 0.0));
+    *outputs[1] = DataValue::Boolean(aim.reachable);
+}
+
+pub static LOOK_AT_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "Look At",
+    inputs: &[
+        InputDesc {
+            id: 0,
+            name: "Target",
+            data_type: DataType::Point3,
+            default: DataValue::Point3(Vec3::new(0.0, 0.0, 0.0)),
+        },
+        // Where the fixture hangs
+        InputDesc {
+            id: 1,
+            name: "Position",
+            data_type: DataType::Point3,
+            default: DataValue::Point3(Vec3::new(0.0, 5.0, 0.0)),
+        },
+        // Where the beam points at tilt 0, down when hung
+        InputDesc {
+            id: 2,
+            name: "Up",
+            data_type: DataType::Vector3,
+            default: DataValue::Vector3(Vec3::new(0.0, -1.0, 0.0)),
+        },
+        // Where tilting swings the beam with the pan at its centre
+        InputDesc {
+            id: 3,
+            name: "Forward",
+            data_type: DataType::Vector3,
+            default: DataValue::Vector3(Vec3::new(0.0, 0.0, 1.0)),
+        },
+        // Degrees
+        InputDesc {
+            id: 4,
+            name: "Pan Range",
+            data_type: DataType::Number,
+            default: DataValue::Number(540.0),
+        },
+        InputDesc {
+            id: 5,
+            name: "Tilt Range",
+            data_type: DataType::Number,
+            default: DataValue::Number(270.0),
+        },
+    ],
+    outputs: &[
+        // x pan, y tilt, 0..1
+        OutputDesc {
+            id: 0,
+            name: "Pan/Tilt",
+            data_type: DataType::Vector3,
+        },
+        OutputDesc {
+            id: 1,
+            name: "Reachable",
+            data_type: DataType::Boolean,
+        },
+    ],
+    process: look_at_process,
+    // Last pan and tilt in radians, for the shortest way to the next target
+    state: &[DataValue::Number(0.0), DataValue::Number(0.0)],
+    volatile: false,
+};
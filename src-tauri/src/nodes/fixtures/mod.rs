@@ -0,0 +1,253 @@
+pub mod cue_list;
+pub mod fixture;
+pub mod group;
+pub mod look_at;
+
+#[cfg(test)]
+mod tests {
+    use crate::core::graph::Graph;
+    use crate::core::node::{NodeKind, NodeUIState};
+    use crate::cues::{Cue, CueValue};
+    use crate::fixtures::patch::PatchTable;
+    use crate::fixtures::profile::Attribute;
+    use crate::fixtures::{FixtureLibrary, FixtureMap, ResolvedFixture};
+    use crate::managers::cues_manager::CuesManager;
+    use crate::managers::fixtures_manager::FixturesManager;
+    use crate::types::color::{Color, ColorValue};
+    use crate::types::data_type::DataValue::*;
+    use crate::types::vector::Vec3;
+
+    // RGBW 4 channel at 1, moving head at 101, RGB at 201
+    fn fixtures() -> FixtureMap {
+        let library = FixtureLibrary::new();
+        let mut patch = PatchTable::new();
+        for (profile, mode, address) in [
+            ("Generic/RGBW", "4 channel", 1),
+            ("Generic/Moving Head", "Standard", 101),
+            ("Generic/RGB", "3 channel", 201),
+        ] {
+            let profile = library.get(profile).unwrap();
+            patch.patch("", profile, mode, 1, address).unwrap();
+        }
+
+        let fixtures = patch
+            .fixtures()
+            .iter()
+            .map(|x| {
+                let profile = library.get(&x.profile).unwrap();
+                (x.id, ResolvedFixture::resolve(x, profile).unwrap())
+            })
+            .collect();
+        FixtureMap {
+            fixtures,
+            ..FixtureMap::default()
+        }
+    }
+
+    #[test]
+    fn fixture_writes_attributes() {
+        let mut graph = Graph::new(0);
+        let ui_state = NodeUIState {
+            position: (0.0, 0.0),
+        };
+        for _ in 0..3 {
+            graph.add_node(NodeKind::Fixture, ui_state.clone());
+        }
+        graph.set_fixtures(std::sync::Arc::new(fixtures()));
+
+        let color = Color(Color::new(ColorValue::RGB(1.0, 0.6, 0.2)));
+        for node in 0..3 {
+            graph.set_input_value(node * 4, Number(node as f64));
+            graph.set_input_value(node * 4 + 1, Number(0.5));
+            graph.set_input_value(node * 4 + 2, color);
+        }
+        graph.set_input_value(7, Vector3(Vec3::new(1.0, 0.25, 0.0)));
+
+        graph.process();
+        let levels = graph.take_dmx_output()[&1].levels;
+
+        // No dimmer, colour scaled by intensity, white taken out of it
+        let rgbw: Vec<_> = levels[0..4].iter().map(|x| x.unwrap()).collect();
+        assert_eq!(vec![102, 51, 0, 26], rgbw);
+
+        // Dimmer on its own channel, 16 bit pan and tilt, shutter open
+        let head: Vec<_> = levels[100..111].iter().map(|x| x.unwrap()).collect();
+        assert_eq!(vec![255, 255, 64, 0, 0, 128, 255, 255, 153, 51, 0], head);
+
+        let rgb: Vec<_> = levels[200..203].iter().map(|x| x.unwrap()).collect();
+        assert_eq!(vec![128, 77, 26], rgb);
+        assert_eq!(18, levels.iter().filter(|x| x.is_some()).count());
+        assert_eq!(None, graph.get_node(0).error);
+
+        // Unpatched
+        graph.set_input_value(0, Number(7.0));
+        graph.process();
+        assert!(graph.get_node(0).error.is_some());
+        assert_eq!(None, graph.take_dmx_output()[&1].levels[0]);
+    }
+
+    #[test]
+    fn look_at_aims_fixture() {
+        let mut fixtures = FixturesManager::new();
+        let head = fixtures
+            .patch("", "Generic/Moving Head", "Standard", 1, 1)
+            .unwrap();
+
+        let mut graph = Graph::new(0);
+        let ui_state = NodeUIState {
+            position: (0.0, 0.0),
+        };
+        graph.add_node(NodeKind::LookAt, ui_state.clone());
+        graph.add_node(NodeKind::Fixture, ui_state);
+        graph.set_fixtures(std::sync::Arc::new(fixtures.fixture_map()));
+        // Pan/Tilt -> Position
+        graph.connect(0, 9);
+        graph.set_input_value(6, Number(head as f64));
+
+        // Hung at 5 m by default, 45 degrees forward
+        graph.set_input_value(0, Point3(Vec3::new(0.0, 0.0, 5.0)));
+        graph.process();
+        assert_eq!(Boolean(true), graph.get_output_value(1));
+        let levels = graph.take_dmx_output()[&1].levels;
+        // Pan centred, tilt 0.5 + 45 / 270
+        let levels: Vec<_> = levels[0..4].iter().map(|x| x.unwrap()).collect();
+        assert_eq!(vec![0x80, 0x00, 0xAA, 0xAA], levels);
+    }
+
+    // Levels of channels 1-4 with the Group node's Value, Spread and Mode
+    fn spread(graph: &mut Graph, value: f64, amount: f64, mode: f64) -> Vec<Option<u8>> {
+        graph.set_input_value(1, Number(value));
+        graph.set_input_value(2, Number(amount));
+        graph.set_input_value(3, Number(mode));
+        graph.process();
+        graph.take_dmx_output()[&1].levels[0..4].to_vec()
+    }
+
+    #[test]
+    fn group_spreads_values() {
+        // Four dimmers on channels 1-4, grouped in order
+        let mut fixtures = FixturesManager::new();
+        let members: Vec<_> = (1..=4)
+            .map(|x| fixtures.patch("", "Generic/Dimmer", "8 bit", 1, x).unwrap())
+            .collect();
+        let group = fixtures.add_group("Pars", members.clone()).unwrap();
+
+        let mut graph = Graph::new(0);
+        graph.add_node(
+            NodeKind::Group,
+            NodeUIState {
+                position: (0.0, 0.0),
+            },
+        );
+        graph.set_fixtures(std::sync::Arc::new(fixtures.fixture_map()));
+        graph.set_input_value(0, Number(group as f64));
+
+        // Later members lag and wrap around
+        let linear = spread(&mut graph, 0.5, 1.0, 0.0);
+        assert_eq!(vec![Some(128), Some(64), Some(0), Some(191)], linear);
+        assert_eq!(
+            vec![Some(191), Some(0), Some(64), Some(128)],
+            spread(&mut graph, 0.5, 1.0, 1.0)
+        );
+        assert_eq!(
+            vec![Some(0), Some(128), Some(128), Some(0)],
+            spread(&mut graph, 0.5, 1.0, 2.0)
+        );
+        assert_eq!(vec![Some(255); 4], spread(&mut graph, 1.0, 0.0, 0.0));
+
+        // A shuffle of the linear levels, the same for the same seed
+        let random = spread(&mut graph, 0.5, 1.0, 3.0);
+        assert_eq!(random, spread(&mut graph, 0.5, 1.0, 3.0));
+        let mut sorted = random.clone();
+        sorted.sort();
+        let mut expected = linear.clone();
+        expected.sort();
+        assert_eq!(expected, sorted);
+
+        // Membership order decides the spread
+        let reversed: Vec<_> = members.iter().rev().copied().collect();
+        fixtures.set_group(group, reversed).unwrap();
+        graph.set_fixtures(std::sync::Arc::new(fixtures.fixture_map()));
+        assert_eq!(
+            vec![Some(191), Some(0), Some(64), Some(128)],
+            spread(&mut graph, 0.5, 1.0, 0.0)
+        );
+
+        // Dimmers have no pan
+        graph.set_node_text(0, "Pan");
+        graph.process();
+        assert!(graph.take_dmx_output().is_empty());
+        graph.set_node_text(0, "Brightness");
+        graph.process();
+        assert!(graph.get_node(0).error.is_some());
+    }
+
+    #[test]
+    fn cue_list_writes_levels() {
+        let mut cues = CuesManager::new();
+        let list = cues.add_list("Main");
+        let values = [
+            (0, Attribute::Intensity, 0.5),
+            (0, Attribute::Red, 1.0),
+            (1, Attribute::Intensity, 1.0),
+            (1, Attribute::Pan, 0.5),
+        ];
+        let values = values
+            .iter()
+            .map(|(fixture, attribute, value)| CueValue {
+                fixture: *fixture,
+                attribute: *attribute,
+                value: *value,
+            })
+            .collect();
+        cues.set_cue(list, Cue::new(2.0, values));
+
+        let mut graph = Graph::new(0);
+        graph.add_node(
+            NodeKind::CueList,
+            NodeUIState {
+                position: (0.0, 0.0),
+            },
+        );
+        graph.set_fixtures(std::sync::Arc::new(fixtures()));
+        graph.set_input_value(0, Number(list as f64));
+
+        // Released
+        graph.set_cues(std::sync::Arc::new(cues.output()));
+        graph.process();
+        assert!(graph.take_dmx_output().is_empty());
+
+        assert!(cues.go(list));
+        graph.set_cues(std::sync::Arc::new(cues.output()));
+        graph.process();
+        assert_eq!(Number(2.0), graph.get_output_value(0));
+        assert_eq!(Boolean(false), graph.get_output_value(1));
+        let levels = graph.take_dmx_output()[&1].levels;
+
+        // Red dimmed without a dimmer channel
+        let rgbw: Vec<_> = levels[0..4].iter().map(|x| x.unwrap()).collect();
+        assert_eq!(vec![128, 0, 0, 0], rgbw);
+        // Everything else of the head at its defaults
+        let head: Vec<_> = levels[100..111].iter().map(|x| x.unwrap()).collect();
+        assert_eq!(vec![128, 0, 128, 0, 0, 255, 255, 0, 0, 0, 0], head);
+        assert_eq!(None, levels[200]);
+
+        // A level into the graph, the fixtures are left to it
+        graph.set_node_text(0, "1 Pan");
+        graph.process();
+        assert_eq!(Number(0.5), graph.get_output_value(2));
+        assert!(graph.take_dmx_output().is_empty());
+        graph.set_node_text(0, "2 Pan");
+        graph.process();
+        assert_eq!(Number(0.0), graph.get_output_value(2));
+        assert_eq!(None, graph.get_node(0).error);
+        graph.set_node_text(0, "1 Brightness");
+        graph.process();
+        assert!(graph.get_node(0).error.is_some());
+
+        graph.set_node_text(0, "");
+        graph.set_input_value(0, Number(3.0));
+        graph.process();
+        assert!(graph.get_node(0).error.is_some());
+    }
+}
@@ -0,0 +1,49 @@
+use crate::core::node::*;
+use crate::types::data_type::*;
+
+// The text is the channel name, e.g. osc/1/fader1, see InputsManager. Fields
+// of another type than what arrives keep their own value, see Graph::run.
+fn external_input_process(
+    ctx: &mut ProcessContext,
+    _inputs: Vec<DataValue>,
+    outputs: &mut [&mut DataValue],
+) {
+    let last_serial = match ctx.state[0] {
+        DataValue::Number(n) => n as u64,
+        _ => panic!("Expected Number"),
+    };
+
+    // Outputs keep the last value while nothing new arrives
+    let input = match ctx.io.inputs.get(ctx.text.trim()) {
+        Some(input) if input.serial > last_serial => input,
+        _ => return,
+    };
+    ctx.state[0] = DataValue::Number(input.serial as f64);
+
+    *outputs[0] = input.value;
+    if let DataValue::Trigger(ref mut t) = outputs[1] {
+        *t = true;
+    };
+}
+
+pub static EXTERNAL_INPUT_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "External Input",
+    inputs: &[],
+    outputs: &[
+        OutputDesc {
+            id: 0,
+            name: "Value",
+            data_type: DataType::Any,
+        },
+        // Fires whenever the channel receives a value, changed or not
+        OutputDesc {
+            id: 1,
+            name: "Received",
+            data_type: DataType::Trigger,
+        },
+    ],
+    process: external_input_process,
+    // Serial of the last value read
+    state: &[DataValue::Number(0.0)],
+    volatile: true,
+};
@@ -0,0 +1,90 @@
+pub mod external_input;
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::core::frame_io::{ExternalInputs, ExternalValue};
+    use crate::core::graph::Graph;
+    use crate::core::node::{NodeKind, NodeUIState};
+    use crate::types::data_type::DataValue::{self, *};
+
+    fn inputs(values: &[(&str, DataValue, u64)]) -> Arc<ExternalInputs> {
+        let inputs = values
+            .iter()
+            .map(|(name, value, serial)| {
+                (
+                    name.to_string(),
+                    ExternalValue {
+                        value: *value,
+                        serial: *serial,
+                    },
+                )
+            })
+            .collect();
+        Arc::new(inputs)
+    }
+
+    #[test]
+    fn external_input_reads_channel() {
+        let mut graph = Graph::new(0);
+        let ui_state = NodeUIState {
+            position: (0.0, 0.0),
+        };
+        graph.add_node(NodeKind::ExternalInput, ui_state.clone());
+        // Received -> Counter
+        graph.add_node(NodeKind::Counter, ui_state);
+        graph.connect(1, 0);
+        graph.set_node_text(0, "osc/1/fader1");
+
+        graph.process();
+        assert_eq!(Number(0.0), graph.get_output_value(0));
+
+        graph.set_external_input(inputs(&[
+            ("osc/1/fader1", Number(0.5), 1),
+            ("osc/1/fader2", Number(0.7), 2),
+        ]));
+        graph.process();
+        assert_eq!(Number(0.5), graph.get_output_value(0));
+        assert_eq!(Number(1.0), graph.get_output_value(2));
+
+        // Same serial, nothing received
+        graph.process();
+        assert_eq!(Number(1.0), graph.get_output_value(2));
+
+        // Any type passes through
+        graph.set_external_input(inputs(&[("osc/1/fader1", Boolean(true), 3)]));
+        graph.process();
+        assert_eq!(Boolean(true), graph.get_output_value(0));
+        assert_eq!(Number(2.0), graph.get_output_value(2));
+    }
+
+    #[test]
+    fn external_input_wrong_type_is_held_back() {
+        let mut graph = Graph::new(0);
+        let ui_state = NodeUIState {
+            position: (0.0, 0.0),
+        };
+        graph.add_node(NodeKind::ExternalInput, ui_state.clone());
+        // Value -> DimmerCurve Value
+        graph.add_node(NodeKind::DimmerCurve, ui_state);
+        graph.connect(0, 0);
+        graph.set_node_text(0, "http/level");
+        graph.set_input_value(0, Number(0.25));
+
+        // [1, 2, 3] PUT to a Number field, the field keeps its own value
+        graph.set_external_input(inputs(&[(
+            "http/level",
+            Vector3(crate::types::vector::Vec3::new(1.0, 2.0, 3.0)),
+            1,
+        )]));
+        graph.process();
+        assert_eq!(Number(0.25), graph.get_output_value(2));
+        assert!(graph.get_node(1).error.is_some());
+
+        graph.set_external_input(inputs(&[("http/level", Number(0.5), 2)]));
+        graph.process();
+        assert_eq!(Number(0.5), graph.get_output_value(2));
+        assert_eq!(None, graph.get_node(1).error);
+    }
+}
@@ -0,0 +1,151 @@
+use crate::core::node::*;
+use crate::types::data_type::*;
+
+fn numbers(inputs: &[DataValue]) -> Vec<f64> {
+    inputs
+        .iter()
+        .map(|x| match x {
+            DataValue::Number(number) => *number,
+            _ => panic!("Expected Number"),
+        })
+        .collect()
+}
+
+fn set_boolean(outputs: &mut [&mut DataValue], value: bool) {
+    if let DataValue::Boolean(ref mut b) = outputs[0] {
+        *b = value;
+    };
+}
+
+fn greater_process(
+    _ctx: &mut ProcessContext,
+    inputs: Vec<DataValue>,
+    outputs: &mut [&mut DataValue],
+) {
+    let n = numbers(&inputs);
+    set_boolean(outputs, n[0] > n[1]);
+}
+
+fn less_process(_ctx: &mut ProcessContext, inputs: Vec<DataValue>, outputs: &mut [&mut DataValue]) {
+    let n = numbers(&inputs);
+    set_boolean(outputs, n[0] < n[1]);
+}
+
+fn equal_process(
+    _ctx: &mut ProcessContext,
+    inputs: Vec<DataValue>,
+    outputs: &mut [&mut DataValue],
+) {
+    let n = numbers(&inputs);
+    set_boolean(outputs, (n[0] - n[1]).abs() <= n[2].abs());
+}
+
+// Inclusive on both ends, min and max may be given in either order
+fn in_range_process(
+    _ctx: &mut ProcessContext,
+    inputs: Vec<DataValue>,
+    outputs: &mut [&mut DataValue],
+) {
+    let n = numbers(&inputs);
+    let (min, max) = if n[1] <= n[2] {
+        (n[1], n[2])
+    } else {
+        (n[2], n[1])
+    };
+    set_boolean(outputs, n[0] >= min && n[0] <= max);
+}
+
+const A_B_INPUTS: &[InputDesc] = &[
+    InputDesc {
+        id: 0,
+        name: "A",
+        data_type: DataType::Number,
+        default: DataValue::default(DataType::Number),
+    },
+    InputDesc {
+        id: 1,
+        name: "B",
+        data_type: DataType::Number,
+        default: DataValue::default(DataType::Number),
+    },
+];
+
+const RESULT_OUTPUT: &[OutputDesc] = &[OutputDesc {
+    id: 0,
+    name: "Result",
+    data_type: DataType::Boolean,
+}];
+
+pub static GREATER_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "Greater Than",
+    inputs: A_B_INPUTS,
+    outputs: RESULT_OUTPUT,
+    process: greater_process,
+    state: &[],
+    volatile: false,
+};
+
+pub static LESS_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "Less Than",
+    inputs: A_B_INPUTS,
+    outputs: RESULT_OUTPUT,
+    process: less_process,
+    state: &[],
+    volatile: false,
+};
+
+pub static EQUAL_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "Equal",
+    inputs: &[
+        InputDesc {
+            id: 0,
+            name: "A",
+            data_type: DataType::Number,
+            default: DataValue::default(DataType::Number),
+        },
+        InputDesc {
+            id: 1,
+            name: "B",
+            data_type: DataType::Number,
+            default: DataValue::default(DataType::Number),
+        },
+        InputDesc {
+            id: 2,
+            name: "Tolerance",
+            data_type: DataType::Number,
+            default: DataValue::Number(0.00001),
+        },
+    ],
+    outputs: RESULT_OUTPUT,
+    process: equal_process,
+    state: &[],
+    volatile: false,
+};
+
+pub static IN_RANGE_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "In Range",
+    inputs: &[
+        InputDesc {
+            id: 0,
+            name: "Value",
+            data_type: DataType::Number,
+            default: DataValue::default(DataType::Number),
+        },
+        InputDesc {
+            id: 1,
+            name: "Min",
+            data_type: DataType::Number,
+            default: DataValue::Number(0.0),
+        },
+        InputDesc {
+            id: 2,
+            name: "Max",
+            data_type: DataType::Number,
+            default: DataValue::Number(1.0),
+        },
+    ],
+    outputs: RESULT_OUTPUT,
+    process: in_range_process,
+    state: &[],
+    volatile: false,
+};
@@ -0,0 +1,90 @@
+use crate::core::node::*;
+use crate::types::data_type::*;
+
+fn number_to_boolean_process(
+    _ctx: &mut ProcessContext,
+    inputs: Vec<DataValue>,
+    outputs: &mut [&mut DataValue],
+) {
+    let (value, threshold) = match (inputs[0], inputs[1]) {
+        (DataValue::Number(v), DataValue::Number(t)) => (v, t),
+        _ => panic!("Expected Number"),
+    };
+
+    if let DataValue::Boolean(ref mut b) = outputs[0] {
+        *b = value >= threshold;
+    };
+}
+
+fn boolean_to_number_process(
+    _ctx: &mut ProcessContext,
+    inputs: Vec<DataValue>,
+    outputs: &mut [&mut DataValue],
+) {
+    let (value, on, off) = match (inputs[0], inputs[1], inputs[2]) {
+        (DataValue::Boolean(v), DataValue::Number(on), DataValue::Number(off)) => (v, on, off),
+        _ => panic!("Expected Boolean, Number, Number"),
+    };
+
+    if let DataValue::Number(ref mut n) = outputs[0] {
+        *n = if value { on } else { off };
+    };
+}
+
+pub static NUMBER_TO_BOOLEAN_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "Number To Boolean",
+    inputs: &[
+        InputDesc {
+            id: 0,
+            name: "Number",
+            data_type: DataType::Number,
+            default: DataValue::default(DataType::Number),
+        },
+        InputDesc {
+            id: 1,
+            name: "Threshold",
+            data_type: DataType::Number,
+            default: DataValue::Number(0.5),
+        },
+    ],
+    outputs: &[OutputDesc {
+        id: 0,
+        name: "Boolean",
+        data_type: DataType::Boolean,
+    }],
+    process: number_to_boolean_process,
+    state: &[],
+    volatile: false,
+};
+
+pub static BOOLEAN_TO_NUMBER_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "Boolean To Number",
+    inputs: &[
+        InputDesc {
+            id: 0,
+            name: "Boolean",
+            data_type: DataType::Boolean,
+            default: DataValue::default(DataType::Boolean),
+        },
+        InputDesc {
+            id: 1,
+            name: "On",
+            data_type: DataType::Number,
+            default: DataValue::Number(1.0),
+        },
+        InputDesc {
+            id: 2,
+            name: "Off",
+            data_type: DataType::Number,
+            default: DataValue::Number(0.0),
+        },
+    ],
+    outputs: &[OutputDesc {
+        id: 0,
+        name: "Number",
+        data_type: DataType::Number,
+    }],
+    process: boolean_to_number_process,
+    state: &[],
+    volatile: false,
+};
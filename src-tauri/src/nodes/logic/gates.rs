@@ -0,0 +1,100 @@
+use crate::core::node::*;
+use crate::types::data_type::*;
+
+fn booleans(inputs: &[DataValue]) -> Vec<bool> {
+    inputs
+        .iter()
+        .map(|x| match x {
+            DataValue::Boolean(boolean) => *boolean,
+            _ => panic!("Expected Boolean"),
+        })
+        .collect()
+}
+
+fn set_boolean(outputs: &mut [&mut DataValue], value: bool) {
+    if let DataValue::Boolean(ref mut b) = outputs[0] {
+        *b = value;
+    };
+}
+
+fn and_process(_ctx: &mut ProcessContext, inputs: Vec<DataValue>, outputs: &mut [&mut DataValue]) {
+    let b = booleans(&inputs);
+    set_boolean(outputs, b[0] && b[1]);
+}
+
+fn or_process(_ctx: &mut ProcessContext, inputs: Vec<DataValue>, outputs: &mut [&mut DataValue]) {
+    let b = booleans(&inputs);
+    set_boolean(outputs, b[0] || b[1]);
+}
+
+fn xor_process(_ctx: &mut ProcessContext, inputs: Vec<DataValue>, outputs: &mut [&mut DataValue]) {
+    let b = booleans(&inputs);
+    set_boolean(outputs, b[0] != b[1]);
+}
+
+fn not_process(_ctx: &mut ProcessContext, inputs: Vec<DataValue>, outputs: &mut [&mut DataValue]) {
+    let b = booleans(&inputs);
+    set_boolean(outputs, !b[0]);
+}
+
+const A_B_INPUTS: &[InputDesc] = &[
+    InputDesc {
+        id: 0,
+        name: "A",
+        data_type: DataType::Boolean,
+        default: DataValue::default(DataType::Boolean),
+    },
+    InputDesc {
+        id: 1,
+        name: "B",
+        data_type: DataType::Boolean,
+        default: DataValue::default(DataType::Boolean),
+    },
+];
+
+const RESULT_OUTPUT: &[OutputDesc] = &[OutputDesc {
+    id: 0,
+    name: "Result",
+    data_type: DataType::Boolean,
+}];
+
+pub static AND_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "And",
+    inputs: A_B_INPUTS,
+    outputs: RESULT_OUTPUT,
+    process: and_process,
+    state: &[],
+    volatile: false,
+};
+
+pub static OR_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "Or",
+    inputs: A_B_INPUTS,
+    outputs: RESULT_OUTPUT,
+    process: or_process,
+    state: &[],
+    volatile: false,
+};
+
+pub static XOR_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "Xor",
+    inputs: A_B_INPUTS,
+    outputs: RESULT_OUTPUT,
+    process: xor_process,
+    state: &[],
+    volatile: false,
+};
+
+pub static NOT_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "Not",
+    inputs: &[InputDesc {
+        id: 0,
+        name: "Value",
+        data_type: DataType::Boolean,
+        default: DataValue::default(DataType::Boolean),
+    }],
+    outputs: RESULT_OUTPUT,
+    process: not_process,
+    state: &[],
+    volatile: false,
+};
@@ -0,0 +1,133 @@
+pub mod compare;
+pub mod convert;
+pub mod gates;
+pub mod select;
+
+#[cfg(test)]
+mod tests {
+    use crate::core::frame_io::FrameIo;
+    use crate::core::node::{NodeDescriptor, ProcessContext};
+    use crate::types::color;
+    use crate::types::data_type::*;
+
+    fn run(desc: &NodeDescriptor, inputs: Vec<DataValue>) -> DataValue {
+        let mut output = DataValue::default(desc.outputs[0].data_type);
+        let mut context = ProcessContext {
+            time: 0.0,
+            delta: 0.0,
+            state: &mut Vec::new(),
+            text: "",
+            cache: &mut None,
+            error: &mut None,
+            io: &mut FrameIo::default(),
+        };
+        (desc.process)(&mut context, inputs, &mut [&mut output]);
+        output
+    }
+
+    #[test]
+    fn comparisons() {
+        use super::compare::*;
+        use DataValue::*;
+
+        assert_eq!(
+            Boolean(true),
+            run(&GREATER_DESCRIPTOR, vec![Number(2.0), Number(1.0)])
+        );
+        assert_eq!(
+            Boolean(false),
+            run(&GREATER_DESCRIPTOR, vec![Number(1.0), Number(1.0)])
+        );
+        assert_eq!(
+            Boolean(true),
+            run(&LESS_DESCRIPTOR, vec![Number(0.5), Number(1.0)])
+        );
+        assert_eq!(
+            Boolean(true),
+            run(
+                &EQUAL_DESCRIPTOR,
+                vec![Number(0.1 + 0.2), Number(0.3), Number(0.00001)]
+            )
+        );
+        assert_eq!(
+            Boolean(false),
+            run(
+                &EQUAL_DESCRIPTOR,
+                vec![Number(0.5), Number(0.6), Number(0.05)]
+            )
+        );
+        assert_eq!(
+            Boolean(true),
+            run(
+                &IN_RANGE_DESCRIPTOR,
+                vec![Number(1.0), Number(2.0), Number(0.0)]
+            )
+        );
+        assert_eq!(
+            Boolean(false),
+            run(
+                &IN_RANGE_DESCRIPTOR,
+                vec![Number(2.5), Number(0.0), Number(2.0)]
+            )
+        );
+    }
+
+    #[test]
+    fn gates() {
+        use super::gates::*;
+        use DataValue::*;
+
+        let cases = [(false, false), (false, true), (true, false), (true, true)];
+        for (a, b) in cases {
+            let inputs = vec![Boolean(a), Boolean(b)];
+            assert_eq!(Boolean(a && b), run(&AND_DESCRIPTOR, inputs.clone()));
+            assert_eq!(Boolean(a || b), run(&OR_DESCRIPTOR, inputs.clone()));
+            assert_eq!(Boolean(a ^ b), run(&XOR_DESCRIPTOR, inputs));
+        }
+        assert_eq!(Boolean(false), run(&NOT_DESCRIPTOR, vec![Boolean(true)]));
+    }
+
+    #[test]
+    fn select_any_type() {
+        use super::select::*;
+        use DataValue::*;
+
+        let red = Color(color::Color::new(color::ColorValue::RGB(1.0, 0.0, 0.0)));
+        assert_eq!(
+            red,
+            run(&SELECT_DESCRIPTOR, vec![Boolean(true), red, Number(1.0)])
+        );
+        assert_eq!(
+            Number(1.0),
+            run(&SELECT_DESCRIPTOR, vec![Boolean(false), red, Number(1.0)])
+        );
+    }
+
+    #[test]
+    fn conversions() {
+        use super::convert::*;
+        use DataValue::*;
+
+        assert_eq!(
+            Boolean(true),
+            run(
+                &NUMBER_TO_BOOLEAN_DESCRIPTOR,
+                vec![Number(0.5), Number(0.5)]
+            )
+        );
+        assert_eq!(
+            Boolean(false),
+            run(
+                &NUMBER_TO_BOOLEAN_DESCRIPTOR,
+                vec![Number(0.49), Number(0.5)]
+            )
+        );
+        assert_eq!(
+            Number(255.0),
+            run(
+                &BOOLEAN_TO_NUMBER_DESCRIPTOR,
+                vec![Boolean(true), Number(255.0), Number(0.0)]
+            )
+        );
+    }
+}
@@ -0,0 +1,48 @@
+use crate::core::node::*;
+use crate::types::data_type::*;
+
+// Passes through "True" or "False" depending on the condition, whatever their type
+fn select_process(
+    _ctx: &mut ProcessContext,
+    inputs: Vec<DataValue>,
+    outputs: &mut [&mut DataValue],
+) {
+    let condition = match inputs[0] {
+        DataValue::Boolean(boolean) => boolean,
+        _ => panic!("Expected Boolean"),
+    };
+
+    *outputs[0] = if condition { inputs[1] } else { inputs[2] };
+}
+
+pub static SELECT_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "Select",
+    inputs: &[
+        InputDesc {
+            id: 0,
+            name: "Condition",
+            data_type: DataType::Boolean,
+            default: DataValue::default(DataType::Boolean),
+        },
+        InputDesc {
+            id: 1,
+            name: "True",
+            data_type: DataType::Any,
+            default: DataValue::default(DataType::Any),
+        },
+        InputDesc {
+            id: 2,
+            name: "False",
+            data_type: DataType::Any,
+            default: DataValue::default(DataType::Any),
+        },
+    ],
+    outputs: &[OutputDesc {
+        id: 0,
+        name: "Result",
+        data_type: DataType::Any,
+    }],
+    process: select_process,
+    state: &[],
+    volatile: false,
+};
@@ -0,0 +1,45 @@
+use crate::core::node::*;
+use crate::types::data_type::*;
+use crate::types::interpolation;
+
+fn clamp_process(ctx: &mut ProcessContext, inputs: Vec<DataValue>, outputs: &mut [&mut DataValue]) {
+    match DataValue::map_components("clamp", &inputs, |x| interpolation::clamp(x[0], x[1], x[2])) {
+        Ok(value) => {
+            *ctx.error = None;
+            *outputs[0] = value;
+        }
+        Err(error) => *ctx.error = Some(error.to_string()),
+    }
+}
+
+pub static CLAMP_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "Clamp",
+    inputs: &[
+        InputDesc {
+            id: 0,
+            name: "Value",
+            data_type: DataType::Any,
+            default: DataValue::default(DataType::Number),
+        },
+        InputDesc {
+            id: 1,
+            name: "Min",
+            data_type: DataType::Number,
+            default: DataValue::Number(0.0),
+        },
+        InputDesc {
+            id: 2,
+            name: "Max",
+            data_type: DataType::Number,
+            default: DataValue::Number(1.0),
+        },
+    ],
+    outputs: &[OutputDesc {
+        id: 0,
+        name: "Value",
+        data_type: DataType::Any,
+    }],
+    process: clamp_process,
+    state: &[],
+    volatile: false,
+};
@@ -0,0 +1,54 @@
+use crate::core::node::*;
+use crate::types::data_type::*;
+use crate::types::interpolation::{EaseMode, Easing};
+
+fn ease_process(ctx: &mut ProcessContext, inputs: Vec<DataValue>, outputs: &mut [&mut DataValue]) {
+    let (family, mode) = match (inputs[1], inputs[2]) {
+        (DataValue::Number(f), DataValue::Number(m)) => {
+            (Easing::from_index(f), EaseMode::from_index(m))
+        }
+        _ => panic!("Expected Number"),
+    };
+
+    match DataValue::map_components("ease", &inputs[0..1], |x| family.apply(mode, x[0])) {
+        Ok(value) => {
+            *ctx.error = None;
+            *outputs[0] = value;
+        }
+        Err(error) => *ctx.error = Some(error.to_string()),
+    }
+}
+
+pub static EASE_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "Ease",
+    inputs: &[
+        InputDesc {
+            id: 0,
+            name: "Value",
+            data_type: DataType::Any,
+            default: DataValue::default(DataType::Number),
+        },
+        // 0 = Linear, 1 = Quad, 2 = Cubic, 3 = Sine, 4 = Expo, 5 = Elastic, 6 = Bounce
+        InputDesc {
+            id: 1,
+            name: "Family",
+            data_type: DataType::Number,
+            default: DataValue::Number(1.0),
+        },
+        // 0 = In, 1 = Out, 2 = In Out
+        InputDesc {
+            id: 2,
+            name: "Mode",
+            data_type: DataType::Number,
+            default: DataValue::Number(2.0),
+        },
+    ],
+    outputs: &[OutputDesc {
+        id: 0,
+        name: "Value",
+        data_type: DataType::Any,
+    }],
+    process: ease_process,
+    state: &[],
+    volatile: false,
+};
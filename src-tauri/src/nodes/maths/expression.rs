@@ -0,0 +1,85 @@
+use std::sync::Arc;
+
+use crate::core::node::*;
+use crate::expression::{self, Expr, ParseError};
+use crate::types::data_type::*;
+
+// Inputs a to d, then the graph time
+const VARIABLES: &[&str] = &["a", "b", "c", "d", "t"];
+
+fn expression_process(
+    ctx: &mut ProcessContext,
+    inputs: Vec<DataValue>,
+    outputs: &mut [&mut DataValue],
+) {
+    if ctx.text.trim().is_empty() {
+        return;
+    }
+
+    // Parse once per text change, errors included
+    if ctx.cache.is_none() {
+        *ctx.cache = Some(Arc::new(expression::parse(ctx.text, VARIABLES)));
+    }
+    let parsed = ctx
+        .cache
+        .as_ref()
+        .and_then(|x| x.downcast_ref::<Result<Expr, ParseError>>());
+
+    let expr = match parsed {
+        Some(Ok(expr)) => expr,
+        Some(Err(error)) => {
+            *ctx.error = Some(error.to_string());
+            return;
+        }
+        None => return,
+    };
+
+    let mut variables = inputs.clone();
+    variables.push(DataValue::Number(ctx.time));
+
+    match expression::evaluate(expr, &variables) {
+        Ok(value) => {
+            *ctx.error = None;
+            *outputs[0] = value;
+        }
+        Err(error) => *ctx.error = Some(error.to_string()),
+    }
+}
+
+pub static EXPRESSION_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "Expression",
+    inputs: &[
+        InputDesc {
+            id: 0,
+            name: "a",
+            data_type: DataType::Any,
+            default: DataValue::default(DataType::Number),
+        },
+        InputDesc {
+            id: 1,
+            name: "b",
+            data_type: DataType::Any,
+            default: DataValue::default(DataType::Number),
+        },
+        InputDesc {
+            id: 2,
+            name: "c",
+            data_type: DataType::Any,
+            default: DataValue::default(DataType::Number),
+        },
+        InputDesc {
+            id: 3,
+            name: "d",
+            data_type: DataType::Any,
+            default: DataValue::default(DataType::Number),
+        },
+    ],
+    outputs: &[OutputDesc {
+        id: 0,
+        name: "Result",
+        data_type: DataType::Any,
+    }],
+    process: expression_process,
+    state: &[],
+    volatile: true,
+};
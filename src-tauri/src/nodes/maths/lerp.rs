@@ -0,0 +1,45 @@
+use crate::core::node::*;
+use crate::types::data_type::*;
+use crate::types::interpolation;
+
+fn lerp_process(ctx: &mut ProcessContext, inputs: Vec<DataValue>, outputs: &mut [&mut DataValue]) {
+    match DataValue::map_components("lerp", &inputs, |x| interpolation::lerp(x[0], x[1], x[2])) {
+        Ok(value) => {
+            *ctx.error = None;
+            *outputs[0] = value;
+        }
+        Err(error) => *ctx.error = Some(error.to_string()),
+    }
+}
+
+pub static LERP_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "Lerp",
+    inputs: &[
+        InputDesc {
+            id: 0,
+            name: "A",
+            data_type: DataType::Any,
+            default: DataValue::Number(0.0),
+        },
+        InputDesc {
+            id: 1,
+            name: "B",
+            data_type: DataType::Any,
+            default: DataValue::Number(1.0),
+        },
+        InputDesc {
+            id: 2,
+            name: "T",
+            data_type: DataType::Number,
+            default: DataValue::Number(0.5),
+        },
+    ],
+    outputs: &[OutputDesc {
+        id: 0,
+        name: "Value",
+        data_type: DataType::Any,
+    }],
+    process: lerp_process,
+    state: &[],
+    volatile: false,
+};
@@ -0,0 +1,71 @@
+use crate::core::node::*;
+use crate::types::data_type::*;
+use crate::types::interpolation;
+
+fn map_range_process(
+    ctx: &mut ProcessContext,
+    inputs: Vec<DataValue>,
+    outputs: &mut [&mut DataValue],
+) {
+    let clamp = inputs[5].is_active();
+
+    match DataValue::map_components("map range", &inputs[0..5], |x| {
+        interpolation::map_range(x[0], x[1], x[2], x[3], x[4], clamp)
+    }) {
+        Ok(value) => {
+            *ctx.error = None;
+            *outputs[0] = value;
+        }
+        Err(error) => *ctx.error = Some(error.to_string()),
+    }
+}
+
+pub static MAP_RANGE_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "Map Range",
+    inputs: &[
+        InputDesc {
+            id: 0,
+            name: "Value",
+            data_type: DataType::Any,
+            default: DataValue::default(DataType::Number),
+        },
+        InputDesc {
+            id: 1,
+            name: "In Min",
+            data_type: DataType::Number,
+            default: DataValue::Number(0.0),
+        },
+        InputDesc {
+            id: 2,
+            name: "In Max",
+            data_type: DataType::Number,
+            default: DataValue::Number(1.0),
+        },
+        InputDesc {
+            id: 3,
+            name: "Out Min",
+            data_type: DataType::Number,
+            default: DataValue::Number(0.0),
+        },
+        InputDesc {
+            id: 4,
+            name: "Out Max",
+            data_type: DataType::Number,
+            default: DataValue::Number(1.0),
+        },
+        InputDesc {
+            id: 5,
+            name: "Clamp",
+            data_type: DataType::Boolean,
+            default: DataValue::Boolean(true),
+        },
+    ],
+    outputs: &[OutputDesc {
+        id: 0,
+        name: "Value",
+        data_type: DataType::Any,
+    }],
+    process: map_range_process,
+    state: &[],
+    volatile: false,
+};
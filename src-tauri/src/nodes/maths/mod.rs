@@ -1,2 +1,41 @@
 pub mod add;
-pub mod multiply;
\ No newline at end of file
+pub mod clamp;
+pub mod ease;
+pub mod expression;
+pub mod lerp;
+pub mod map_range;
+pub mod multiply;
+pub mod smoothstep;
+
+#[cfg(test)]
+mod tests {
+    use crate::core::graph::Graph;
+    use crate::core::node::{NodeKind, NodeUIState};
+    use crate::types::data_type::DataValue::*;
+
+    #[test]
+    fn component_errors_are_reported() {
+        let mut graph = Graph::new(0);
+        graph.add_node(
+            NodeKind::Lerp,
+            NodeUIState {
+                position: (0.0, 0.0),
+            },
+        );
+
+        graph.process();
+        assert_eq!(Number(0.5), graph.get_output_value(0));
+        assert!(graph.get_node(0).error.is_none());
+
+        // No components to interpolate, the last output is kept
+        graph.set_input_value(0, Boolean(true));
+        graph.process();
+        assert_eq!(Number(0.5), graph.get_output_value(0));
+        assert!(graph.get_node(0).error.is_some());
+
+        graph.set_input_value(0, Number(1.0));
+        graph.process();
+        assert_eq!(Number(1.0), graph.get_output_value(0));
+        assert!(graph.get_node(0).error.is_none());
+    }
+}
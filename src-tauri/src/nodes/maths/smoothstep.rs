@@ -0,0 +1,51 @@
+use crate::core::node::*;
+use crate::types::data_type::*;
+use crate::types::interpolation;
+
+fn smoothstep_process(
+    ctx: &mut ProcessContext,
+    inputs: Vec<DataValue>,
+    outputs: &mut [&mut DataValue],
+) {
+    match DataValue::map_components("smoothstep", &inputs, |x| {
+        interpolation::smoothstep(x[1], x[2], x[0])
+    }) {
+        Ok(value) => {
+            *ctx.error = None;
+            *outputs[0] = value;
+        }
+        Err(error) => *ctx.error = Some(error.to_string()),
+    }
+}
+
+pub static SMOOTHSTEP_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "Smoothstep",
+    inputs: &[
+        InputDesc {
+            id: 0,
+            name: "Value",
+            data_type: DataType::Any,
+            default: DataValue::default(DataType::Number),
+        },
+        InputDesc {
+            id: 1,
+            name: "Edge 0",
+            data_type: DataType::Number,
+            default: DataValue::Number(0.0),
+        },
+        InputDesc {
+            id: 2,
+            name: "Edge 1",
+            data_type: DataType::Number,
+            default: DataValue::Number(1.0),
+        },
+    ],
+    outputs: &[OutputDesc {
+        id: 0,
+        name: "Value",
+        data_type: DataType::Any,
+    }],
+    process: smoothstep_process,
+    state: &[],
+    volatile: false,
+};
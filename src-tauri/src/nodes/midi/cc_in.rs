@@ -0,0 +1,64 @@
+use crate::core::node::*;
+use crate::nodes::midi::{channel_index, data_byte};
+use crate::types::data_type::*;
+
+fn midi_cc_in_process(
+    ctx: &mut ProcessContext,
+    inputs: Vec<DataValue>,
+    outputs: &mut [&mut DataValue],
+) {
+    let (channel, controller) = match (inputs[0], inputs[1]) {
+        (DataValue::Number(c), DataValue::Number(n)) => (channel_index(c), data_byte(n)),
+        _ => panic!("Expected Number"),
+    };
+
+    // Controllers that haven't moved yet read as 0
+    let raw = ctx
+        .io
+        .midi_in
+        .controllers
+        .get(&(channel, controller))
+        .copied()
+        .unwrap_or(0);
+
+    if let DataValue::Number(ref mut n) = outputs[0] {
+        *n = raw as f64 / 127.0;
+    };
+    if let DataValue::Number(ref mut n) = outputs[1] {
+        *n = raw as f64;
+    };
+}
+
+pub static MIDI_CC_IN_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "MIDI CC In",
+    inputs: &[
+        // 1..16
+        InputDesc {
+            id: 0,
+            name: "Channel",
+            data_type: DataType::Number,
+            default: DataValue::Number(1.0),
+        },
+        InputDesc {
+            id: 1,
+            name: "Controller",
+            data_type: DataType::Number,
+            default: DataValue::Number(0.0),
+        },
+    ],
+    outputs: &[
+        OutputDesc {
+            id: 0,
+            name: "Value",
+            data_type: DataType::Number,
+        },
+        OutputDesc {
+            id: 1,
+            name: "Raw",
+            data_type: DataType::Number,
+        },
+    ],
+    process: midi_cc_in_process,
+    state: &[],
+    volatile: true,
+};
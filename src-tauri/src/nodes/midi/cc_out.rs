@@ -0,0 +1,66 @@
+use crate::core::node::*;
+use crate::midi::message::MidiMessage;
+use crate::nodes::midi::{channel_index, data_byte};
+use crate::types::data_type::*;
+
+fn midi_cc_out_process(
+    ctx: &mut ProcessContext,
+    inputs: Vec<DataValue>,
+    _outputs: &mut [&mut DataValue],
+) {
+    let (channel, controller, value) = match (inputs[0], inputs[1], inputs[2]) {
+        (DataValue::Number(c), DataValue::Number(n), DataValue::Number(v)) => (
+            channel_index(c),
+            data_byte(n),
+            data_byte(v.clamp(0.0, 1.0) * 127.0),
+        ),
+        _ => panic!("Expected Number"),
+    };
+
+    // -1 until the first frame, which only records the value like OscOut
+    let last = match ctx.state[0] {
+        DataValue::Number(n) => n,
+        _ => panic!("Expected Number"),
+    };
+    ctx.state[0] = DataValue::Number(value as f64);
+
+    // Only whole steps are sent, small changes don't flood the port
+    if last >= 0.0 && last != value as f64 {
+        ctx.io.midi_out.push(MidiMessage::ControlChange {
+            channel,
+            controller,
+            value,
+        });
+    }
+}
+
+pub static MIDI_CC_OUT_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "MIDI CC Out",
+    inputs: &[
+        // 1..16
+        InputDesc {
+            id: 0,
+            name: "Channel",
+            data_type: DataType::Number,
+            default: DataValue::Number(1.0),
+        },
+        InputDesc {
+            id: 1,
+            name: "Controller",
+            data_type: DataType::Number,
+            default: DataValue::Number(0.0),
+        },
+        // 0..1
+        InputDesc {
+            id: 2,
+            name: "Value",
+            data_type: DataType::Number,
+            default: DataValue::Number(0.0),
+        },
+    ],
+    outputs: &[],
+    process: midi_cc_out_process,
+    // Last value, 0..127
+    state: &[DataValue::Number(-1.0)],
+    volatile: true,
+};
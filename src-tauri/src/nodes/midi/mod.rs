@@ -0,0 +1,183 @@
+pub mod cc_in;
+pub mod cc_out;
+pub mod note_in;
+
+// "Channel" inputs count from 1 like the devices do
+fn channel_index(channel: f64) -> u8 {
+    (channel.round().clamp(1.0, 16.0) - 1.0) as u8
+}
+
+// Note, controller or value, 0..127
+fn data_byte(value: f64) -> u8 {
+    value.round().clamp(0.0, 127.0) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::core::graph::Graph;
+    use crate::core::node::{NodeKind, NodeUIState};
+    use crate::managers::midi_manager::{LearnTarget, MidiManager};
+    use crate::midi::port::VirtualPort;
+    use crate::types::data_type::DataValue::*;
+
+    fn graph_with(kinds: &[NodeKind]) -> Graph {
+        let mut graph = Graph::new(0);
+        for kind in kinds {
+            graph.add_node(
+                *kind,
+                NodeUIState {
+                    position: (0.0, 0.0),
+                },
+            );
+        }
+        graph
+    }
+
+    // One frame the way the app runs it: read ports, process, send
+    fn frame(graph: &mut Graph, midi: &mut MidiManager) {
+        midi.check_learn(graph);
+        midi.poll();
+        if let Some(learned) = midi.take_learned() {
+            learned.apply(graph);
+        }
+        graph.set_midi_input(Arc::new(midi.inputs().clone()));
+        graph.process();
+        midi.send(&graph.take_midi_output());
+    }
+
+    #[test]
+    fn notes_and_controllers_in() {
+        // Note In (inputs 0-1, outputs 0-2), CC In (inputs 2-3, outputs 3-4), Counter on "On"
+        let mut graph = graph_with(&[NodeKind::MidiNoteIn, NodeKind::MidiCCIn, NodeKind::Counter]);
+        graph.connect(2, 4);
+        graph.set_input_value(1, Number(64.0));
+        graph.set_input_value(2, Number(2.0));
+        graph.set_input_value(3, Number(7.0));
+
+        let port = VirtualPort::new("Controller");
+        let mut midi = MidiManager::new();
+        midi.add_port(Box::new(port.clone()));
+
+        port.inject(&[0x90, 64, 127, 0xB1, 7, 127]);
+        frame(&mut graph, &mut midi);
+        assert_eq!(Boolean(true), graph.get_output_value(0));
+        assert_eq!(Number(1.0), graph.get_output_value(1));
+        assert_eq!(Number(1.0), graph.get_output_value(3));
+        assert_eq!(Number(127.0), graph.get_output_value(4));
+        assert_eq!(Number(1.0), graph.get_output_value(5));
+
+        // Held notes only fire "On" once, the wrong channel is ignored
+        port.inject(&[0xB0, 7, 0]);
+        frame(&mut graph, &mut midi);
+        assert_eq!(Number(1.0), graph.get_output_value(5));
+        assert_eq!(Number(127.0), graph.get_output_value(4));
+
+        port.inject(&[0x90, 64, 0]);
+        frame(&mut graph, &mut midi);
+        assert_eq!(Boolean(false), graph.get_output_value(0));
+        assert_eq!(Number(1.0), graph.get_output_value(1));
+
+        port.inject(&[0x90, 64, 10]);
+        frame(&mut graph, &mut midi);
+        assert_eq!(Number(2.0), graph.get_output_value(5));
+
+        // Tapped within one frame: on for that frame, then off
+        port.inject(&[0x80, 64, 0]);
+        frame(&mut graph, &mut midi);
+        port.inject(&[0x90, 64, 100, 0x80, 64, 0]);
+        frame(&mut graph, &mut midi);
+        assert_eq!(Boolean(true), graph.get_output_value(0));
+        assert_eq!(Number(3.0), graph.get_output_value(5));
+        frame(&mut graph, &mut midi);
+        assert_eq!(Boolean(false), graph.get_output_value(0));
+        assert_eq!(Number(3.0), graph.get_output_value(5));
+    }
+
+    #[test]
+    fn controllers_out() {
+        let mut graph = graph_with(&[NodeKind::MidiCCOut]);
+        graph.set_input_value(0, Number(3.0));
+        graph.set_input_value(1, Number(20.0));
+
+        let port = VirtualPort::new("Faders");
+        let mut midi = MidiManager::new();
+        midi.add_port(Box::new(port.clone()));
+
+        frame(&mut graph, &mut midi);
+        assert!(port.take_sent().is_empty());
+
+        graph.set_input_value(2, Number(1.0));
+        frame(&mut graph, &mut midi);
+        // Changes smaller than a step aren't sent
+        graph.set_input_value(2, Number(0.999));
+        frame(&mut graph, &mut midi);
+        assert_eq!(vec![0xB2, 20, 127], port.take_sent());
+    }
+
+    #[test]
+    fn learn_binds_next_message() {
+        let mut graph = graph_with(&[NodeKind::MidiCCIn]);
+        let port = VirtualPort::new("Controller");
+        let mut midi = MidiManager::new();
+        midi.add_port(Box::new(port.clone()));
+
+        assert!(!midi.learn(LearnTarget {
+            graph_id: 0,
+            node_id: 0,
+            kind: NodeKind::Counter,
+            renumbered: 0,
+        }));
+        assert!(midi.learn(LearnTarget {
+            graph_id: 0,
+            node_id: 0,
+            kind: NodeKind::MidiCCIn,
+            renumbered: 0,
+        }));
+
+        // Notes don't fit a CC node, it keeps waiting
+        port.inject(&[0x90, 1, 1]);
+        frame(&mut graph, &mut midi);
+        assert!(midi.learning().is_some());
+
+        port.inject(&[0xB4, 21, 64]);
+        frame(&mut graph, &mut midi);
+        assert!(midi.learning().is_none());
+        assert_eq!(Number(5.0), graph.get_input_value(0));
+        assert_eq!(Number(21.0), graph.get_input_value(1));
+
+        // The learned message is already read in the same frame
+        assert_eq!(Number(64.0), graph.get_output_value(1));
+    }
+
+    #[test]
+    fn learn_ends_when_node_is_removed() {
+        let kinds = [NodeKind::MidiCCIn, NodeKind::MidiCCIn, NodeKind::MidiCCIn];
+        let mut graph = graph_with(&kinds);
+        let port = VirtualPort::new("Controller");
+        let mut midi = MidiManager::new();
+        midi.add_port(Box::new(port.clone()));
+
+        // The last node moves into the id of the removed one
+        assert!(midi.learn(LearnTarget::new(&graph, 0, 1).unwrap()));
+        graph.remove_node(1);
+        port.inject(&[0xB4, 21, 64]);
+        frame(&mut graph, &mut midi);
+        assert!(midi.learning().is_none());
+        assert_eq!(Number(1.0), graph.get_input_value(2));
+        assert_eq!(Number(0.0), graph.get_input_value(3));
+
+        // Removed after the message arrived, before it was applied
+        assert!(midi.learn(LearnTarget::new(&graph, 0, 1).unwrap()));
+        port.inject(&[0xB4, 21, 64]);
+        midi.poll();
+        graph.remove_node(0);
+        midi.take_learned().unwrap().apply(&mut graph);
+        assert_eq!(Number(1.0), graph.get_input_value(0));
+        assert_eq!(Number(0.0), graph.get_input_value(1));
+
+        assert!(LearnTarget::new(&graph, 0, 1).is_none());
+    }
+}
+
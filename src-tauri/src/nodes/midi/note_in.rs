@@ -0,0 +1,75 @@
+use crate::core::node::*;
+use crate::nodes::midi::{channel_index, data_byte};
+use crate::types::data_type::*;
+
+fn midi_note_in_process(
+    ctx: &mut ProcessContext,
+    inputs: Vec<DataValue>,
+    outputs: &mut [&mut DataValue],
+) {
+    let (channel, note) = match (inputs[0], inputs[1]) {
+        (DataValue::Number(c), DataValue::Number(n)) => (channel_index(c), data_byte(n)),
+        _ => panic!("Expected Number"),
+    };
+
+    let midi_in = &ctx.io.midi_in;
+    let held = midi_in.notes.get(&(channel, note)).copied();
+    // Struck this frame, even if already released, e.g. a drum pad
+    let struck = midi_in.struck.get(&(channel, note)).copied();
+    let velocity = struck.or(held);
+    let was_held = ctx.state[0].is_active();
+    ctx.state[0] = DataValue::Boolean(held.is_some());
+
+    // A tap is on for one frame
+    if let DataValue::Boolean(ref mut b) = outputs[0] {
+        *b = velocity.is_some();
+    };
+    // Velocity is held after the note is released
+    if let (DataValue::Number(ref mut n), Some(velocity)) = (&mut *outputs[1], velocity) {
+        *n = velocity as f64 / 127.0;
+    };
+    if let DataValue::Trigger(ref mut t) = outputs[2] {
+        *t = struck.is_some() || (held.is_some() && !was_held);
+    };
+}
+
+pub static MIDI_NOTE_IN_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "MIDI Note In",
+    inputs: &[
+        // 1..16
+        InputDesc {
+            id: 0,
+            name: "Channel",
+            data_type: DataType::Number,
+            default: DataValue::Number(1.0),
+        },
+        // 0..127, 60 is middle C
+        InputDesc {
+            id: 1,
+            name: "Note",
+            data_type: DataType::Number,
+            default: DataValue::Number(60.0),
+        },
+    ],
+    outputs: &[
+        OutputDesc {
+            id: 0,
+            name: "Gate",
+            data_type: DataType::Boolean,
+        },
+        OutputDesc {
+            id: 1,
+            name: "Velocity",
+            data_type: DataType::Number,
+        },
+        OutputDesc {
+            id: 2,
+            name: "On",
+            data_type: DataType::Trigger,
+        },
+    ],
+    process: midi_note_in_process,
+    // Whether the note was held last frame
+    state: &[DataValue::Boolean(false)],
+    volatile: true,
+};
@@ -1,2 +1,11 @@
 pub mod constants;
-pub mod maths;
\ No newline at end of file
+pub mod dmx;
+pub mod fixtures;
+pub mod io;
+pub mod logic;
+pub mod maths;
+pub mod midi;
+pub mod osc;
+pub mod subgraph;
+pub mod time;
+pub mod trigger;
\ No newline at end of file
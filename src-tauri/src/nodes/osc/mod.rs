@@ -0,0 +1,124 @@
+pub mod osc_in;
+pub mod osc_out;
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::core::graph::Graph;
+    use crate::core::node::{NodeKind, NodeUIState};
+    use crate::osc::codec::{OscArg, OscMessage};
+    use crate::osc::{OscInput, OscInputs};
+    use crate::types::data_type::DataValue::*;
+    use crate::types::vector::Vec3;
+
+    fn single_node_graph(kind: NodeKind, text: &str) -> Graph {
+        let mut graph = Graph::new(0);
+        graph.add_node(
+            kind,
+            NodeUIState {
+                position: (0.0, 0.0),
+            },
+        );
+        graph.set_node_text(0, text);
+        graph
+    }
+
+    fn input(address: &str, args: Vec<OscArg>, serial: u64) -> (String, OscInput) {
+        (address.to_string(), OscInput { args, serial })
+    }
+
+    #[test]
+    fn osc_in_latest_match() {
+        let mut graph = single_node_graph(NodeKind::OscIn, "/1/fader*");
+        // Received -> Counter, to see the trigger
+        graph.add_node(
+            NodeKind::Counter,
+            NodeUIState {
+                position: (0.0, 0.0),
+            },
+        );
+        graph.connect(2, 1);
+
+        graph.process();
+        assert_eq!(Number(0.0), graph.get_output_value(0));
+        assert_eq!(Number(0.0), graph.get_output_value(3));
+
+        let osc_in: OscInputs = [
+            input("/1/fader1", vec![OscArg::Float(0.5)], 2),
+            input("/1/fader2", vec![OscArg::Float(0.25), OscArg::Int(2)], 3),
+            input("/2/fader1", vec![OscArg::Float(1.0)], 4),
+        ]
+        .into_iter()
+        .collect();
+        graph.set_osc_input(Arc::new(osc_in.clone()));
+
+        graph.process();
+        assert_eq!(Number(0.25), graph.get_output_value(0));
+        assert_eq!(
+            Vector3(Vec3::new(0.25, 2.0, 0.0)),
+            graph.get_output_value(1)
+        );
+        assert_eq!(Number(1.0), graph.get_output_value(3));
+
+        // Nothing new, the value is held
+        graph.process();
+        assert_eq!(Number(0.25), graph.get_output_value(0));
+        assert_eq!(Number(1.0), graph.get_output_value(3));
+
+        // Second argument
+        let mut osc_in = osc_in;
+        osc_in.extend([input(
+            "/1/fader1",
+            vec![OscArg::Float(0.1), OscArg::Float(0.9)],
+            5,
+        )]);
+        graph.set_osc_input(Arc::new(osc_in));
+        graph.set_input_value(0, Number(1.0));
+        graph.process();
+        assert_eq!(Number(0.9f32 as f64), graph.get_output_value(0));
+
+        graph.set_node_text(0, "fader");
+        graph.process();
+        assert!(graph.get_node(0).error.is_some());
+    }
+
+    #[test]
+    fn osc_out_sends_changes() {
+        let mut graph = single_node_graph(NodeKind::OscOut, "/cue/level");
+
+        // The first frame only records the value
+        graph.process();
+        assert!(graph.take_osc_output().is_empty());
+
+        graph.set_input_value(0, Number(0.5));
+        graph.process();
+        graph.process();
+        assert_eq!(
+            vec![OscMessage::new("/cue/level", vec![OscArg::Float(0.5)])],
+            graph.take_osc_output()
+        );
+
+        graph.set_input_value(0, Boolean(true));
+        graph.process();
+        assert_eq!(
+            vec![OscMessage::new("/cue/level", vec![OscArg::Bool(true)])],
+            graph.take_osc_output()
+        );
+
+        // Triggers send without arguments every time they fire
+        graph.set_node_text(0, "/go");
+        for _ in 0..2 {
+            graph.set_input_value(0, Trigger(true));
+            graph.process();
+        }
+        graph.process();
+        assert_eq!(
+            vec![
+                OscMessage::new("/go", vec![]),
+                OscMessage::new("/go", vec![])
+            ],
+            graph.take_osc_output()
+        );
+    }
+}
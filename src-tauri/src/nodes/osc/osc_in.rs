@@ -0,0 +1,96 @@
+use crate::core::node::*;
+use crate::osc::pattern;
+use crate::types::data_type::*;
+use crate::types::vector::Vec3;
+
+// The text is the address pattern, e.g. /1/fader*
+fn osc_in_process(
+    ctx: &mut ProcessContext,
+    inputs: Vec<DataValue>,
+    outputs: &mut [&mut DataValue],
+) {
+    let argument = match inputs[0] {
+        DataValue::Number(n) => n.round().max(0.0) as usize,
+        _ => panic!("Expected Number"),
+    };
+
+    if ctx.text.is_empty() {
+        return;
+    }
+    if !ctx.text.starts_with('/') {
+        *ctx.error = Some("OSC addresses start with '/'".to_string());
+        return;
+    }
+    *ctx.error = None;
+
+    let last_serial = match ctx.state[0] {
+        DataValue::Number(n) => n as u64,
+        _ => panic!("Expected Number"),
+    };
+
+    // When a pattern matches several addresses the newest message wins
+    let latest = if pattern::is_literal(ctx.text) {
+        ctx.io.osc_in.get(ctx.text)
+    } else {
+        ctx.io
+            .osc_in
+            .iter()
+            .filter(|(address, _)| pattern::matches(ctx.text, address))
+            .map(|(_, input)| input)
+            .max_by_key(|input| input.serial)
+    };
+
+    let input = match latest {
+        Some(input) if input.serial > last_serial => input,
+        // Outputs keep the last received values
+        _ => return,
+    };
+    ctx.state[0] = DataValue::Number(input.serial as f64);
+
+    let number = |i: usize| input.args.get(i).and_then(|x| x.as_f64()).unwrap_or(0.0);
+
+    if let DataValue::Number(ref mut n) = outputs[0] {
+        *n = number(argument);
+    };
+    if let DataValue::Vector3(ref mut v) = outputs[1] {
+        *v = Vec3::new(number(argument), number(argument + 1), number(argument + 2));
+    };
+    if let DataValue::Trigger(ref mut t) = outputs[2] {
+        *t = true;
+    };
+}
+
+pub static OSC_IN_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "OSC In",
+    inputs: &[
+        // Index of the message argument read by Value, Vector reads three from it
+        InputDesc {
+            id: 0,
+            name: "Argument",
+            data_type: DataType::Number,
+            default: DataValue::Number(0.0),
+        },
+    ],
+    outputs: &[
+        OutputDesc {
+            id: 0,
+            name: "Value",
+            data_type: DataType::Number,
+        },
+        OutputDesc {
+            id: 1,
+            name: "Vector",
+            data_type: DataType::Vector3,
+        },
+        // Fires on every new message
+        OutputDesc {
+            id: 2,
+            name: "Received",
+            data_type: DataType::Trigger,
+        },
+    ],
+    process: osc_in_process,
+    // Serial of the last message read
+    state: &[DataValue::Number(0.0)],
+    volatile: true,
+};
@@ -0,0 +1,66 @@
+use crate::core::node::*;
+use crate::osc::codec::{OscArg, OscMessage};
+use crate::types::data_type::*;
+
+// The text is the address to send to, e.g. /cue/1/go
+fn osc_out_process(
+    ctx: &mut ProcessContext,
+    inputs: Vec<DataValue>,
+    _outputs: &mut [&mut DataValue],
+) {
+    let value = inputs[0];
+
+    if ctx.text.is_empty() {
+        return;
+    }
+    if !ctx.text.starts_with('/') {
+        *ctx.error = Some("OSC addresses start with '/'".to_string());
+        return;
+    }
+    *ctx.error = None;
+
+    // The first frame only records the value, so loading a graph doesn't fire anything
+    let seen_before = ctx.state[1].is_active();
+    let send = match value {
+        // Triggers send a message without arguments each time they fire
+        DataValue::Trigger(fired) => fired,
+        _ => seen_before && ctx.state[0] != value,
+    };
+    ctx.state[0] = value;
+    ctx.state[1] = DataValue::Boolean(true);
+
+    if !send {
+        return;
+    }
+
+    let args = match value {
+        DataValue::Boolean(b) => vec![OscArg::Bool(b)],
+        DataValue::Trigger(_) => Vec::new(),
+        _ => value
+            .components()
+            .unwrap_or_default()
+            .iter()
+            .map(|x| OscArg::Float(*x as f32))
+            .collect(),
+    };
+
+    ctx.io.osc_out.push(OscMessage::new(ctx.text, args));
+}
+
+pub static OSC_OUT_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "OSC Out",
+    inputs: &[
+        // Numbers, Vectors and Colors are sent as floats, Booleans as T/F
+        InputDesc {
+            id: 0,
+            name: "Value",
+            data_type: DataType::Any,
+            default: DataValue::default(DataType::Number),
+        },
+    ],
+    outputs: &[],
+    process: osc_out_process,
+    // Last value, whether a frame was processed yet
+    state: &[DataValue::Number(0.0), DataValue::Boolean(false)],
+    volatile: true,
+};
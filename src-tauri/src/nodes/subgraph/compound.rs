@@ -0,0 +1,215 @@
+use std::sync::{Arc, Mutex};
+
+use crate::core::compound::{CompoundDef, CompoundId, MAX_DEPTH};
+use crate::core::graph::Graph;
+use crate::core::input_field::InputId;
+use crate::core::node::*;
+use crate::core::output_pin::OutputId;
+use crate::types::data_type::*;
+
+// The graph of a definition, kept in the node's cache between frames
+struct Instance {
+    def: Arc<CompoundDef>,
+    graph: Graph,
+    // Value fields of the GroupInput nodes, Value pins of the GroupOutput nodes
+    inputs: Vec<InputId>,
+    outputs: Vec<OutputId>,
+}
+
+impl Instance {
+    fn new(def: Arc<CompoundDef>) -> Result<Instance, String> {
+        let mut graph = Graph::new(0);
+        let nodes = graph.insert_fragment(&def.fragment, (0.0, 0.0))?;
+
+        let mut inputs = Vec::new();
+        let mut outputs = Vec::new();
+        for node_id in nodes {
+            let node = graph.get_node(node_id);
+            match node.kind {
+                NodeKind::GroupInput => inputs.push(node.inputs[0]),
+                NodeKind::GroupOutput => outputs.push(node.outputs[0]),
+                _ => (),
+            }
+        }
+
+        Ok(Instance {
+            def,
+            graph,
+            inputs,
+            outputs,
+        })
+    }
+}
+
+// Processes the graph of a compound definition inline, with the inputs going
+// in through its GroupInput nodes and the outputs out of its GroupOutput nodes
+fn compound_process(
+    ctx: &mut ProcessContext,
+    inputs: Vec<DataValue>,
+    outputs: &mut [&mut DataValue],
+) {
+    let id = match inputs[0] {
+        DataValue::Number(id) => id.round().max(0.0) as CompoundId,
+        _ => panic!("Expected Number"),
+    };
+
+    let Some(def) = ctx.io.compounds.get(id).cloned() else {
+        *ctx.error = Some(format!("Compound {} doesn't exist", id));
+        return;
+    };
+    if ctx.io.depth >= MAX_DEPTH {
+        *ctx.error = Some("Compounds nested too deep".to_string());
+        return;
+    }
+
+    // Built again when the definition was edited, which restarts its state
+    let cached = ctx
+        .cache
+        .clone()
+        .and_then(|x| x.downcast::<Mutex<Instance>>().ok())
+        .filter(|x| x.lock().is_ok_and(|x| Arc::ptr_eq(&x.def, &def)));
+    let instance = match cached {
+        Some(instance) => instance,
+        None => match Instance::new(def) {
+            Ok(instance) => {
+                let instance = Arc::new(Mutex::new(instance));
+                *ctx.cache = Some(instance.clone());
+                instance
+            }
+            Err(error) => {
+                *ctx.error = Some(error);
+                return;
+            }
+        },
+    };
+    let Ok(mut instance) = instance.lock() else {
+        return;
+    };
+    *ctx.error = None;
+
+    let Instance {
+        graph,
+        inputs: fields,
+        outputs: pins,
+        ..
+    } = &mut *instance;
+    // Fields inside that take another type than a pin's value, e.g. the
+    // Number of a pin left unconnected, keep their own, see Graph::run
+    for (field, value) in fields.iter().zip(&inputs[1..]) {
+        graph.set_input_value(*field, *value);
+    }
+
+    ctx.io.depth += 1;
+    graph.process_nested(ctx.time, ctx.delta, ctx.io);
+    ctx.io.depth -= 1;
+
+    for (pin, output) in pins.iter().zip(outputs.iter_mut()) {
+        **output = graph.get_output_value(*pin);
+    }
+}
+
+pub static COMPOUND_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "Compound",
+    inputs: &[
+        // Compound id
+        InputDesc {
+            id: 0,
+            name: "Compound",
+            data_type: DataType::Number,
+            default: DataValue::Number(0.0),
+        },
+        InputDesc {
+            id: 1,
+            name: "In 1",
+            data_type: DataType::Any,
+            default: DataValue::default(DataType::Any),
+        },
+        InputDesc {
+            id: 2,
+            name: "In 2",
+            data_type: DataType::Any,
+            default: DataValue::default(DataType::Any),
+        },
+        InputDesc {
+            id: 3,
+            name: "In 3",
+            data_type: DataType::Any,
+            default: DataValue::default(DataType::Any),
+        },
+        InputDesc {
+            id: 4,
+            name: "In 4",
+            data_type: DataType::Any,
+            default: DataValue::default(DataType::Any),
+        },
+        InputDesc {
+            id: 5,
+            name: "In 5",
+            data_type: DataType::Any,
+            default: DataValue::default(DataType::Any),
+        },
+        InputDesc {
+            id: 6,
+            name: "In 6",
+            data_type: DataType::Any,
+            default: DataValue::default(DataType::Any),
+        },
+        InputDesc {
+            id: 7,
+            name: "In 7",
+            data_type: DataType::Any,
+            default: DataValue::default(DataType::Any),
+        },
+        InputDesc {
+            id: 8,
+            name: "In 8",
+            data_type: DataType::Any,
+            default: DataValue::default(DataType::Any),
+        },
+    ],
+    outputs: &[
+        OutputDesc {
+            id: 0,
+            name: "Out 1",
+            data_type: DataType::Any,
+        },
+        OutputDesc {
+            id: 1,
+            name: "Out 2",
+            data_type: DataType::Any,
+        },
+        OutputDesc {
+            id: 2,
+            name: "Out 3",
+            data_type: DataType::Any,
+        },
+        OutputDesc {
+            id: 3,
+            name: "Out 4",
+            data_type: DataType::Any,
+        },
+        OutputDesc {
+            id: 4,
+            name: "Out 5",
+            data_type: DataType::Any,
+        },
+        OutputDesc {
+            id: 5,
+            name: "Out 6",
+            data_type: DataType::Any,
+        },
+        OutputDesc {
+            id: 6,
+            name: "Out 7",
+            data_type: DataType::Any,
+        },
+        OutputDesc {
+            id: 7,
+            name: "Out 8",
+            data_type: DataType::Any,
+        },
+    ],
+    process: compound_process,
+    state: &[],
+    volatile: true,
+};
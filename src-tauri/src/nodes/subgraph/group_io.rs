@@ -0,0 +1,49 @@
+use crate::core::node::*;
+use crate::types::data_type::*;
+
+// Both pass the value through. Inside a compound the Compound node sets the
+// Value of its GroupInput nodes and reads the Value of its GroupOutput nodes,
+// the text of either names the pin.
+fn group_io_process(
+    _ctx: &mut ProcessContext,
+    inputs: Vec<DataValue>,
+    outputs: &mut [&mut DataValue],
+) {
+    *outputs[0] = inputs[0];
+}
+
+pub static GROUP_INPUT_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "Group Input",
+    inputs: &[InputDesc {
+        id: 0,
+        name: "Value",
+        data_type: DataType::Any,
+        default: DataValue::default(DataType::Any),
+    }],
+    outputs: &[OutputDesc {
+        id: 0,
+        name: "Value",
+        data_type: DataType::Any,
+    }],
+    process: group_io_process,
+    state: &[],
+    volatile: false,
+};
+
+pub static GROUP_OUTPUT_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "Group Output",
+    inputs: &[InputDesc {
+        id: 0,
+        name: "Value",
+        data_type: DataType::Any,
+        default: DataValue::default(DataType::Any),
+    }],
+    outputs: &[OutputDesc {
+        id: 0,
+        name: "Value",
+        data_type: DataType::Any,
+    }],
+    process: group_io_process,
+    state: &[],
+    volatile: false,
+};
@@ -0,0 +1,227 @@
+pub mod compound;
+pub mod group_io;
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Instant;
+
+    use crate::core::compound::CompoundLibrary;
+    use crate::core::fragment::{Fragment, FragmentNode};
+    use crate::core::graph::Graph;
+    use crate::core::history::GraphEdit;
+    use crate::core::node::{NodeKind, NodeUIState};
+    use crate::managers::graph_manager::GraphManager;
+    use crate::project::Project;
+    use crate::types::color;
+    use crate::types::data_type::DataValue::*;
+
+    fn add(graph: &mut Graph, kind: NodeKind, text: &str) {
+        graph.add_node(
+            kind,
+            NodeUIState {
+                position: (0.0, 0.0),
+            },
+        );
+        graph.set_node_text(graph.to_dto().nodes.len() - 1, text);
+    }
+
+    fn connections(graph: &Graph) -> Vec<(usize, usize)> {
+        let dto = graph.to_dto();
+        dto.connections.iter().map(|x| (x.from, x.to)).collect()
+    }
+
+    #[test]
+    fn compound_processes_inline() {
+        // Group Input -> "a * 2" -> Group Output
+        let mut def = Graph::new(0);
+        add(&mut def, NodeKind::GroupInput, "");
+        add(&mut def, NodeKind::Expression, "a * 2");
+        add(&mut def, NodeKind::GroupOutput, "Double");
+        def.connect(0, 1);
+        def.connect(1, 5);
+        let fragment = def.extract_subgraph(&[0, 1, 2]);
+
+        let mut library = CompoundLibrary::default();
+        let id = library.add("Double", fragment.clone()).unwrap();
+        assert_eq!(vec!["In 1"], library.get(id).unwrap().inputs());
+        assert_eq!(vec!["Double"], library.get(id).unwrap().outputs());
+
+        // Two instances of it
+        let mut graph = Graph::new(0);
+        add(&mut graph, NodeKind::ConstantNumber, "");
+        add(&mut graph, NodeKind::Compound, "");
+        add(&mut graph, NodeKind::Compound, "");
+        graph.set_input_value(0, Number(3.0));
+        graph.connect(0, 2);
+        graph.set_input_value(11, Number(5.0));
+        graph.set_compounds(Arc::new(library.clone()));
+        graph.process();
+        assert_eq!(Number(6.0), graph.get_output_value(1));
+        assert_eq!(Number(10.0), graph.get_output_value(9));
+
+        // One edit updates both
+        let mut edited = fragment.clone();
+        edited.nodes[1].text = "a * 3".to_string();
+        library.set(id, edited).unwrap();
+        graph.set_compounds(Arc::new(library.clone()));
+        graph.process();
+        assert_eq!(Number(9.0), graph.get_output_value(1));
+        assert_eq!(Number(15.0), graph.get_output_value(9));
+
+        graph.set_input_value(1, Number(4.0));
+        graph.process();
+        assert!(graph.get_node(1).error.is_some());
+
+        // A compound can't end up inside itself
+        add(&mut def, NodeKind::Compound, "");
+        def.set_input_value(7, Number(id as f64));
+        let nested = def.extract_subgraph(&[0, 1, 2, 3]);
+        assert!(library.set(id, nested.clone()).is_err());
+        let outer = library.add("Outer", nested).unwrap();
+        let mut inner = fragment;
+        inner.nodes[0].kind = NodeKind::Compound;
+        inner.nodes[0].values = vec![Number(outer as f64)];
+        assert!(library.set(id, inner).is_err());
+    }
+
+    #[test]
+    fn group_and_expand_nodes() {
+        let mut graphs = GraphManager::new();
+        graphs.add_graph();
+        let graph = graphs.get_graph_mut(0);
+        // Number -> "a + 1" -> "a * 10" -> Number
+        add(graph, NodeKind::ConstantNumber, "");
+        add(graph, NodeKind::Expression, "a + 1");
+        add(graph, NodeKind::Expression, "a * 10");
+        add(graph, NodeKind::ConstantNumber, "");
+        graph.connect(0, 1);
+        graph.connect(1, 5);
+        graph.connect(2, 9);
+        graph.set_input_value(0, Number(2.0));
+
+        let id = graphs.group_nodes(0, &[1, 2], "Scale").unwrap();
+        let graph = graphs.get_graph_mut(0);
+        assert_eq!(3, graph.to_dto().nodes.len());
+        assert_eq!(NodeKind::Compound, graph.get_node(2).kind);
+        assert_eq!(Number(id as f64), graph.get_input_value(2));
+        assert_eq!(vec![(2, 1), (0, 3)], connections(graph));
+        graph.process();
+        assert_eq!(Number(30.0), graph.get_output_value(1));
+
+        assert!(graph.undo());
+        assert_eq!(vec![(0, 1), (1, 5), (2, 9)], connections(graph));
+        assert!(graph.redo());
+
+        assert_eq!(
+            vec![2, 3],
+            graph.expand_compound(2, Instant::now()).unwrap()
+        );
+        assert_eq!(vec![(3, 1), (0, 2), (2, 6)], connections(graph));
+        assert_eq!("a * 10", graph.get_node(3).text);
+        graph.process();
+        assert_eq!(Number(30.0), graph.get_output_value(1));
+        assert!(graph.expand_compound(2, Instant::now()).is_err());
+
+        // Saved with the project
+        let project = Project {
+            compounds: graphs.compounds().config(),
+            ..Project::default()
+        };
+        let project = Project::from_json(&project.to_json().unwrap()).unwrap();
+        let mut loaded = GraphManager::new();
+        loaded.set_compounds(project.compounds);
+        assert_eq!(graphs.compounds().config(), loaded.compounds().config());
+    }
+
+    #[test]
+    fn compound_pin_of_another_type() {
+        let mut graphs = GraphManager::new();
+        graphs.add_graph();
+        let graph = graphs.get_graph_mut(0);
+        let (red, blue) = (
+            Color(color::Color::new(color::ColorValue::RGB(1.0, 0.0, 0.0))),
+            Color(color::Color::new(color::ColorValue::RGB(0.0, 0.0, 1.0))),
+        );
+        // Color -> Color -> Color, the middle one grouped
+        add(graph, NodeKind::ConstantColor, "");
+        add(graph, NodeKind::ConstantColor, "");
+        add(graph, NodeKind::ConstantColor, "");
+        graph.connect(0, 1);
+        graph.connect(1, 2);
+        graph.set_input_value(0, blue);
+        graph.set_input_value(1, red);
+
+        graphs.group_nodes(0, &[1], "Pass").unwrap();
+        let graph = graphs.get_graph_mut(0);
+        graph.process();
+        assert_eq!(blue, graph.get_output_value(2));
+
+        // "In 1" falls back to a Number, the field inside keeps its own value
+        graph
+            .edit(GraphEdit::Disconnect { input: 3 }, Instant::now())
+            .unwrap();
+        graph.process();
+        graph.process();
+        assert_eq!(red, graph.get_output_value(2));
+        assert_eq!(None, graph.get_node(2).error);
+    }
+
+    #[test]
+    fn group_rejected_by_the_library() {
+        let mut graphs = GraphManager::new();
+        graphs.add_graph();
+        // Uses compound 1, which doesn't exist yet
+        let fragment = Fragment {
+            nodes: vec![FragmentNode {
+                kind: NodeKind::Compound,
+                position: (0.0, 0.0),
+                text: String::new(),
+                values: vec![Number(1.0)],
+            }],
+            connections: Vec::new(),
+        };
+        let id = graphs.add_compound("Outer", fragment).unwrap();
+
+        let graph = graphs.get_graph_mut(0);
+        add(graph, NodeKind::ConstantNumber, "");
+        add(graph, NodeKind::Compound, "");
+        graph.set_input_value(1, Number(id as f64));
+        graph.connect(0, 2);
+
+        // Compound 1 would be inside itself, the graph stays as it was
+        assert!(graphs.group_nodes(0, &[1], "Inner").is_err());
+        let graph = graphs.get_graph(0);
+        assert_eq!(2, graph.to_dto().nodes.len());
+        assert_eq!(vec![(0, 2)], connections(graph));
+        assert!(!graph.history().can_undo());
+        assert_eq!(1, graphs.compounds().defs().count());
+    }
+
+    #[test]
+    fn compound_pins_are_limited() {
+        let boundary = |kind| FragmentNode {
+            kind,
+            position: (0.0, 0.0),
+            text: String::new(),
+            values: Vec::new(),
+        };
+        let fragment = |inputs, outputs| Fragment {
+            nodes: [
+                (NodeKind::GroupInput, inputs),
+                (NodeKind::GroupOutput, outputs),
+            ]
+            .into_iter()
+            .flat_map(|(kind, n)| (0..n).map(move |_| boundary(kind)))
+            .collect(),
+            connections: Vec::new(),
+        };
+
+        let mut library = CompoundLibrary::default();
+        let id = library.add("Wide", fragment(8, 8)).unwrap();
+        assert!(library.add("Wider", fragment(9, 1)).is_err());
+        assert!(library.set(id, fragment(1, 9)).is_err());
+        assert_eq!(8, library.get(id).unwrap().inputs().len());
+    }
+}
+
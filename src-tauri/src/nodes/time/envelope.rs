@@ -0,0 +1,154 @@
+use crate::core::node::*;
+use crate::types::data_type::*;
+
+const IDLE: f64 = 0.0;
+const ATTACK: f64 = 1.0;
+const DECAY: f64 = 2.0;
+const SUSTAIN: f64 = 3.0;
+const RELEASE: f64 = 4.0;
+
+fn number(value: DataValue) -> f64 {
+    match value {
+        DataValue::Number(n) => n,
+        _ => panic!("Expected Number"),
+    }
+}
+
+// Linear ADSR. The stage reached at the previous frame is advanced by delta first, then the gate is applied,
+// so a gate that opens on a frame starts the attack from that frame's time.
+fn envelope_process(
+    ctx: &mut ProcessContext,
+    inputs: Vec<DataValue>,
+    outputs: &mut [&mut DataValue],
+) {
+    let gate = inputs[0].is_active();
+    let attack = number(inputs[1]).max(0.0);
+    let decay = number(inputs[2]).max(0.0);
+    let sustain = number(inputs[3]).clamp(0.0, 1.0);
+    let release = number(inputs[4]).max(0.0);
+
+    let mut stage = number(ctx.state[0]);
+    let mut level = number(ctx.state[1]);
+    let mut release_from = number(ctx.state[2]);
+
+    let mut remaining = ctx.delta;
+    loop {
+        if stage == ATTACK {
+            let needed = (1.0 - level) * attack;
+            if remaining >= needed {
+                remaining -= needed;
+                level = 1.0;
+                stage = DECAY;
+            } else {
+                level += remaining / attack;
+                break;
+            }
+        } else if stage == DECAY {
+            let needed = if level <= sustain {
+                0.0
+            } else {
+                (level - sustain) / (1.0 - sustain) * decay
+            };
+            if remaining >= needed {
+                remaining -= needed;
+                level = sustain;
+                stage = SUSTAIN;
+            } else {
+                level -= remaining / decay * (1.0 - sustain);
+                break;
+            }
+        } else if stage == SUSTAIN {
+            level = sustain;
+            break;
+        } else if stage == RELEASE {
+            let needed = if release_from <= 0.0 {
+                0.0
+            } else {
+                level / release_from * release
+            };
+            if remaining >= needed {
+                level = 0.0;
+                stage = IDLE;
+            } else {
+                level -= remaining / release * release_from;
+            }
+            break;
+        } else {
+            level = 0.0;
+            break;
+        }
+    }
+
+    let open = stage == ATTACK || stage == DECAY || stage == SUSTAIN;
+    if gate && !open {
+        stage = ATTACK;
+        if attack == 0.0 {
+            level = 1.0;
+            stage = DECAY;
+        }
+    } else if !gate && open {
+        stage = RELEASE;
+        release_from = level;
+        if release == 0.0 {
+            level = 0.0;
+            stage = IDLE;
+        }
+    }
+
+    ctx.state[0] = DataValue::Number(stage);
+    ctx.state[1] = DataValue::Number(level);
+    ctx.state[2] = DataValue::Number(release_from);
+
+    if let DataValue::Number(ref mut n) = outputs[0] {
+        *n = level;
+    };
+}
+
+pub static ENVELOPE_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "Envelope",
+    inputs: &[
+        InputDesc {
+            id: 0,
+            name: "Gate",
+            data_type: DataType::Boolean,
+            default: DataValue::default(DataType::Boolean),
+        },
+        InputDesc {
+            id: 1,
+            name: "Attack",
+            data_type: DataType::Number,
+            default: DataValue::Number(0.1),
+        },
+        InputDesc {
+            id: 2,
+            name: "Decay",
+            data_type: DataType::Number,
+            default: DataValue::Number(0.2),
+        },
+        InputDesc {
+            id: 3,
+            name: "Sustain",
+            data_type: DataType::Number,
+            default: DataValue::Number(0.8),
+        },
+        InputDesc {
+            id: 4,
+            name: "Release",
+            data_type: DataType::Number,
+            default: DataValue::Number(0.5),
+        },
+    ],
+    outputs: &[OutputDesc {
+        id: 0,
+        name: "Level",
+        data_type: DataType::Number,
+    }],
+    process: envelope_process,
+    // Stage, level, level when the release started
+    state: &[
+        DataValue::Number(IDLE),
+        DataValue::Number(0.0),
+        DataValue::Number(0.0),
+    ],
+    volatile: true,
+};
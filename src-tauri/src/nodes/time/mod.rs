@@ -0,0 +1,120 @@
+pub mod envelope;
+pub mod slew;
+
+#[cfg(test)]
+mod tests {
+    use crate::core::graph::Graph;
+    use crate::core::node::{NodeKind, NodeUIState};
+    use crate::types::data_type::DataValue::{self, *};
+    use crate::types::vector::Vec3;
+
+    fn single_node_graph(kind: NodeKind) -> Graph {
+        let mut graph = Graph::new(0);
+        graph.add_node(
+            kind,
+            NodeUIState {
+                position: (0.0, 0.0),
+            },
+        );
+        graph
+    }
+
+    fn step(graph: &mut Graph, delta: f64) -> DataValue {
+        graph.tick(delta);
+        graph.get_output_value(0)
+    }
+
+    #[test]
+    fn envelope_adsr() {
+        let mut graph = single_node_graph(NodeKind::Envelope);
+        // Attack 0.5s, decay 0.5s to 0.5, release 1s
+        graph.set_input_value(1, Number(0.5));
+        graph.set_input_value(2, Number(0.5));
+        graph.set_input_value(3, Number(0.5));
+        graph.set_input_value(4, Number(1.0));
+
+        assert_eq!(Number(0.0), step(&mut graph, 0.25));
+
+        graph.set_input_value(0, Boolean(true));
+        assert_eq!(Number(0.0), step(&mut graph, 0.25));
+        assert_eq!(Number(0.5), step(&mut graph, 0.25));
+        assert_eq!(Number(1.0), step(&mut graph, 0.25));
+        assert_eq!(Number(0.75), step(&mut graph, 0.25));
+        assert_eq!(Number(0.5), step(&mut graph, 0.25));
+        assert_eq!(Number(0.5), step(&mut graph, 0.5));
+        assert_eq!(2.0, graph.clock().time);
+
+        graph.set_input_value(0, Boolean(false));
+        assert_eq!(Number(0.5), step(&mut graph, 0.25));
+        assert_eq!(Number(0.375), step(&mut graph, 0.25));
+        assert_eq!(Number(0.0), step(&mut graph, 0.75));
+        assert_eq!(Number(0.0), step(&mut graph, 0.25));
+    }
+
+    #[test]
+    fn envelope_crosses_stages_within_a_frame() {
+        let mut graph = single_node_graph(NodeKind::Envelope);
+        graph.set_input_value(1, Number(0.5));
+        graph.set_input_value(2, Number(0.5));
+        graph.set_input_value(3, Number(0.5));
+        graph.set_input_value(4, Number(1.0));
+
+        graph.set_input_value(0, Boolean(true));
+        step(&mut graph, 0.0);
+        // 0.5s of attack then 0.25s of decay
+        assert_eq!(Number(0.75), step(&mut graph, 0.75));
+
+        // Released mid decay, then retriggered part way through the release
+        graph.set_input_value(0, Boolean(false));
+        assert_eq!(Number(0.75), step(&mut graph, 0.0));
+        assert_eq!(Number(0.5625), step(&mut graph, 0.25));
+        graph.set_input_value(0, Boolean(true));
+        assert_eq!(Number(0.375), step(&mut graph, 0.25));
+        assert_eq!(Number(0.625), step(&mut graph, 0.125));
+    }
+
+    #[test]
+    fn slew_limits_rate() {
+        let mut graph = single_node_graph(NodeKind::Slew);
+        // Rise 2/s, fall 1/s
+        graph.set_input_value(1, Number(2.0));
+        graph.set_input_value(2, Number(1.0));
+
+        assert_eq!(Number(0.0), step(&mut graph, 0.25));
+        graph.set_input_value(0, Number(1.0));
+        assert_eq!(Number(0.5), step(&mut graph, 0.25));
+        assert_eq!(Number(1.0), step(&mut graph, 0.25));
+        assert_eq!(Number(1.0), step(&mut graph, 0.25));
+        graph.set_input_value(0, Number(0.0));
+        assert_eq!(Number(0.75), step(&mut graph, 0.25));
+        assert_eq!(Number(0.25), step(&mut graph, 0.5));
+        assert_eq!(Number(0.0), step(&mut graph, 0.5));
+    }
+
+    #[test]
+    fn slew_component_wise() {
+        let mut graph = single_node_graph(NodeKind::Slew);
+        graph.set_input_value(0, Vector3(Vec3::new(0.0, 0.0, 0.0)));
+        step(&mut graph, 0.25);
+
+        graph.set_input_value(0, Vector3(Vec3::new(1.0, -1.0, 0.1)));
+        assert_eq!(Vector3(Vec3::new(0.25, -0.25, 0.1)), step(&mut graph, 0.25));
+    }
+
+    #[test]
+    fn smooth_one_pole() {
+        let mut graph = single_node_graph(NodeKind::Smooth);
+        graph.set_input_value(1, Number(0.5));
+        step(&mut graph, 0.1);
+
+        graph.set_input_value(0, Number(1.0));
+        let expected = 1.0 - (-1.0f64).exp();
+        assert_eq!(Number(expected), step(&mut graph, 0.5));
+        let expected = expected + (1.0 - expected) * (1.0 - (-1.0f64).exp());
+        assert_eq!(Number(expected), step(&mut graph, 0.5));
+
+        // Zero time constant follows immediately
+        graph.set_input_value(1, Number(0.0));
+        assert_eq!(Number(1.0), step(&mut graph, 0.1));
+    }
+}
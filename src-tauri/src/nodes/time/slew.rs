@@ -0,0 +1,115 @@
+use crate::core::node::*;
+use crate::types::data_type::*;
+
+fn number(value: DataValue) -> f64 {
+    match value {
+        DataValue::Number(n) => n,
+        _ => panic!("Expected Number"),
+    }
+}
+
+// Moves the previous output towards the input using step(current, target) per component.
+// The first value, or one of a different type, is passed straight through.
+fn follow(
+    ctx: &mut ProcessContext,
+    target: DataValue,
+    outputs: &mut [&mut DataValue],
+    step: impl Fn(f64, f64) -> f64,
+) {
+    let value = match ctx.state[0] {
+        DataValue::Boolean(false) => Ok(target),
+        current => DataValue::map_components("follow", &[current, target], |x| step(x[0], x[1])),
+    }
+    .unwrap_or(target);
+
+    ctx.state[0] = value;
+    *outputs[0] = value;
+}
+
+// Linear rate limit, rates are in units per second
+fn slew_process(ctx: &mut ProcessContext, inputs: Vec<DataValue>, outputs: &mut [&mut DataValue]) {
+    let max_rise = number(inputs[1]).max(0.0) * ctx.delta;
+    let max_fall = number(inputs[2]).max(0.0) * ctx.delta;
+
+    follow(ctx, inputs[0], outputs, |current, target| {
+        current + (target - current).clamp(-max_fall, max_rise)
+    });
+}
+
+// Exponential one pole filter, "Time" is the time constant in seconds
+fn smooth_process(
+    ctx: &mut ProcessContext,
+    inputs: Vec<DataValue>,
+    outputs: &mut [&mut DataValue],
+) {
+    let time = number(inputs[1]);
+    let k = if time <= 0.0 {
+        1.0
+    } else {
+        1.0 - (-ctx.delta / time).exp()
+    };
+
+    follow(ctx, inputs[0], outputs, |current, target| {
+        current + (target - current) * k
+    });
+}
+
+pub static SLEW_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "Slew",
+    inputs: &[
+        InputDesc {
+            id: 0,
+            name: "Value",
+            data_type: DataType::Any,
+            default: DataValue::default(DataType::Number),
+        },
+        InputDesc {
+            id: 1,
+            name: "Rise",
+            data_type: DataType::Number,
+            default: DataValue::Number(1.0),
+        },
+        InputDesc {
+            id: 2,
+            name: "Fall",
+            data_type: DataType::Number,
+            default: DataValue::Number(1.0),
+        },
+    ],
+    outputs: &[OutputDesc {
+        id: 0,
+        name: "Value",
+        data_type: DataType::Any,
+    }],
+    process: slew_process,
+    // Previous output, Boolean(false) until the first frame
+    state: &[DataValue::Boolean(false)],
+    volatile: true,
+};
+
+pub static SMOOTH_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "Smooth",
+    inputs: &[
+        InputDesc {
+            id: 0,
+            name: "Value",
+            data_type: DataType::Any,
+            default: DataValue::default(DataType::Number),
+        },
+        InputDesc {
+            id: 1,
+            name: "Time",
+            data_type: DataType::Number,
+            default: DataValue::Number(0.1),
+        },
+    ],
+    outputs: &[OutputDesc {
+        id: 0,
+        name: "Value",
+        data_type: DataType::Any,
+    }],
+    process: smooth_process,
+    // Previous output, Boolean(false) until the first frame
+    state: &[DataValue::Boolean(false)],
+    volatile: true,
+};
@@ -0,0 +1,71 @@
+use crate::core::node::*;
+use crate::types::data_type::*;
+
+fn counter_process(
+    ctx: &mut ProcessContext,
+    inputs: Vec<DataValue>,
+    outputs: &mut [&mut DataValue],
+) {
+    let (step, limit) = match (inputs[2], inputs[3]) {
+        (DataValue::Number(s), DataValue::Number(l)) => (s, l),
+        _ => panic!("Expected Number"),
+    };
+    let mut count = match ctx.state[0] {
+        DataValue::Number(n) => n,
+        _ => 0.0,
+    };
+
+    if inputs[1].is_active() {
+        count = 0.0;
+    } else if inputs[0].is_active() {
+        count += step;
+        // A limit of zero or less counts forever
+        if limit > 0.0 && count >= limit {
+            count = 0.0;
+        }
+    }
+    ctx.state[0] = DataValue::Number(count);
+
+    if let DataValue::Number(ref mut n) = outputs[0] {
+        *n = count;
+    };
+}
+
+pub static COUNTER_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "Counter",
+    inputs: &[
+        InputDesc {
+            id: 0,
+            name: "Trigger",
+            data_type: DataType::Trigger,
+            default: DataValue::default(DataType::Trigger),
+        },
+        InputDesc {
+            id: 1,
+            name: "Reset",
+            data_type: DataType::Trigger,
+            default: DataValue::default(DataType::Trigger),
+        },
+        InputDesc {
+            id: 2,
+            name: "Step",
+            data_type: DataType::Number,
+            default: DataValue::Number(1.0),
+        },
+        InputDesc {
+            id: 3,
+            name: "Wrap At",
+            data_type: DataType::Number,
+            default: DataValue::Number(0.0),
+        },
+    ],
+    outputs: &[OutputDesc {
+        id: 0,
+        name: "Count",
+        data_type: DataType::Number,
+    }],
+    process: counter_process,
+    // Current count
+    state: &[DataValue::Number(0.0)],
+    volatile: false,
+};
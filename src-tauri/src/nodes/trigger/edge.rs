@@ -0,0 +1,68 @@
+use crate::core::node::*;
+use crate::types::data_type::*;
+
+// Returns (previous, current) and remembers current for the next frame
+fn edge(ctx: &mut ProcessContext, input: DataValue) -> (bool, bool) {
+    let current = input.is_active();
+    let previous = ctx.state[0].is_active();
+    ctx.state[0] = DataValue::Boolean(current);
+
+    (previous, current)
+}
+
+fn rising_edge_process(
+    ctx: &mut ProcessContext,
+    inputs: Vec<DataValue>,
+    outputs: &mut [&mut DataValue],
+) {
+    let (previous, current) = edge(ctx, inputs[0]);
+
+    if let DataValue::Trigger(ref mut t) = outputs[0] {
+        *t = !previous && current;
+    };
+}
+
+fn falling_edge_process(
+    ctx: &mut ProcessContext,
+    inputs: Vec<DataValue>,
+    outputs: &mut [&mut DataValue],
+) {
+    let (previous, current) = edge(ctx, inputs[0]);
+
+    if let DataValue::Trigger(ref mut t) = outputs[0] {
+        *t = previous && !current;
+    };
+}
+
+const EDGE_INPUTS: &[InputDesc] = &[InputDesc {
+    id: 0,
+    name: "Value",
+    data_type: DataType::Boolean,
+    default: DataValue::default(DataType::Boolean),
+}];
+
+const EDGE_OUTPUTS: &[OutputDesc] = &[OutputDesc {
+    id: 0,
+    name: "Trigger",
+    data_type: DataType::Trigger,
+}];
+
+pub static RISING_EDGE_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "Rising Edge",
+    inputs: EDGE_INPUTS,
+    outputs: EDGE_OUTPUTS,
+    process: rising_edge_process,
+    // Previous input
+    state: &[DataValue::Boolean(false)],
+    volatile: false,
+};
+
+pub static FALLING_EDGE_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "Falling Edge",
+    inputs: EDGE_INPUTS,
+    outputs: EDGE_OUTPUTS,
+    process: falling_edge_process,
+    // Previous input
+    state: &[DataValue::Boolean(false)],
+    volatile: false,
+};
@@ -0,0 +1,161 @@
+pub mod counter;
+pub mod edge;
+pub mod timer;
+pub mod toggle;
+
+#[cfg(test)]
+mod tests {
+    use crate::core::frame_io::FrameIo;
+    use crate::core::node::{NodeDescriptor, ProcessContext};
+    use crate::types::data_type::*;
+
+    // Steps a single node through frames, keeping its state between them
+    struct Runner {
+        desc: &'static NodeDescriptor,
+        state: Vec<DataValue>,
+        time: f64,
+    }
+
+    impl Runner {
+        fn new(desc: &'static NodeDescriptor) -> Runner {
+            Runner {
+                desc,
+                state: desc.state.to_vec(),
+                time: 0.0,
+            }
+        }
+
+        fn step(&mut self, delta: f64, inputs: Vec<DataValue>) -> DataValue {
+            self.time += delta;
+            let mut output = DataValue::default(self.desc.outputs[0].data_type);
+            let mut context = ProcessContext {
+                time: self.time,
+                delta,
+                state: &mut self.state,
+                text: "",
+                cache: &mut None,
+                error: &mut None,
+                io: &mut FrameIo::default(),
+            };
+            (self.desc.process)(&mut context, inputs, &mut [&mut output]);
+            output
+        }
+    }
+
+    #[test]
+    fn edges() {
+        use super::edge::*;
+        use DataValue::*;
+
+        let mut rising = Runner::new(&RISING_EDGE_DESCRIPTOR);
+        let mut falling = Runner::new(&FALLING_EDGE_DESCRIPTOR);
+        let sequence = [false, true, true, false, false, true];
+        let expected_rising = [false, true, false, false, false, true];
+        let expected_falling = [false, false, false, true, false, false];
+
+        for i in 0..sequence.len() {
+            let input = vec![Boolean(sequence[i])];
+            assert_eq!(Trigger(expected_rising[i]), rising.step(0.1, input.clone()));
+            assert_eq!(Trigger(expected_falling[i]), falling.step(0.1, input));
+        }
+    }
+
+    #[test]
+    fn toggle() {
+        use super::toggle::*;
+        use DataValue::*;
+
+        let mut toggle = Runner::new(&TOGGLE_DESCRIPTOR);
+        assert_eq!(
+            Boolean(true),
+            toggle.step(0.1, vec![Trigger(true), Trigger(false)])
+        );
+        assert_eq!(
+            Boolean(true),
+            toggle.step(0.1, vec![Trigger(false), Trigger(false)])
+        );
+        assert_eq!(
+            Boolean(false),
+            toggle.step(0.1, vec![Trigger(true), Trigger(false)])
+        );
+        assert_eq!(
+            Boolean(true),
+            toggle.step(0.1, vec![Trigger(true), Trigger(false)])
+        );
+        assert_eq!(
+            Boolean(false),
+            toggle.step(0.1, vec![Trigger(false), Trigger(true)])
+        );
+    }
+
+    #[test]
+    fn counter() {
+        use super::counter::*;
+        use DataValue::*;
+
+        let mut counter = Runner::new(&COUNTER_DESCRIPTOR);
+        let fire = || vec![Trigger(true), Trigger(false), Number(1.0), Number(3.0)];
+        let idle = || vec![Trigger(false), Trigger(false), Number(1.0), Number(3.0)];
+
+        assert_eq!(Number(1.0), counter.step(0.1, fire()));
+        assert_eq!(Number(1.0), counter.step(0.1, idle()));
+        assert_eq!(Number(2.0), counter.step(0.1, fire()));
+        assert_eq!(Number(0.0), counter.step(0.1, fire()));
+        assert_eq!(Number(1.0), counter.step(0.1, fire()));
+        assert_eq!(
+            Number(0.0),
+            counter.step(
+                0.1,
+                vec![Trigger(true), Trigger(true), Number(1.0), Number(3.0)]
+            )
+        );
+    }
+
+    #[test]
+    fn one_shot() {
+        use super::timer::*;
+        use DataValue::*;
+
+        let mut one_shot = Runner::new(&ONE_SHOT_DESCRIPTOR);
+        assert_eq!(
+            Boolean(false),
+            one_shot.step(0.25, vec![Trigger(false), Number(1.0)])
+        );
+        // Fired at t = 0.5, off from t = 1.5
+        assert_eq!(
+            Boolean(true),
+            one_shot.step(0.25, vec![Trigger(true), Number(1.0)])
+        );
+        assert_eq!(
+            Boolean(true),
+            one_shot.step(0.5, vec![Trigger(false), Number(1.0)])
+        );
+        assert_eq!(
+            Boolean(true),
+            one_shot.step(0.25, vec![Trigger(false), Number(1.0)])
+        );
+        assert_eq!(
+            Boolean(false),
+            one_shot.step(0.25, vec![Trigger(false), Number(1.0)])
+        );
+    }
+
+    #[test]
+    fn debounce() {
+        use super::timer::*;
+        use DataValue::*;
+
+        let mut debounce = Runner::new(&DEBOUNCE_DESCRIPTOR);
+        let step = |d: &mut Runner, v: bool| d.step(0.25, vec![Boolean(v), Number(0.5)]);
+
+        // Bounces shorter than the hold time are ignored
+        assert_eq!(Boolean(false), step(&mut debounce, true));
+        assert_eq!(Boolean(false), step(&mut debounce, false));
+        assert_eq!(Boolean(false), step(&mut debounce, true));
+        assert_eq!(Boolean(false), step(&mut debounce, true));
+        assert_eq!(Boolean(true), step(&mut debounce, true));
+        assert_eq!(Boolean(true), step(&mut debounce, false));
+        assert_eq!(Boolean(true), step(&mut debounce, false));
+        assert_eq!(Boolean(false), step(&mut debounce, false));
+    }
+}
@@ -0,0 +1,107 @@
+use crate::core::node::*;
+use crate::types::data_type::*;
+
+fn number(value: DataValue) -> f64 {
+    match value {
+        DataValue::Number(n) => n,
+        _ => panic!("Expected Number"),
+    }
+}
+
+// Output stays on for "Duration" seconds after each trigger, retriggering restarts it
+fn one_shot_process(
+    ctx: &mut ProcessContext,
+    inputs: Vec<DataValue>,
+    outputs: &mut [&mut DataValue],
+) {
+    let duration = number(inputs[1]);
+
+    if inputs[0].is_active() {
+        ctx.state[0] = DataValue::Number(ctx.time + duration);
+    }
+    let end = number(ctx.state[0]);
+
+    if let DataValue::Boolean(ref mut b) = outputs[0] {
+        *b = ctx.time < end;
+    };
+}
+
+// Output follows the input once it has held the same value for "Time" seconds
+fn debounce_process(
+    ctx: &mut ProcessContext,
+    inputs: Vec<DataValue>,
+    outputs: &mut [&mut DataValue],
+) {
+    let input = inputs[0].is_active();
+    let hold = number(inputs[1]);
+
+    if input != ctx.state[1].is_active() {
+        ctx.state[1] = DataValue::Boolean(input);
+        ctx.state[2] = DataValue::Number(ctx.time);
+    }
+    if ctx.time - number(ctx.state[2]) >= hold {
+        ctx.state[0] = DataValue::Boolean(input);
+    }
+
+    if let DataValue::Boolean(ref mut b) = outputs[0] {
+        *b = ctx.state[0].is_active();
+    };
+}
+
+pub static ONE_SHOT_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "One Shot",
+    inputs: &[
+        InputDesc {
+            id: 0,
+            name: "Trigger",
+            data_type: DataType::Trigger,
+            default: DataValue::default(DataType::Trigger),
+        },
+        InputDesc {
+            id: 1,
+            name: "Duration",
+            data_type: DataType::Number,
+            default: DataValue::Number(1.0),
+        },
+    ],
+    outputs: &[OutputDesc {
+        id: 0,
+        name: "Active",
+        data_type: DataType::Boolean,
+    }],
+    process: one_shot_process,
+    // Time the output turns off
+    state: &[DataValue::Number(f64::NEG_INFINITY)],
+    volatile: true,
+};
+
+pub static DEBOUNCE_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "Debounce",
+    inputs: &[
+        InputDesc {
+            id: 0,
+            name: "Value",
+            data_type: DataType::Boolean,
+            default: DataValue::default(DataType::Boolean),
+        },
+        InputDesc {
+            id: 1,
+            name: "Time",
+            data_type: DataType::Number,
+            default: DataValue::Number(0.05),
+        },
+    ],
+    outputs: &[OutputDesc {
+        id: 0,
+        name: "Value",
+        data_type: DataType::Boolean,
+    }],
+    process: debounce_process,
+    // Output, last input, time the input last changed
+    state: &[
+        DataValue::Boolean(false),
+        DataValue::Boolean(false),
+        DataValue::Number(0.0),
+    ],
+    volatile: true,
+};
@@ -0,0 +1,48 @@
+use crate::core::node::*;
+use crate::types::data_type::*;
+
+fn toggle_process(
+    ctx: &mut ProcessContext,
+    inputs: Vec<DataValue>,
+    outputs: &mut [&mut DataValue],
+) {
+    let mut on = ctx.state[0].is_active();
+
+    if inputs[1].is_active() {
+        on = false;
+    } else if inputs[0].is_active() {
+        on = !on;
+    }
+    ctx.state[0] = DataValue::Boolean(on);
+
+    if let DataValue::Boolean(ref mut b) = outputs[0] {
+        *b = on;
+    };
+}
+
+pub static TOGGLE_DESCRIPTOR: NodeDescriptor = NodeDescriptor {
+    name: "Toggle",
+    inputs: &[
+        InputDesc {
+            id: 0,
+            name: "Trigger",
+            data_type: DataType::Trigger,
+            default: DataValue::default(DataType::Trigger),
+        },
+        InputDesc {
+            id: 1,
+            name: "Reset",
+            data_type: DataType::Trigger,
+            default: DataValue::default(DataType::Trigger),
+        },
+    ],
+    outputs: &[OutputDesc {
+        id: 0,
+        name: "Value",
+        data_type: DataType::Boolean,
+    }],
+    process: toggle_process,
+    // Current value
+    state: &[DataValue::Boolean(false)],
+    volatile: false,
+};
@@ -0,0 +1,266 @@
+use std::fmt::{Display, Formatter};
+
+const BUNDLE_ID: &[u8; 8] = b"#bundle\0";
+
+// Timetag meaning "now"
+pub const IMMEDIATELY: u64 = 1;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum OscPacket {
+    Message(OscMessage),
+    Bundle(OscBundle),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct OscMessage {
+    pub address: String,
+    pub args: Vec<OscArg>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct OscBundle {
+    // NTP format, IMMEDIATELY for bundles that aren't scheduled
+    pub timetag: u64,
+    pub content: Vec<OscPacket>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum OscArg {
+    Int(i32),
+    Float(f32),
+    String(String),
+    Blob(Vec<u8>),
+    Bool(bool),
+}
+
+impl OscArg {
+    // Numbers and Bools, None for Strings and Blobs
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            OscArg::Int(i) => Some(*i as f64),
+            OscArg::Float(f) => Some(*f as f64),
+            OscArg::Bool(b) => Some(if *b { 1.0 } else { 0.0 }),
+            _ => None,
+        }
+    }
+
+    fn type_tag(&self) -> u8 {
+        match self {
+            OscArg::Int(_) => b'i',
+            OscArg::Float(_) => b'f',
+            OscArg::String(_) => b's',
+            OscArg::Blob(_) => b'b',
+            OscArg::Bool(true) => b'T',
+            OscArg::Bool(false) => b'F',
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum OscError {
+    Truncated,
+    InvalidAddress,
+    InvalidString,
+    MissingTypeTags,
+    UnsupportedType(char),
+    InvalidBundle,
+}
+
+impl Display for OscError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            OscError::Truncated => write!(f, "Truncated OSC packet"),
+            OscError::InvalidAddress => write!(f, "OSC addresses start with '/'"),
+            OscError::InvalidString => write!(f, "Invalid OSC string"),
+            OscError::MissingTypeTags => write!(f, "OSC message without type tags"),
+            OscError::UnsupportedType(tag) => write!(f, "Unsupported OSC type '{}'", tag),
+            OscError::InvalidBundle => write!(f, "Invalid OSC bundle"),
+        }
+    }
+}
+
+impl OscPacket {
+    pub fn decode(bytes: &[u8]) -> Result<OscPacket, OscError> {
+        if bytes.starts_with(BUNDLE_ID) {
+            OscBundle::decode(bytes).map(OscPacket::Bundle)
+        } else {
+            OscMessage::decode(bytes).map(OscPacket::Message)
+        }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.write(&mut bytes);
+        bytes
+    }
+
+    fn write(&self, bytes: &mut Vec<u8>) {
+        match self {
+            OscPacket::Message(message) => message.write(bytes),
+            OscPacket::Bundle(bundle) => bundle.write(bytes),
+        }
+    }
+
+    // Every message, with the contents of nested bundles in order
+    pub fn into_messages(self) -> Vec<OscMessage> {
+        match self {
+            OscPacket::Message(message) => vec![message],
+            OscPacket::Bundle(bundle) => bundle
+                .content
+                .into_iter()
+                .flat_map(|x| x.into_messages())
+                .collect(),
+        }
+    }
+}
+
+// Everything in OSC is padded to 4 bytes
+fn padded(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], OscError> {
+        let end = self.position + len;
+        if end > self.bytes.len() {
+            return Err(OscError::Truncated);
+        }
+        let slice = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(slice)
+    }
+
+    fn int(&mut self) -> Result<i32, OscError> {
+        let b = self.take(4)?;
+        Ok(i32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn string(&mut self) -> Result<String, OscError> {
+        let rest = &self.bytes[self.position.min(self.bytes.len())..];
+        let len = rest
+            .iter()
+            .position(|x| *x == 0)
+            .ok_or(OscError::Truncated)?;
+        let value = std::str::from_utf8(&rest[..len])
+            .map_err(|_| OscError::InvalidString)?
+            .to_string();
+        // Include the terminator in the padding
+        self.take(padded(len + 1))?;
+        Ok(value)
+    }
+
+    fn blob(&mut self) -> Result<Vec<u8>, OscError> {
+        let len = self.int()?;
+        if len < 0 {
+            return Err(OscError::Truncated);
+        }
+        let len = len as usize;
+        let value = self.take(len)?.to_vec();
+        self.take(padded(len) - len)?;
+        Ok(value)
+    }
+}
+
+fn write_string(bytes: &mut Vec<u8>, value: &str) {
+    bytes.extend_from_slice(value.as_bytes());
+    bytes.resize(bytes.len() + padded(value.len() + 1) - value.len(), 0);
+}
+
+impl OscMessage {
+    pub fn new(address: &str, args: Vec<OscArg>) -> OscMessage {
+        OscMessage {
+            address: address.to_string(),
+            args,
+        }
+    }
+
+    fn decode(bytes: &[u8]) -> Result<OscMessage, OscError> {
+        let mut reader = Reader { bytes, position: 0 };
+
+        let address = reader.string()?;
+        if !address.starts_with('/') {
+            return Err(OscError::InvalidAddress);
+        }
+
+        // Very old senders leave out the type tags, there is no way to read their arguments
+        if reader.position == bytes.len() {
+            return Ok(OscMessage::new(&address, Vec::new()));
+        }
+        let tags = reader.string()?;
+        let tags = tags.strip_prefix(',').ok_or(OscError::MissingTypeTags)?;
+
+        let mut args = Vec::new();
+        for tag in tags.chars() {
+            args.push(match tag {
+                'i' => OscArg::Int(reader.int()?),
+                'f' => OscArg::Float(f32::from_bits(reader.int()? as u32)),
+                's' => OscArg::String(reader.string()?),
+                'b' => OscArg::Blob(reader.blob()?),
+                'T' => OscArg::Bool(true),
+                'F' => OscArg::Bool(false),
+                _ => return Err(OscError::UnsupportedType(tag)),
+            });
+        }
+
+        Ok(OscMessage { address, args })
+    }
+
+    fn write(&self, bytes: &mut Vec<u8>) {
+        write_string(bytes, &self.address);
+
+        let mut tags = vec![b','];
+        tags.extend(self.args.iter().map(|x| x.type_tag()));
+        write_string(bytes, std::str::from_utf8(&tags).unwrap());
+
+        for arg in &self.args {
+            match arg {
+                OscArg::Int(i) => bytes.extend_from_slice(&i.to_be_bytes()),
+                OscArg::Float(f) => bytes.extend_from_slice(&f.to_be_bytes()),
+                OscArg::String(s) => write_string(bytes, s),
+                OscArg::Blob(b) => {
+                    bytes.extend_from_slice(&(b.len() as i32).to_be_bytes());
+                    bytes.extend_from_slice(b);
+                    bytes.resize(bytes.len() + padded(b.len()) - b.len(), 0);
+                }
+                OscArg::Bool(_) => {}
+            }
+        }
+    }
+}
+
+impl OscBundle {
+    fn decode(bytes: &[u8]) -> Result<OscBundle, OscError> {
+        let mut reader = Reader { bytes, position: 0 };
+        reader.take(BUNDLE_ID.len())?;
+
+        let t = reader.take(8)?;
+        let timetag = u64::from_be_bytes([t[0], t[1], t[2], t[3], t[4], t[5], t[6], t[7]]);
+
+        let mut content = Vec::new();
+        while reader.position < bytes.len() {
+            let size = reader.int()?;
+            if size <= 0 || size % 4 != 0 {
+                return Err(OscError::InvalidBundle);
+            }
+            content.push(OscPacket::decode(reader.take(size as usize)?)?);
+        }
+
+        Ok(OscBundle { timetag, content })
+    }
+
+    fn write(&self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(BUNDLE_ID);
+        bytes.extend_from_slice(&self.timetag.to_be_bytes());
+
+        for packet in &self.content {
+            let element = packet.encode();
+            bytes.extend_from_slice(&(element.len() as i32).to_be_bytes());
+            bytes.extend_from_slice(&element);
+        }
+    }
+}
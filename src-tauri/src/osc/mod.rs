@@ -0,0 +1,188 @@
+pub mod codec;
+pub mod pattern;
+pub mod server;
+
+use std::collections::HashMap;
+
+use crate::osc::codec::OscArg;
+
+// Latest arguments received on an address
+#[derive(Clone, Debug, PartialEq)]
+pub struct OscInput {
+    pub args: Vec<OscArg>,
+    // Increases with every message received, across all addresses
+    pub serial: u64,
+}
+
+pub type OscInputs = HashMap<String, OscInput>;
+
+#[cfg(test)]
+mod tests {
+    use std::net::UdpSocket;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use super::codec::*;
+    use super::pattern::matches;
+    use super::server::*;
+
+    #[test]
+    fn message_encoding() {
+        let message = OscMessage::new("/foo", vec![OscArg::Int(1000), OscArg::Float(0.5)]);
+        let bytes = OscPacket::Message(message.clone()).encode();
+
+        assert_eq!(
+            vec![
+                b'/', b'f', b'o', b'o', 0, 0, 0, 0, // address, padded
+                b',', b'i', b'f', 0, // type tags
+                0, 0, 0x03, 0xE8, // 1000
+                0x3F, 0, 0, 0, // 0.5
+            ],
+            bytes
+        );
+        assert_eq!(Ok(OscPacket::Message(message)), OscPacket::decode(&bytes));
+    }
+
+    #[test]
+    fn message_argument_types() {
+        let message = OscMessage::new(
+            "/all/types",
+            vec![
+                OscArg::String("hello".to_string()),
+                OscArg::Blob(vec![1, 2, 3, 4, 5]),
+                OscArg::Bool(true),
+                OscArg::Bool(false),
+                OscArg::Int(-7),
+                OscArg::Float(-1.25),
+            ],
+        );
+        let bytes = OscPacket::Message(message.clone()).encode();
+
+        assert_eq!(0, bytes.len() % 4);
+        assert_eq!(Ok(OscPacket::Message(message)), OscPacket::decode(&bytes));
+
+        assert_eq!(Some(1.0), OscArg::Bool(true).as_f64());
+        assert_eq!(Some(-7.0), OscArg::Int(-7).as_f64());
+        assert_eq!(None, OscArg::String("1".to_string()).as_f64());
+    }
+
+    #[test]
+    fn bundle_encoding() {
+        let inner = OscBundle {
+            timetag: 42,
+            content: vec![OscPacket::Message(OscMessage::new("/b", vec![]))],
+        };
+        let bundle = OscPacket::Bundle(OscBundle {
+            timetag: IMMEDIATELY,
+            content: vec![
+                OscPacket::Message(OscMessage::new("/a", vec![OscArg::Int(1)])),
+                OscPacket::Bundle(inner),
+            ],
+        });
+        let bytes = bundle.encode();
+
+        assert_eq!(b"#bundle\0", &bytes[0..8]);
+        assert_eq!([0, 0, 0, 0, 0, 0, 0, 1], bytes[8..16]);
+        assert_eq!(Ok(bundle.clone()), OscPacket::decode(&bytes));
+
+        let addresses: Vec<String> = bundle
+            .into_messages()
+            .into_iter()
+            .map(|x| x.address)
+            .collect();
+        assert_eq!(vec!["/a", "/b"], addresses);
+    }
+
+    #[test]
+    fn decode_errors() {
+        let bytes = OscPacket::Message(OscMessage::new("/foo", vec![OscArg::Int(1)])).encode();
+
+        assert_eq!(Err(OscError::Truncated), OscPacket::decode(&bytes[..14]));
+        assert_eq!(
+            Err(OscError::InvalidAddress),
+            OscPacket::decode(b"foo\0,\0\0\0")
+        );
+        assert_eq!(
+            Err(OscError::UnsupportedType('h')),
+            OscPacket::decode(b"/foo\0\0\0\0,h\0\0\0\0\0\0\0\0\0\0")
+        );
+
+        let mut bundle = OscPacket::Bundle(OscBundle {
+            timetag: IMMEDIATELY,
+            content: vec![OscPacket::Message(OscMessage::new("/a", vec![]))],
+        })
+        .encode();
+        bundle[19] = 3;
+        assert_eq!(Err(OscError::InvalidBundle), OscPacket::decode(&bundle));
+    }
+
+    #[test]
+    fn address_patterns() {
+        assert!(matches("/1/fader1", "/1/fader1"));
+        assert!(!matches("/1/fader1", "/1/fader2"));
+        assert!(!matches("/1/fader", "/1/fader/x"));
+
+        assert!(matches("/1/fader?", "/1/fader2"));
+        assert!(!matches("/1/fader?", "/1/fader"));
+
+        assert!(matches("/1/*", "/1/fader1"));
+        assert!(matches("/*/fader1", "/2/fader1"));
+        assert!(matches("/1/fa*er*", "/1/fader12"));
+        assert!(matches("/1/fader*", "/1/fader"));
+        // Wildcards stay within their part
+        assert!(!matches("/*", "/1/fader1"));
+
+        assert!(matches("/1/fader[1-3]", "/1/fader2"));
+        assert!(!matches("/1/fader[1-3]", "/1/fader4"));
+        assert!(matches("/1/fader[!1-3]", "/1/fader4"));
+        assert!(matches("/1/[abc]", "/1/b"));
+        assert!(matches("/1/[-a]", "/1/-"));
+
+        assert!(matches("/{cue,go}/1", "/go/1"));
+        assert!(matches("/{cue,cue_id}/1", "/cue_id/1"));
+        assert!(!matches("/{cue,go}/1", "/stop/1"));
+        assert!(matches("/cue/{1,2}*", "/cue/25"));
+    }
+
+    #[test]
+    fn server_and_client_loopback() {
+        let server = OscServer::bind("127.0.0.1:0").unwrap();
+        let client = OscClient::new(server.local_addr()).unwrap();
+
+        client
+            .send_messages(vec![
+                OscMessage::new("/1/fader1", vec![OscArg::Float(0.25)]),
+                OscMessage::new("/1/fader1", vec![OscArg::Float(0.75)]),
+            ])
+            .unwrap();
+        client
+            .send(&OscPacket::Bundle(OscBundle {
+                timetag: IMMEDIATELY,
+                content: vec![OscPacket::Message(OscMessage::new(
+                    "/1/toggle1",
+                    vec![OscArg::Bool(true)],
+                ))],
+            }))
+            .unwrap();
+        // Garbage doesn't stop the server
+        UdpSocket::bind("127.0.0.1:0")
+            .unwrap()
+            .send_to(b"garbage", server.local_addr())
+            .unwrap();
+
+        let start = Instant::now();
+        while server.snapshot().len() < 2 || server.latest("/1/toggle1").is_none() {
+            assert!(
+                start.elapsed() < Duration::from_secs(2),
+                "No messages received"
+            );
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        let fader = server.latest("/1/fader1").unwrap();
+        let toggle = server.latest("/1/toggle1").unwrap();
+        assert_eq!(vec![OscArg::Float(0.75)], fader.args);
+        assert_eq!(vec![OscArg::Bool(true)], toggle.args);
+        assert!(toggle.serial > fader.serial);
+    }
+}
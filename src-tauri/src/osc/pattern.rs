@@ -0,0 +1,77 @@
+// OSC 1.0 address pattern matching. Wildcards never match across '/':
+//   ?          any single character
+//   *          any run of characters, including none
+//   [abc]      one of the characters, ranges like [a-z], [!abc] negates
+//   {foo,bar}  one of the strings
+pub fn matches(pattern: &str, address: &str) -> bool {
+    let mut pattern_parts = pattern.split('/');
+    let mut address_parts = address.split('/');
+
+    loop {
+        match (pattern_parts.next(), address_parts.next()) {
+            (Some(p), Some(a)) => {
+                if !match_part(p.as_bytes(), a.as_bytes()) {
+                    return false;
+                }
+            }
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
+
+// Patterns without wildcards only match their own address
+pub fn is_literal(pattern: &str) -> bool {
+    !pattern.contains(['?', '*', '[', '{'])
+}
+
+fn match_part(pattern: &[u8], address: &[u8]) -> bool {
+    match pattern.first() {
+        None => address.is_empty(),
+        Some(b'*') => (0..=address.len()).any(|i| match_part(&pattern[1..], &address[i..])),
+        Some(b'?') => !address.is_empty() && match_part(&pattern[1..], &address[1..]),
+        Some(b'[') => match pattern.iter().position(|x| *x == b']') {
+            Some(end) => {
+                !address.is_empty()
+                    && in_set(&pattern[1..end], address[0])
+                    && match_part(&pattern[end + 1..], &address[1..])
+            }
+            // Unclosed, treat it as a literal
+            None => literal(pattern, address),
+        },
+        Some(b'{') => match pattern.iter().position(|x| *x == b'}') {
+            Some(end) => pattern[1..end].split(|x| *x == b',').any(|alternative| {
+                address.starts_with(alternative)
+                    && match_part(&pattern[end + 1..], &address[alternative.len()..])
+            }),
+            None => literal(pattern, address),
+        },
+        Some(_) => literal(pattern, address),
+    }
+}
+
+fn literal(pattern: &[u8], address: &[u8]) -> bool {
+    address.first() == pattern.first() && match_part(&pattern[1..], &address[1..])
+}
+
+fn in_set(set: &[u8], c: u8) -> bool {
+    let (negate, set) = match set.first() {
+        Some(b'!') => (true, &set[1..]),
+        _ => (false, set),
+    };
+
+    let mut found = false;
+    let mut i = 0;
+    while i < set.len() {
+        // A '-' at either end is literal
+        if i + 2 < set.len() && set[i + 1] == b'-' {
+            found |= (set[i]..=set[i + 2]).contains(&c);
+            i += 3;
+        } else {
+            found |= set[i] == c;
+            i += 1;
+        }
+    }
+
+    found != negate
+}
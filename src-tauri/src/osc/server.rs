@@ -0,0 +1,154 @@
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::osc::codec::{OscMessage, OscPacket};
+use crate::osc::{OscInput, OscInputs};
+
+// TouchOSC's defaults, it sends to 8000 and listens on 9000
+pub const OSC_IN_PORT: u16 = 8000;
+pub const OSC_OUT_PORT: u16 = 9000;
+
+// How often the receive thread checks whether it should stop
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+struct Shared {
+    latest: Mutex<OscInputs>,
+    serial: AtomicU64,
+    running: AtomicBool,
+}
+
+// Receives OSC on a background thread and keeps the latest message of every address.
+// Bundles are applied as soon as they arrive, their timetags are ignored.
+pub struct OscServer {
+    local_addr: SocketAddr,
+    shared: Arc<Shared>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl OscServer {
+    pub fn bind(addr: impl ToSocketAddrs) -> io::Result<OscServer> {
+        let socket = UdpSocket::bind(addr)?;
+        socket.set_read_timeout(Some(POLL_INTERVAL))?;
+
+        let local_addr = socket.local_addr()?;
+        let shared = Arc::new(Shared {
+            latest: Mutex::new(OscInputs::new()),
+            serial: AtomicU64::new(0),
+            running: AtomicBool::new(true),
+        });
+
+        let thread = {
+            let shared = shared.clone();
+            thread::Builder::new()
+                .name("osc-server".to_string())
+                .spawn(move || receive_loop(socket, shared))?
+        };
+
+        Ok(OscServer {
+            local_addr,
+            shared,
+            thread: Some(thread),
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    pub fn latest(&self, address: &str) -> Option<OscInput> {
+        self.shared.latest.lock().unwrap().get(address).cloned()
+    }
+
+    pub fn snapshot(&self) -> OscInputs {
+        self.shared.latest.lock().unwrap().clone()
+    }
+}
+
+impl Drop for OscServer {
+    fn drop(&mut self) {
+        self.shared.running.store(false, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn receive_loop(socket: UdpSocket, shared: Arc<Shared>) {
+    let mut buffer = vec![0u8; 65536];
+
+    while shared.running.load(Ordering::Relaxed) {
+        let len = match socket.recv(&mut buffer) {
+            Ok(len) => len,
+            // Read timeouts land here too, giving the running check a chance
+            Err(_) => continue,
+        };
+
+        // Malformed packets are ignored
+        let messages = match OscPacket::decode(&buffer[..len]) {
+            Ok(packet) => packet.into_messages(),
+            Err(_) => continue,
+        };
+
+        let mut latest = shared.latest.lock().unwrap();
+        for message in messages {
+            let serial = shared.serial.fetch_add(1, Ordering::Relaxed) + 1;
+            latest.insert(
+                message.address,
+                OscInput {
+                    args: message.args,
+                    serial,
+                },
+            );
+        }
+    }
+}
+
+// Sends OSC to a single target, e.g. QLab or a TouchOSC tablet
+pub struct OscClient {
+    socket: UdpSocket,
+    target: SocketAddr,
+}
+
+impl OscClient {
+    pub fn new(target: SocketAddr) -> io::Result<OscClient> {
+        let socket = UdpSocket::bind(match target {
+            SocketAddr::V4(_) => "0.0.0.0:0",
+            SocketAddr::V6(_) => "[::]:0",
+        })?;
+        socket.set_broadcast(true)?;
+
+        Ok(OscClient { socket, target })
+    }
+
+    pub fn target(&self) -> SocketAddr {
+        self.target
+    }
+
+    pub fn set_target(&mut self, target: SocketAddr) -> io::Result<()> {
+        // Switching between IPv4 and IPv6 needs a new socket
+        if target.is_ipv4() != self.target.is_ipv4() {
+            *self = OscClient::new(target)?;
+        } else {
+            self.target = target;
+        }
+        Ok(())
+    }
+
+    pub fn send(&self, packet: &OscPacket) -> io::Result<()> {
+        self.socket
+            .send_to(&packet.encode(), self.target)
+            .map(|_| ())
+    }
+
+    // One packet per message, not every receiver handles bundles
+    pub fn send_messages(&self, messages: Vec<OscMessage>) -> io::Result<()> {
+        for message in messages {
+            self.send(&OscPacket::Message(message))?;
+        }
+        Ok(())
+    }
+}
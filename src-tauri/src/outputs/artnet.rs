@@ -0,0 +1,50 @@
+use std::collections::HashMap;
+use std::io;
+use std::net::{SocketAddr, UdpSocket};
+
+use crate::artnet::packet::{ArtDmx, ArtnetPacket};
+use crate::dmx::{PortAddress, UNIVERSE_SIZE};
+use crate::outputs::HardwareInterface;
+
+// Sends ArtDmx to one node, or to everyone with a broadcast target
+pub struct ArtnetOutput {
+    socket: UdpSocket,
+    target: SocketAddr,
+    sequences: HashMap<PortAddress, u8>,
+}
+
+impl ArtnetOutput {
+    pub fn new(target: SocketAddr) -> io::Result<ArtnetOutput> {
+        let socket = UdpSocket::bind(match target {
+            SocketAddr::V4(_) => "0.0.0.0:0",
+            SocketAddr::V6(_) => "[::]:0",
+        })?;
+        socket.set_broadcast(true)?;
+
+        Ok(ArtnetOutput {
+            socket,
+            target,
+            sequences: HashMap::new(),
+        })
+    }
+}
+
+impl HardwareInterface for ArtnetOutput {
+    fn send(&mut self, destination: u16, data: &[u8; UNIVERSE_SIZE]) -> io::Result<()> {
+        let port_address = destination & 0x7FFF;
+
+        // 1..255, 0 would turn off the receiver's reordering
+        let sequence = self.sequences.entry(port_address).or_insert(0);
+        *sequence = sequence.checked_add(1).unwrap_or(1);
+
+        let packet = ArtnetPacket::Dmx(ArtDmx {
+            sequence: *sequence,
+            physical: 0,
+            port_address,
+            data: data.to_vec(),
+        });
+        self.socket
+            .send_to(&packet.encode(), self.target)
+            .map(|_| ())
+    }
+}
@@ -0,0 +1,53 @@
+use std::io;
+
+use crate::dmx::UNIVERSE_SIZE;
+use crate::outputs::open_dmx::encode_frame;
+use crate::outputs::serial::ByteSink;
+use crate::outputs::HardwareInterface;
+
+const START_OF_MESSAGE: u8 = 0x7E;
+const END_OF_MESSAGE: u8 = 0xE7;
+const LABEL_SEND_DMX: u8 = 6;
+
+// Enttec DMX USB Pro. The widget times the DMX frames itself, it is sent
+// the levels in a "send DMX" (label 6) message whenever they change.
+pub struct EnttecProOutput {
+    sink: Box<dyn ByteSink>,
+    last: Option<[u8; UNIVERSE_SIZE]>,
+}
+
+impl EnttecProOutput {
+    pub fn new(sink: Box<dyn ByteSink>) -> EnttecProOutput {
+        EnttecProOutput { sink, last: None }
+    }
+}
+
+// 0x7E, label, data length (LSB first), data, 0xE7
+pub fn encode_message(label: u8, data: &[u8]) -> Vec<u8> {
+    let mut message = Vec::with_capacity(data.len() + 5);
+    message.push(START_OF_MESSAGE);
+    message.push(label);
+    message.extend_from_slice(&(data.len() as u16).to_le_bytes());
+    message.extend_from_slice(data);
+    message.push(END_OF_MESSAGE);
+    message
+}
+
+// Data is the start code and up to 512 levels
+pub fn encode_send_dmx(data: &[u8]) -> Vec<u8> {
+    encode_message(LABEL_SEND_DMX, &encode_frame(data))
+}
+
+impl HardwareInterface for EnttecProOutput {
+    // One universe per widget, the destination isn't used
+    fn send(&mut self, _destination: u16, data: &[u8; UNIVERSE_SIZE]) -> io::Result<()> {
+        // The widget keeps repeating the last levels on its own
+        if self.last.as_ref() == Some(data) {
+            return Ok(());
+        }
+
+        self.sink.write(&encode_send_dmx(data))?;
+        self.last = Some(*data);
+        Ok(())
+    }
+}
@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+use std::io;
+
+use crate::dmx::UNIVERSE_SIZE;
+use crate::midi::message::MidiMessage;
+use crate::midi::port::MidiPort;
+use crate::outputs::HardwareInterface;
+
+const CONTROLLERS: usize = 128;
+const MIDI_CHANNELS: usize = 16;
+
+// For controllers with motor faders or LED rings. DMX channels 1-128 become
+// CC 0-127 on the first MIDI channel, 129-256 on the next one and so on.
+// Only changes of the 7 bit value are sent.
+pub struct MidiOutput {
+    port: Box<dyn MidiPort>,
+    // Last value sent, by MIDI channel and controller, so universes routed
+    // to different MIDI channels don't hide each other's changes
+    last: HashMap<(u8, u8), u8>,
+}
+
+impl MidiOutput {
+    pub fn new(port: Box<dyn MidiPort>) -> MidiOutput {
+        MidiOutput {
+            port,
+            last: HashMap::new(),
+        }
+    }
+}
+
+impl HardwareInterface for MidiOutput {
+    // destination is the first MIDI channel, 1..16
+    fn send(&mut self, destination: u16, data: &[u8; UNIVERSE_SIZE]) -> io::Result<()> {
+        let first = destination.clamp(1, MIDI_CHANNELS as u16) as usize - 1;
+
+        let mut changes = Vec::new();
+        let mut bytes = Vec::new();
+        for (i, level) in data.iter().enumerate() {
+            let channel = first + i / CONTROLLERS;
+            if channel >= MIDI_CHANNELS {
+                break;
+            }
+
+            let key = (channel as u8, (i % CONTROLLERS) as u8);
+            let value = level >> 1;
+            if self.last.get(&key) == Some(&value) {
+                continue;
+            }
+            changes.push((key, value));
+
+            let message = MidiMessage::ControlChange {
+                channel: key.0,
+                controller: key.1,
+                value,
+            };
+            bytes.extend(message.encode());
+        }
+
+        if bytes.is_empty() {
+            return Ok(());
+        }
+        self.port
+            .send(&bytes)
+            .map_err(|e| io::Error::new(io::ErrorKind::BrokenPipe, e.to_string()))?;
+        // Only once sent, failed changes are tried again next frame
+        self.last.extend(changes);
+        Ok(())
+    }
+}
@@ -0,0 +1,308 @@
+pub mod artnet;
+pub mod enttec;
+pub mod midi;
+pub mod open_dmx;
+pub mod sacn;
+pub mod serial;
+pub mod threaded;
+
+use std::io;
+use std::net::SocketAddr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::dmx::merge::UniverseMerge;
+use crate::dmx::{PortAddress, UNIVERSE_SIZE};
+
+pub type InterfaceId = u32;
+
+// Where merged universes leave the program, one per configured interface
+pub trait HardwareInterface: Send {
+    // destination is the universe as the interface knows it, see Route
+    fn send(&mut self, destination: u16, data: &[u8; UNIVERSE_SIZE]) -> io::Result<()>;
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum InterfaceKind {
+    // A node's IP, or a broadcast address, usually on port 6454
+    ArtNet { target: SocketAddr },
+    // Multicast to every universe's group, unless a unicast target is set
+    Sacn { target: Option<SocketAddr> },
+    // Serial devices, e.g. /dev/ttyUSB0 or COM3
+    OpenDmx { port: String },
+    EnttecPro { port: String },
+    // Levels become Control Changes, see MidiOutput
+    Midi { port: String },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct InterfaceConfig {
+    pub id: InterfaceId,
+    pub name: String,
+    pub kind: InterfaceKind,
+}
+
+// Sends a merged universe through an interface. The destination is the
+// Art-Net port address, sACN universe or first MIDI channel, unused for OpenDMX.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Route {
+    pub universe: PortAddress,
+    pub interface: InterfaceId,
+    pub destination: u16,
+}
+
+// The routing saved with a project
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct OutputConfig {
+    pub interfaces: Vec<InterfaceConfig>,
+    pub routes: Vec<Route>,
+    #[serde(default)]
+    pub merge: Vec<UniverseMerge>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "state", content = "message")]
+pub enum InterfaceStatus {
+    // Nothing sent yet
+    Idle,
+    Ok,
+    Error(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::UdpSocket;
+    use std::time::Duration;
+
+    use super::artnet::ArtnetOutput;
+    use super::enttec::{encode_send_dmx, EnttecProOutput};
+    use super::midi::MidiOutput;
+    use super::open_dmx::OpenDmxOutput;
+    use super::sacn::SacnOutput;
+    use super::serial::{is_device_path, open_file_sink, MemorySink, SinkEvent};
+    use super::threaded::ThreadedOutput;
+    use super::*;
+    use crate::artnet::packet::{ArtDmx, ArtnetPacket};
+    use crate::midi::port::VirtualPort;
+    use crate::sacn::packet::SacnData;
+
+    fn listener() -> UdpSocket {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        socket
+            .set_read_timeout(Some(Duration::from_secs(2)))
+            .unwrap();
+        socket
+    }
+
+    fn receive(socket: &UdpSocket) -> Vec<u8> {
+        let mut buffer = [0; 1024];
+        let (len, _) = socket.recv_from(&mut buffer).unwrap();
+        buffer[..len].to_vec()
+    }
+
+    fn universe(levels: &[(usize, u8)]) -> [u8; UNIVERSE_SIZE] {
+        let mut data = [0; UNIVERSE_SIZE];
+        for (channel, level) in levels {
+            data[channel - 1] = *level;
+        }
+        data
+    }
+
+    #[test]
+    fn artnet_output_loopback() {
+        let socket = listener();
+        let mut output = ArtnetOutput::new(socket.local_addr().unwrap()).unwrap();
+
+        output.send(0x12, &universe(&[(1, 255), (512, 7)])).unwrap();
+        output.send(0x12, &universe(&[])).unwrap();
+
+        let first = ArtnetPacket::decode(&receive(&socket)).unwrap();
+        let second = ArtnetPacket::decode(&receive(&socket)).unwrap();
+        let ArtnetPacket::Dmx(ArtDmx {
+            sequence,
+            port_address,
+            data,
+            ..
+        }) = first
+        else {
+            panic!("Expected ArtDmx");
+        };
+        assert_eq!(0x12, port_address);
+        assert_eq!(1, sequence);
+        assert_eq!(512, data.len());
+        assert_eq!((255, 7), (data[0], data[511]));
+
+        // Sequence counts per universe
+        assert!(matches!(
+            second,
+            ArtnetPacket::Dmx(ArtDmx { sequence: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn sacn_output_loopback() {
+        let socket = listener();
+        let mut output = SacnOutput::new(Some(socket.local_addr().unwrap())).unwrap();
+
+        output.send(5, &universe(&[(3, 128)])).unwrap();
+        output.send(6, &universe(&[])).unwrap();
+
+        let first = SacnData::decode(&receive(&socket)).unwrap();
+        let second = SacnData::decode(&receive(&socket)).unwrap();
+        assert_eq!(5, first.universe);
+        assert_eq!(128, first.data[2]);
+        assert_eq!(6, second.universe);
+        assert_eq!(first.cid, second.cid);
+        assert_eq!("wovmoh", first.source_name);
+    }
+
+    #[test]
+    fn midi_output_sends_changes_as_cc() {
+        let port = VirtualPort::new("Faders");
+        let mut output = MidiOutput::new(Box::new(port.clone()));
+
+        // Everything is sent the first time. Starting on MIDI channel 2,
+        // channel 129 lands on MIDI channel 3.
+        output
+            .send(2, &universe(&[(1, 255), (2, 100), (129, 2)]))
+            .unwrap();
+        let sent = port.take_sent();
+        assert_eq!(UNIVERSE_SIZE * 3, sent.len());
+        assert_eq!(&[0xB1, 0, 127, 0xB1, 1, 50, 0xB1, 2, 0], &sent[0..9]);
+        assert_eq!(&[0xB2, 0, 1], &sent[128 * 3..129 * 3]);
+
+        // Unchanged or below the 7 bit resolution, nothing to send
+        output
+            .send(2, &universe(&[(1, 255), (2, 101), (129, 2)]))
+            .unwrap();
+        assert!(port.take_sent().is_empty());
+
+        output.send(2, &universe(&[(2, 100), (129, 2)])).unwrap();
+        assert_eq!(vec![0xB1, 0, 0], port.take_sent());
+
+        // A universe on other MIDI channels keeps its own values
+        output.send(9, &universe(&[(1, 255)])).unwrap();
+        assert_eq!(&[0xB8, 0, 127], &port.take_sent()[0..3]);
+        output.send(2, &universe(&[(2, 100), (129, 2)])).unwrap();
+        assert!(port.take_sent().is_empty());
+
+        // Failed sends are tried again next frame
+        port.set_unplugged(true);
+        assert!(output.send(2, &universe(&[(1, 255)])).is_err());
+        port.set_unplugged(false);
+        output.send(2, &universe(&[(1, 255)])).unwrap();
+        assert_eq!(vec![0xB1, 0, 127, 0xB1, 1, 0, 0xB2, 0, 0], port.take_sent());
+    }
+
+    #[test]
+    fn enttec_send_dmx_encoding() {
+        assert_eq!(
+            vec![0x7E, 6, 4, 0, 0, 10, 20, 30, 0xE7],
+            encode_send_dmx(&[10, 20, 30])
+        );
+
+        let bytes = encode_send_dmx(&universe(&[(1, 1), (512, 2)]));
+        assert_eq!(518, bytes.len());
+        // 513 bytes of data, start code included
+        assert_eq!(&[0x7E, 6, 0x01, 0x02, 0, 1], &bytes[0..6]);
+        assert_eq!(&[2, 0xE7], &bytes[516..518]);
+    }
+
+    #[test]
+    fn enttec_output_sends_changes() {
+        let sink = MemorySink::new();
+        let mut output = EnttecProOutput::new(Box::new(sink.clone()));
+
+        let data = universe(&[(1, 255)]);
+        output.send(0, &data).unwrap();
+        output.send(0, &data).unwrap();
+        assert_eq!(
+            vec![SinkEvent::Write(encode_send_dmx(&data))],
+            sink.take_events()
+        );
+
+        // Failed writes are tried again next frame
+        sink.set_error(Some(io::ErrorKind::BrokenPipe));
+        let data = universe(&[(1, 0)]);
+        assert!(output.send(0, &data).is_err());
+        sink.set_error(None);
+        output.send(0, &data).unwrap();
+        assert_eq!(1, sink.take_events().len());
+    }
+
+    #[test]
+    fn open_dmx_break_and_mark_after_break() {
+        let sink = MemorySink::new();
+        let mut output = OpenDmxOutput::new(Box::new(sink.clone()));
+
+        let data = universe(&[(2, 128)]);
+        output.send(0, &data).unwrap();
+        output.send(0, &data).unwrap();
+
+        let mut frame = vec![0; UNIVERSE_SIZE + 1];
+        frame[2] = 128;
+        let expected = vec![
+            SinkEvent::Break(true),
+            SinkEvent::Wait(Duration::from_micros(176)),
+            SinkEvent::Break(false),
+            SinkEvent::Wait(Duration::from_micros(12)),
+            SinkEvent::Write(frame),
+        ];
+        let events = sink.take_events();
+        // Every frame, not only changes
+        assert_eq!(expected, events[0..5]);
+        assert_eq!(expected, events[5..10]);
+
+        sink.set_error(Some(io::ErrorKind::NotConnected));
+        let error = output.send(0, &data).unwrap_err();
+        assert_eq!(io::ErrorKind::NotConnected, error.kind());
+    }
+
+    #[test]
+    fn file_sink_only_opens_devices() {
+        assert!(is_device_path("/dev/ttyUSB0"));
+        assert!(is_device_path("COM3"));
+        assert!(is_device_path(r"\\.\COM12"));
+        assert!(!is_device_path("/dev/../etc/passwd"));
+        assert!(!is_device_path("/tmp/levels"));
+        assert!(!is_device_path("COM"));
+        assert!(!is_device_path("levels.txt"));
+
+        let path = std::env::temp_dir().join("wovmoh_file_sink.txt");
+        let error = open_file_sink(path.to_str().unwrap()).err().unwrap();
+        assert_eq!(io::ErrorKind::InvalidInput, error.kind());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn threaded_output_sends_the_latest_levels() {
+        let sink = MemorySink::new();
+        let mut output = ThreadedOutput::new(Box::new(OpenDmxOutput::new(Box::new(sink.clone()))));
+
+        let data = universe(&[(1, 255)]);
+        output.send(0, &data).unwrap();
+        let start = std::time::Instant::now();
+        let mut events = Vec::new();
+        while !events.iter().any(|x| matches!(x, SinkEvent::Write(_))) {
+            assert!(start.elapsed() < Duration::from_secs(2), "Nothing written");
+            std::thread::sleep(Duration::from_millis(1));
+            events.extend(sink.take_events());
+        }
+        assert_eq!(SinkEvent::Break(true), events[0]);
+
+        // The writer's error comes back on a later send
+        sink.set_error(Some(io::ErrorKind::BrokenPipe));
+        let start = std::time::Instant::now();
+        while output.send(0, &data).is_ok() {
+            assert!(start.elapsed() < Duration::from_secs(2), "No error");
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        sink.set_error(None);
+        let start = std::time::Instant::now();
+        while output.send(0, &data).is_err() {
+            assert!(start.elapsed() < Duration::from_secs(2), "Still failing");
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use std::io;
+use std::time::Duration;
+
+use crate::dmx::UNIVERSE_SIZE;
+use crate::outputs::serial::ByteSink;
+use crate::outputs::HardwareInterface;
+
+// Within the DMX512 minimums of 88us and 8us, with room for slow drivers
+pub const BREAK_TIME: Duration = Duration::from_micros(176);
+pub const MAB_TIME: Duration = Duration::from_micros(12);
+
+// FTDI based OpenDMX adapter. The adapter is a plain serial port at 250000
+// baud 8N2, so the break and mark after break are timed here every frame.
+pub struct OpenDmxOutput {
+    sink: Box<dyn ByteSink>,
+}
+
+impl OpenDmxOutput {
+    pub fn new(sink: Box<dyn ByteSink>) -> OpenDmxOutput {
+        OpenDmxOutput { sink }
+    }
+}
+
+// Null start code, then the levels
+pub fn encode_frame(data: &[u8]) -> Vec<u8> {
+    let data = &data[..data.len().min(UNIVERSE_SIZE)];
+
+    let mut frame = Vec::with_capacity(data.len() + 1);
+    frame.push(0);
+    frame.extend_from_slice(data);
+    frame
+}
+
+impl HardwareInterface for OpenDmxOutput {
+    // One universe per adapter, the destination isn't used
+    fn send(&mut self, _destination: u16, data: &[u8; UNIVERSE_SIZE]) -> io::Result<()> {
+        self.sink.set_break(true)?;
+        self.sink.wait(BREAK_TIME);
+        self.sink.set_break(false)?;
+        self.sink.wait(MAB_TIME);
+        self.sink.write(&encode_frame(data))
+    }
+}
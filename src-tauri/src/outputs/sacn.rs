@@ -0,0 +1,68 @@
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::io;
+use std::net::{SocketAddr, UdpSocket};
+
+use crate::dmx::UNIVERSE_SIZE;
+use crate::outputs::HardwareInterface;
+use crate::sacn::packet::{multicast_addr, SacnData, DEFAULT_PRIORITY, MAX_UNIVERSE, SACN_PORT};
+
+const SOURCE_NAME: &str = "wovmoh";
+
+// Sends E1.31 to each universe's multicast group, or to a unicast target
+pub struct SacnOutput {
+    socket: UdpSocket,
+    target: Option<SocketAddr>,
+    cid: [u8; 16],
+    sequences: HashMap<u16, u8>,
+}
+
+impl SacnOutput {
+    pub fn new(target: Option<SocketAddr>) -> io::Result<SacnOutput> {
+        let socket = UdpSocket::bind(match target {
+            Some(SocketAddr::V6(_)) => "[::]:0",
+            _ => "0.0.0.0:0",
+        })?;
+
+        Ok(SacnOutput {
+            socket,
+            target,
+            cid: random_cid(),
+            sequences: HashMap::new(),
+        })
+    }
+}
+
+impl HardwareInterface for SacnOutput {
+    fn send(&mut self, destination: u16, data: &[u8; UNIVERSE_SIZE]) -> io::Result<()> {
+        let universe = destination.clamp(1, MAX_UNIVERSE);
+
+        let sequence = self.sequences.entry(universe).or_insert(0);
+        *sequence = sequence.wrapping_add(1);
+
+        let packet = SacnData {
+            cid: self.cid,
+            source_name: SOURCE_NAME.to_string(),
+            priority: DEFAULT_PRIORITY,
+            sequence: *sequence,
+            terminated: false,
+            universe,
+            data: data.to_vec(),
+        };
+        let target = self
+            .target
+            .unwrap_or(SocketAddr::from((multicast_addr(universe), SACN_PORT)));
+
+        self.socket.send_to(&packet.encode(), target).map(|_| ())
+    }
+}
+
+// Only has to differ between senders, std's hasher keys are random enough
+fn random_cid() -> [u8; 16] {
+    let mut cid = [0; 16];
+    for half in cid.chunks_mut(8) {
+        half.copy_from_slice(&RandomState::new().build_hasher().finish().to_be_bytes());
+    }
+    cid
+}
@@ -0,0 +1,174 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Component, Path};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use serialport::{DataBits, FlowControl, Parity, SerialPort, StopBits};
+
+// The serial side of a USB DMX adapter. Implemented by whatever drives the
+// port on the platform, and by MemorySink in tests.
+pub trait ByteSink: Send {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()>;
+
+    // Holds the line low while on, the break before each DMX frame
+    fn set_break(&mut self, on: bool) -> io::Result<()>;
+
+    fn wait(&mut self, duration: Duration);
+}
+
+// Opens the sink for a port name, e.g. /dev/ttyUSB0 or COM3
+pub type SinkOpener = Box<dyn Fn(&str) -> io::Result<Box<dyn ByteSink>> + Send>;
+
+// DMX line settings. The Enttec Pro ignores them, it frames DMX itself.
+pub const DMX_BAUD_RATE: u32 = 250_000;
+
+// A serial port with line control, so an OpenDMX gets its break
+pub struct SerialSink {
+    port: Box<dyn SerialPort>,
+}
+
+impl SerialSink {
+    pub fn open(port: &str) -> io::Result<SerialSink> {
+        let port = serialport::new(port, DMX_BAUD_RATE)
+            .data_bits(DataBits::Eight)
+            .parity(Parity::None)
+            .stop_bits(StopBits::Two)
+            .flow_control(FlowControl::None)
+            .timeout(Duration::from_millis(100))
+            .open()?;
+        Ok(SerialSink { port })
+    }
+}
+
+impl ByteSink for SerialSink {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.port.write_all(bytes)?;
+        self.port.flush()
+    }
+
+    fn set_break(&mut self, on: bool) -> io::Result<()> {
+        if on {
+            Ok(self.port.set_break()?)
+        } else {
+            Ok(self.port.clear_break()?)
+        }
+    }
+
+    fn wait(&mut self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+// The default opener
+pub fn open_serial_sink(port: &str) -> io::Result<Box<dyn ByteSink>> {
+    Ok(Box::new(SerialSink::open(port)?))
+}
+
+// A device written as a plain file. Enough for adapters that frame DMX
+// themselves, it has no line control for a break.
+pub struct FileSink {
+    device: File,
+}
+
+impl FileSink {
+    // Only device paths, so a wrong port name can't write into a file
+    pub fn open(port: &str) -> io::Result<FileSink> {
+        if !is_device_path(port) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("'{}' isn't a serial device", port),
+            ));
+        }
+        let device = OpenOptions::new().write(true).open(port)?;
+        Ok(FileSink { device })
+    }
+}
+
+impl ByteSink for FileSink {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.device.write_all(bytes)?;
+        self.device.flush()
+    }
+
+    fn set_break(&mut self, _on: bool) -> io::Result<()> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "this port can't send a DMX break",
+        ))
+    }
+
+    fn wait(&mut self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+pub fn open_file_sink(port: &str) -> io::Result<Box<dyn ByteSink>> {
+    Ok(Box::new(FileSink::open(port)?))
+}
+
+// Under /dev, or a Windows COM port
+pub fn is_device_path(port: &str) -> bool {
+    let path = Path::new(port);
+    if path.starts_with("/dev") {
+        return !path.components().any(|x| x == Component::ParentDir);
+    }
+
+    let name = port.strip_prefix(r"\\.\").unwrap_or(port);
+    name.len() > 3
+        && name[..3].eq_ignore_ascii_case("COM")
+        && name[3..].chars().all(|x| x.is_ascii_digit())
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SinkEvent {
+    Write(Vec<u8>),
+    Break(bool),
+    Wait(Duration),
+}
+
+// In memory sink. Clones share their events, so a test can keep one to read
+// back what the other was sent. Waits are recorded, not slept.
+#[derive(Clone, Default)]
+pub struct MemorySink {
+    events: Arc<Mutex<Vec<SinkEvent>>>,
+    // Every call fails with this while set, as an unplugged device would
+    error: Arc<Mutex<Option<io::ErrorKind>>>,
+}
+
+impl MemorySink {
+    pub fn new() -> MemorySink {
+        MemorySink::default()
+    }
+
+    pub fn take_events(&self) -> Vec<SinkEvent> {
+        std::mem::take(&mut *self.events.lock().unwrap())
+    }
+
+    pub fn set_error(&self, error: Option<io::ErrorKind>) {
+        *self.error.lock().unwrap() = error;
+    }
+
+    fn record(&self, event: SinkEvent) -> io::Result<()> {
+        if let Some(kind) = *self.error.lock().unwrap() {
+            return Err(io::Error::from(kind));
+        }
+        self.events.lock().unwrap().push(event);
+        Ok(())
+    }
+}
+
+impl ByteSink for MemorySink {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.record(SinkEvent::Write(bytes.to_vec()))
+    }
+
+    fn set_break(&mut self, on: bool) -> io::Result<()> {
+        self.record(SinkEvent::Break(on))
+    }
+
+    fn wait(&mut self, duration: Duration) {
+        let _ = self.record(SinkEvent::Wait(duration));
+    }
+}
@@ -0,0 +1,86 @@
+use std::io;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+
+use crate::dmx::UNIVERSE_SIZE;
+use crate::outputs::HardwareInterface;
+
+#[derive(Default)]
+struct Slot {
+    // Latest universe handed over, replaced if the writer hasn't taken it yet
+    pending: Option<(u16, [u8; UNIVERSE_SIZE])>,
+    // Result of the writer's last send
+    result: Option<Result<(), (io::ErrorKind, String)>>,
+    closed: bool,
+}
+
+type Shared = Arc<(Mutex<Slot>, Condvar)>;
+
+// Runs a slow interface on its own thread, e.g. a serial adapter that takes
+// most of a frame to send a universe. send() only hands the latest levels
+// over and returns how the previous send went.
+pub struct ThreadedOutput {
+    shared: Shared,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ThreadedOutput {
+    pub fn new(device: Box<dyn HardwareInterface>) -> ThreadedOutput {
+        let shared = Shared::default();
+        let thread = {
+            let shared = shared.clone();
+            thread::spawn(move || write_loop(device, shared))
+        };
+        ThreadedOutput {
+            shared,
+            thread: Some(thread),
+        }
+    }
+}
+
+fn write_loop(mut device: Box<dyn HardwareInterface>, shared: Shared) {
+    let (slot, ready) = &*shared;
+    loop {
+        let (destination, data) = {
+            let mut slot = slot.lock().unwrap();
+            while slot.pending.is_none() && !slot.closed {
+                slot = ready.wait(slot).unwrap();
+            }
+            match slot.pending.take() {
+                Some(pending) if !slot.closed => pending,
+                _ => return,
+            }
+        };
+
+        let result = device
+            .send(destination, &data)
+            .map_err(|e| (e.kind(), e.to_string()));
+        slot.lock().unwrap().result = Some(result);
+    }
+}
+
+impl HardwareInterface for ThreadedOutput {
+    fn send(&mut self, destination: u16, data: &[u8; UNIVERSE_SIZE]) -> io::Result<()> {
+        let (slot, ready) = &*self.shared;
+        let mut slot = slot.lock().unwrap();
+        slot.pending = Some((destination, *data));
+        ready.notify_one();
+
+        match &slot.result {
+            Some(Err((kind, message))) => Err(io::Error::new(*kind, message.clone())),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Drop for ThreadedOutput {
+    // Waits for a send in progress, so the device is closed before it's reopened
+    fn drop(&mut self) {
+        let (slot, ready) = &*self.shared;
+        slot.lock().unwrap().closed = true;
+        ready.notify_one();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::compound::CompoundDef;
+use crate::core::graph::GraphConfig;
+use crate::core::snapshot::Snapshot;
+use crate::cues::CueList;
+use crate::fixtures::FixtureConfig;
+use crate::outputs::OutputConfig;
+
+// Everything saved with a show, as JSON. Sections default when missing so
+// older files keep loading as sections are added.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Project {
+    #[serde(default)]
+    pub outputs: OutputConfig,
+    #[serde(default)]
+    pub fixtures: FixtureConfig,
+    #[serde(default)]
+    pub cues: Vec<CueList>,
+    // Every graph with its timeline, snapshots refer to their node ids
+    #[serde(default)]
+    pub graphs: Vec<GraphConfig>,
+    #[serde(default)]
+    pub snapshots: Vec<Snapshot>,
+    #[serde(default)]
+    pub compounds: Vec<CompoundDef>,
+}
+
+impl Project {
+    pub fn from_json(json: &str) -> serde_json::Result<Project> {
+        serde_json::from_str(json)
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn load(path: &Path) -> io::Result<Project> {
+        Ok(Project::from_json(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_json()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use super::*;
+    use crate::core::node::{NodeKind, NodeUIState};
+    use crate::dmx::merge::{
+        ChannelRange, MergePolicy, MergeSource, SourcePriority, UniverseMerge,
+    };
+    use crate::managers::fixtures_manager::FixturesManager;
+    use crate::managers::graph_manager::GraphManager;
+    use crate::outputs::{InterfaceConfig, InterfaceKind, Route};
+    use crate::timeline::transport::LoopRegion;
+    use crate::timeline::{Interpolation, Keyframe};
+    use crate::types::data_type::DataValue::*;
+
+    #[test]
+    fn outputs_round_trip() {
+        let project = Project {
+            outputs: OutputConfig {
+                interfaces: vec![
+                    InterfaceConfig {
+                        id: 0,
+                        name: "Stage left".to_string(),
+                        kind: InterfaceKind::ArtNet {
+                            target: SocketAddr::from(([10, 0, 0, 20], 6454)),
+                        },
+                    },
+                    InterfaceConfig {
+                        id: 3,
+                        name: "House".to_string(),
+                        kind: InterfaceKind::Sacn { target: None },
+                    },
+                ],
+                routes: vec![Route {
+                    universe: 1,
+                    interface: 3,
+                    destination: 2,
+                }],
+                merge: vec![UniverseMerge {
+                    universe: 1,
+                    policy: MergePolicy::Htp,
+                    ranges: vec![ChannelRange {
+                        start: 10,
+                        end: 20,
+                        policy: MergePolicy::Ltp,
+                    }],
+                    priorities: vec![SourcePriority {
+                        source: MergeSource::Graph(2),
+                        priority: 150,
+                    }],
+                    input: Some(5),
+                }],
+            },
+            ..Project::default()
+        };
+
+        let json = project.to_json().unwrap();
+        assert!(json.contains("\"type\": \"ArtNet\""));
+        assert!(json.contains("\"target\": \"10.0.0.20:6454\""));
+        assert_eq!(project, Project::from_json(&json).unwrap());
+
+        let path = std::env::temp_dir().join("wovmoh_outputs_round_trip.json");
+        project.save(&path).unwrap();
+        assert_eq!(project, Project::load(&path).unwrap());
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn fixtures_round_trip() {
+        let mut fixtures = FixturesManager::new();
+        let mut profile = fixtures.library().get("Generic/RGB").unwrap().clone();
+        profile.manufacturer = "Acme".to_string();
+        fixtures.add_profile(profile);
+        fixtures
+            .patch("Wash", "Acme/RGB", "3 channel", 1, 10)
+            .unwrap();
+        let spot = fixtures
+            .patch("Spot", "Generic/Dimmer", "8 bit", 2, 1)
+            .unwrap();
+        fixtures.add_group("Spots", vec![spot]).unwrap();
+
+        let project = Project {
+            fixtures: fixtures.config(),
+            ..Project::default()
+        };
+        // Only the profiles in use are saved
+        assert_eq!(2, project.fixtures.profiles.len());
+
+        let project = Project::from_json(&project.to_json().unwrap()).unwrap();
+        let mut loaded = FixturesManager::new();
+        loaded.set_config(project.fixtures);
+        assert_eq!(fixtures.config(), loaded.config());
+        assert_eq!(fixtures.fixture_map(), loaded.fixture_map());
+        assert_eq!(2, loaded.fixture_map().fixtures.len());
+
+        assert_eq!(vec![spot], loaded.groups()[0].fixtures);
+
+        // New fixtures and groups don't reuse loaded ids
+        let id = loaded
+            .patch("Extra", "Acme/RGB", "3 channel", 3, 1)
+            .unwrap();
+        assert_eq!(2, id);
+        assert_eq!(1, loaded.add_group("Extra", vec![id]).unwrap());
+    }
+
+    #[test]
+    fn timelines_round_trip() {
+        let mut graphs = GraphManager::new();
+        graphs.add_graph();
+        let graph = graphs.get_graph_mut(0);
+        graph.add_node(
+            NodeKind::ConstantNumber,
+            NodeUIState {
+                position: (0.0, 0.0),
+            },
+        );
+        for (time, value) in [(0.0, 0.0), (2.0, 10.0)] {
+            let keyframe = Keyframe {
+                time,
+                value: Number(value),
+                interpolation: Interpolation::Linear,
+            };
+            graph.set_keyframe(0, keyframe).unwrap();
+        }
+        let transport = &mut graph.timeline_mut().transport;
+        transport.set_loop(Some(LoopRegion {
+            start: 0.5,
+            end: 1.5,
+        }));
+        transport.play();
+
+        let project = Project {
+            graphs: graphs.graphs_config(),
+            ..Project::default()
+        };
+        let project = Project::from_json(&project.to_json().unwrap()).unwrap();
+        let mut loaded = GraphManager::new();
+        loaded.set_graphs(project.graphs);
+        assert_eq!(graphs.graphs_config(), loaded.graphs_config());
+
+        loaded.tick(1.0);
+        assert_eq!(Number(5.0), loaded.get_graph(0).get_input_value(0));
+    }
+
+    #[test]
+    fn missing_sections_default() {
+        assert_eq!(Project::default(), Project::from_json("{}").unwrap());
+        assert!(Project::from_json("{\"outputs\": 1}").is_err());
+    }
+}
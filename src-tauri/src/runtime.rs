@@ -0,0 +1,89 @@
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Tauri Imports
+use tauri::async_runtime::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::managers::cues_manager::CuesManager;
+use crate::managers::graph_manager::GraphManager;
+use crate::managers::inputs_manager::InputsManager;
+use crate::managers::outputs_manager::OutputsManager;
+use crate::osc::server::OscClient;
+
+pub const FRAME_RATE: f64 = 40.0;
+
+// Inputs are taken at the start of the frame and cue fades advanced, then
+// every graph is processed and the DMX they wrote goes out
+pub fn run_frame(
+    graphs: &mut GraphManager,
+    inputs: &mut InputsManager,
+    outputs: &mut OutputsManager,
+    cues: &mut CuesManager,
+    delta: f64,
+) {
+    if let Some(target) = inputs.midi().learning() {
+        let graph = graphs.get_graph(target.graph_id);
+        inputs.midi_mut().check_learn(graph);
+    }
+    inputs.poll();
+
+    if let Some(learned) = inputs.midi_mut().take_learned() {
+        learned.apply(graphs.get_graph_mut(learned.target.graph_id));
+    }
+
+    graphs.set_external_input(inputs.external_inputs());
+    let dmx_in = Arc::new(inputs.dmx_inputs());
+    graphs.set_dmx_input(dmx_in.clone());
+    graphs.set_osc_input(inputs.osc_inputs());
+    graphs.set_midi_input(inputs.midi_inputs().clone());
+
+    cues.tick(delta);
+    graphs.set_cues(cues.output());
+
+    graphs.tick(delta);
+
+    outputs.process(graphs.take_dmx_output(), &dmx_in);
+}
+
+// Runs frames at FRAME_RATE on a thread of its own
+pub fn start(app: AppHandle) {
+    let period = Duration::from_secs_f64(1.0 / FRAME_RATE);
+
+    thread::Builder::new()
+        .name("frame-loop".to_string())
+        .spawn(move || {
+            let mut last = Instant::now();
+            loop {
+                let start = Instant::now();
+                let delta = start.duration_since(last).as_secs_f64();
+                last = start;
+
+                {
+                    // Same lock order as the commands: graphs, inputs, outputs, cues
+                    let graphs = app.state::<Mutex<GraphManager>>();
+                    let inputs = app.state::<Mutex<InputsManager>>();
+                    let outputs = app.state::<Mutex<OutputsManager>>();
+                    let cues = app.state::<Mutex<CuesManager>>();
+                    let mut graphs = graphs.blocking_lock();
+                    let mut inputs = inputs.blocking_lock();
+                    let mut outputs = outputs.blocking_lock();
+                    let mut cues = cues.blocking_lock();
+
+                    run_frame(&mut graphs, &mut inputs, &mut outputs, &mut cues, delta);
+
+                    let osc_out = graphs.take_osc_output();
+                    let _ = app
+                        .state::<Mutex<OscClient>>()
+                        .blocking_lock()
+                        .send_messages(osc_out);
+                    let midi_out = graphs.take_midi_output();
+                    inputs.midi_mut().send(&midi_out);
+                }
+
+                thread::sleep(period.saturating_sub(start.elapsed()));
+            }
+        })
+        .expect("error while starting the frame loop");
+}
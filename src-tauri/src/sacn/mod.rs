@@ -0,0 +1,105 @@
+pub mod packet;
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::packet::*;
+
+    fn data(universe: u16, data: Vec<u8>) -> SacnData {
+        SacnData {
+            cid: [
+                0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E,
+                0x0F, 0x10,
+            ],
+            source_name: "wovmoh".to_string(),
+            priority: DEFAULT_PRIORITY,
+            sequence: 7,
+            terminated: false,
+            universe,
+            data,
+        }
+    }
+
+    #[test]
+    fn data_packet_encoding() {
+        let bytes = data(1, vec![255; 512]).encode();
+
+        assert_eq!(638, bytes.len());
+        // Preamble, postamble, ACN packet identifier
+        assert_eq!(&[0x00, 0x10, 0x00, 0x00], &bytes[0..4]);
+        assert_eq!(b"ASC-E1.17\0\0\0", &bytes[4..16]);
+        // Flags and lengths of the three layers
+        assert_eq!(&[0x72, 0x6E], &bytes[16..18]);
+        assert_eq!(&[0x72, 0x58], &bytes[38..40]);
+        assert_eq!(&[0x72, 0x0B], &bytes[115..117]);
+        // Vectors
+        assert_eq!(&[0, 0, 0, 4], &bytes[18..22]);
+        assert_eq!(&[0, 0, 0, 2], &bytes[40..44]);
+        assert_eq!(&[0x02, 0xA1], &bytes[117..119]);
+        assert_eq!(0x01, bytes[22]);
+        assert_eq!(0x10, bytes[37]);
+        assert_eq!(b"wovmoh\0", &bytes[44..51]);
+        // Priority, sync address, sequence, options, universe
+        assert_eq!(&[100, 0, 0, 7, 0, 0, 1], &bytes[108..115]);
+        // First address, increment, count with start code, start code
+        assert_eq!(
+            &[0x00, 0x00, 0x00, 0x01, 0x02, 0x01, 0x00],
+            &bytes[119..126]
+        );
+        assert_eq!(255, bytes[126]);
+        assert_eq!(255, bytes[637]);
+
+        assert_eq!(Ok(data(1, vec![255; 512])), SacnData::decode(&bytes));
+    }
+
+    #[test]
+    fn short_universes_and_termination() {
+        let mut packet = data(63999, vec![1, 2, 3]);
+        packet.terminated = true;
+        packet.source_name = "x".repeat(100);
+        let bytes = packet.encode();
+
+        assert_eq!(129, bytes.len());
+        assert_eq!(&[0x70, 0x71], &bytes[16..18]);
+        assert_eq!(&[0x70, 0x0E], &bytes[115..117]);
+        assert_eq!(0x40, bytes[112]);
+        assert_eq!(&[0xF9, 0xFF], &bytes[113..115]);
+        // Names are cut to leave room for the terminator
+        assert_eq!(0, bytes[107]);
+
+        let decoded = SacnData::decode(&bytes).unwrap();
+        assert!(decoded.terminated);
+        assert_eq!(63, decoded.source_name.len());
+        assert_eq!(vec![1, 2, 3], decoded.data);
+    }
+
+    #[test]
+    fn rejects_bad_packets() {
+        let bytes = data(1, vec![0; 4]).encode();
+
+        assert_eq!(Err(SacnError::NotSacn), SacnData::decode(b"Art-Net\0"));
+        assert_eq!(Err(SacnError::Truncated), SacnData::decode(&bytes[..120]));
+
+        let mut wrong_vector = bytes.clone();
+        wrong_vector[21] = 8;
+        assert_eq!(
+            Err(SacnError::UnsupportedVector(8)),
+            SacnData::decode(&wrong_vector)
+        );
+
+        let mut wrong_start_code = bytes.clone();
+        wrong_start_code[125] = 0xCC;
+        assert_eq!(
+            Err(SacnError::UnsupportedStartCode(0xCC)),
+            SacnData::decode(&wrong_start_code)
+        );
+    }
+
+    #[test]
+    fn multicast_addresses() {
+        assert_eq!(Ipv4Addr::new(239, 255, 0, 1), multicast_addr(1));
+        assert_eq!(Ipv4Addr::new(239, 255, 1, 0), multicast_addr(256));
+        assert_eq!(Ipv4Addr::new(239, 255, 249, 255), multicast_addr(63999));
+    }
+}
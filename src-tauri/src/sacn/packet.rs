@@ -0,0 +1,169 @@
+use std::fmt::{Display, Formatter};
+use std::net::Ipv4Addr;
+
+use crate::dmx::UNIVERSE_SIZE;
+
+pub const SACN_PORT: u16 = 5568;
+pub const DEFAULT_PRIORITY: u8 = 100;
+pub const MAX_UNIVERSE: u16 = 63999;
+
+const ACN_ID: &[u8; 12] = b"ASC-E1.17\0\0\0";
+const VECTOR_ROOT_E131_DATA: u32 = 0x0000_0004;
+const VECTOR_E131_DATA_PACKET: u32 = 0x0000_0002;
+const VECTOR_DMP_SET_PROPERTY: u8 = 0x02;
+const ADDRESS_DATA_TYPE: u8 = 0xA1;
+
+// Start of each layer's flags and length field
+const ROOT_LAYER: usize = 16;
+const FRAMING_LAYER: usize = 38;
+const DMP_LAYER: usize = 115;
+// Everything before the start code
+const HEADER_SIZE: usize = 125;
+const SOURCE_NAME_SIZE: usize = 64;
+
+// Framing options
+const OPTION_STREAM_TERMINATED: u8 = 0x40;
+
+// E1.31 data packet, one universe of levels
+#[derive(Clone, Debug, PartialEq)]
+pub struct SacnData {
+    // Identifies the sender, constant for its lifetime
+    pub cid: [u8; 16],
+    pub source_name: String,
+    // 0..200, receivers follow the highest
+    pub priority: u8,
+    pub sequence: u8,
+    // Last packet of a source that stops sending
+    pub terminated: bool,
+    // 1..63999
+    pub universe: u16,
+    // Up to 512 levels after the null start code
+    pub data: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SacnError {
+    NotSacn,
+    Truncated,
+    UnsupportedVector(u32),
+    UnsupportedStartCode(u8),
+}
+
+impl Display for SacnError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            SacnError::NotSacn => write!(f, "Not an sACN packet"),
+            SacnError::Truncated => write!(f, "Truncated sACN packet"),
+            SacnError::UnsupportedVector(v) => write!(f, "Unsupported sACN vector {:#010x}", v),
+            SacnError::UnsupportedStartCode(s) => write!(f, "Unsupported start code {:#04x}", s),
+        }
+    }
+}
+
+// Receivers of a universe join 239.255.<universe high byte>.<universe low byte>
+pub fn multicast_addr(universe: u16) -> Ipv4Addr {
+    let [high, low] = universe.to_be_bytes();
+    Ipv4Addr::new(239, 255, high, low)
+}
+
+impl SacnData {
+    pub fn decode(bytes: &[u8]) -> Result<SacnData, SacnError> {
+        if bytes.len() < ROOT_LAYER || bytes[4..16] != ACN_ID[..] {
+            return Err(SacnError::NotSacn);
+        }
+        if bytes.len() < HEADER_SIZE + 1 {
+            return Err(SacnError::Truncated);
+        }
+
+        let vector = read_u32(&bytes[18..22]);
+        if vector != VECTOR_ROOT_E131_DATA {
+            return Err(SacnError::UnsupportedVector(vector));
+        }
+        let vector = read_u32(&bytes[40..44]);
+        if vector != VECTOR_E131_DATA_PACKET {
+            return Err(SacnError::UnsupportedVector(vector));
+        }
+
+        // Property value count includes the start code
+        let count = u16::from_be_bytes([bytes[123], bytes[124]]) as usize;
+        if count == 0 || bytes.len() < HEADER_SIZE + count {
+            return Err(SacnError::Truncated);
+        }
+        if bytes[HEADER_SIZE] != 0 {
+            return Err(SacnError::UnsupportedStartCode(bytes[HEADER_SIZE]));
+        }
+
+        let mut cid = [0; 16];
+        cid.copy_from_slice(&bytes[22..38]);
+
+        let name = &bytes[44..44 + SOURCE_NAME_SIZE];
+        let end = name.iter().position(|x| *x == 0).unwrap_or(name.len());
+
+        Ok(SacnData {
+            cid,
+            source_name: String::from_utf8_lossy(&name[..end]).into_owned(),
+            priority: bytes[108],
+            sequence: bytes[111],
+            terminated: bytes[112] & OPTION_STREAM_TERMINATED != 0,
+            universe: u16::from_be_bytes([bytes[113], bytes[114]]),
+            data: bytes[HEADER_SIZE + 1..HEADER_SIZE + count].to_vec(),
+        })
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let data = &self.data[..self.data.len().min(UNIVERSE_SIZE)];
+        let size = HEADER_SIZE + 1 + data.len();
+
+        let mut bytes = Vec::with_capacity(size);
+
+        // Root layer
+        bytes.extend_from_slice(&0x0010u16.to_be_bytes());
+        bytes.extend_from_slice(&0x0000u16.to_be_bytes());
+        bytes.extend_from_slice(ACN_ID);
+        bytes.extend_from_slice(&flags_and_length(size - ROOT_LAYER));
+        bytes.extend_from_slice(&VECTOR_ROOT_E131_DATA.to_be_bytes());
+        bytes.extend_from_slice(&self.cid);
+
+        // Framing layer
+        bytes.extend_from_slice(&flags_and_length(size - FRAMING_LAYER));
+        bytes.extend_from_slice(&VECTOR_E131_DATA_PACKET.to_be_bytes());
+        let mut name = [0u8; SOURCE_NAME_SIZE];
+        // Null terminated, so at most 63 bytes of the name
+        let len = self.source_name.len().min(SOURCE_NAME_SIZE - 1);
+        name[..len].copy_from_slice(&self.source_name.as_bytes()[..len]);
+        bytes.extend_from_slice(&name);
+        bytes.push(self.priority.min(200));
+        // No synchronization
+        bytes.extend_from_slice(&0u16.to_be_bytes());
+        bytes.push(self.sequence);
+        bytes.push(if self.terminated {
+            OPTION_STREAM_TERMINATED
+        } else {
+            0
+        });
+        bytes.extend_from_slice(&self.universe.to_be_bytes());
+
+        // DMP layer
+        bytes.extend_from_slice(&flags_and_length(size - DMP_LAYER));
+        bytes.push(VECTOR_DMP_SET_PROPERTY);
+        bytes.push(ADDRESS_DATA_TYPE);
+        // First property address, address increment
+        bytes.extend_from_slice(&0x0000u16.to_be_bytes());
+        bytes.extend_from_slice(&0x0001u16.to_be_bytes());
+        bytes.extend_from_slice(&(data.len() as u16 + 1).to_be_bytes());
+        // Null start code
+        bytes.push(0);
+        bytes.extend_from_slice(data);
+
+        bytes
+    }
+}
+
+// High 4 bits are always 0x7, low 12 bits the length from the field itself
+fn flags_and_length(length: usize) -> [u8; 2] {
+    (0x7000 | (length as u16 & 0x0FFF)).to_be_bytes()
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
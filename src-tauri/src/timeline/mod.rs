@@ -0,0 +1,309 @@
+pub mod transport;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::input_field::InputId;
+use crate::types::data_type::DataValue;
+use transport::Transport;
+
+// How a keyframe goes to the next one
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Interpolation {
+    // Holds until the next keyframe
+    Step,
+    Linear,
+    // Timing curve through (0, 0), (x1, y1), (x2, y2), (1, 1), as in CSS
+    Bezier { x1: f64, y1: f64, x2: f64, y2: f64 },
+}
+
+impl Interpolation {
+    // Progress 0..1 towards the next keyframe, t the fraction of the time between them
+    pub fn progress(&self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match *self {
+            Interpolation::Step => 0.0,
+            Interpolation::Linear => t,
+            Interpolation::Bezier { x1, y1, x2, y2 } => {
+                // x is monotonic with x1 and x2 in 0..1, bisect for the curve parameter
+                let (x1, x2) = (x1.clamp(0.0, 1.0), x2.clamp(0.0, 1.0));
+                let (mut low, mut high) = (0.0, 1.0);
+                for _ in 0..32 {
+                    let mid = (low + high) / 2.0;
+                    if bezier(mid, x1, x2) < t {
+                        low = mid;
+                    } else {
+                        high = mid;
+                    }
+                }
+                bezier((low + high) / 2.0, y1, y2)
+            }
+        }
+    }
+}
+
+// One dimension of a cubic bezier from 0 to 1
+fn bezier(t: f64, p1: f64, p2: f64) -> f64 {
+    let u = 1.0 - t;
+    3.0 * u * u * t * p1 + 3.0 * u * t * t * p2 + t * t * t
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Keyframe {
+    // Seconds from the start of the timeline
+    pub time: f64,
+    pub value: DataValue,
+    pub interpolation: Interpolation,
+}
+
+// Keyframes of one input field, in time order
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Track {
+    pub input: InputId,
+    pub keyframes: Vec<Keyframe>,
+}
+
+impl Track {
+    pub fn new(input: InputId) -> Track {
+        Track {
+            input,
+            keyframes: Vec::new(),
+        }
+    }
+
+    // Replaces the keyframe at the same time
+    pub fn set_keyframe(&mut self, keyframe: Keyframe) {
+        self.remove_keyframe(keyframe.time);
+        let index = self.keyframes.partition_point(|x| x.time < keyframe.time);
+        self.keyframes.insert(index, keyframe);
+    }
+
+    pub fn remove_keyframe(&mut self, time: f64) -> bool {
+        let count = self.keyframes.len();
+        self.keyframes.retain(|x| x.time != time);
+        self.keyframes.len() != count
+    }
+
+    // Before the first keyframe its value, after the last one its value.
+    // None without keyframes.
+    pub fn value_at(&self, time: f64) -> Option<DataValue> {
+        let next = self.keyframes.partition_point(|x| x.time <= time);
+        if next == 0 {
+            return self.keyframes.first().map(|x| x.value);
+        }
+
+        let key = &self.keyframes[next - 1];
+        let Some(to) = self.keyframes.get(next) else {
+            return Some(key.value);
+        };
+        let t = (time - key.time) / (to.time - key.time);
+        let progress = key.interpolation.progress(t);
+        Some(key.value.interpolate(&to.value, progress))
+    }
+}
+
+// Automation of the input fields of a graph. Graph::tick applies it before
+// processing the frame.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Timeline {
+    pub tracks: Vec<Track>,
+    pub transport: Transport,
+}
+
+impl Timeline {
+    pub fn track(&self, input: InputId) -> Option<&Track> {
+        self.tracks.iter().find(|x| x.input == input)
+    }
+
+    // The track of the input, made when missing
+    pub fn track_mut(&mut self, input: InputId) -> &mut Track {
+        let index = match self.tracks.iter().position(|x| x.input == input) {
+            Some(index) => index,
+            None => {
+                self.tracks.push(Track::new(input));
+                self.tracks.len() - 1
+            }
+        };
+        &mut self.tracks[index]
+    }
+
+    pub fn remove_track(&mut self, input: InputId) {
+        self.tracks.retain(|x| x.input != input);
+    }
+
+    // Time of the last keyframe
+    pub fn length(&self) -> f64 {
+        self.tracks
+            .iter()
+            .filter_map(|x| x.keyframes.last())
+            .map(|x| x.time)
+            .fold(0.0, f64::max)
+    }
+
+    // Moves the transport on by delta seconds, then the value of every track
+    // at the playhead. Nothing while paused, unless it was just seeked.
+    pub fn advance(&mut self, delta: f64) -> Vec<(InputId, DataValue)> {
+        if !self.transport.advance(delta) {
+            return Vec::new();
+        }
+        self.values_at(self.transport.position)
+    }
+
+    pub fn values_at(&self, time: f64) -> Vec<(InputId, DataValue)> {
+        self.tracks
+            .iter()
+            .filter_map(|x| Some((x.input, x.value_at(time)?)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::transport::LoopRegion;
+    use super::*;
+    use crate::core::graph::Graph;
+    use crate::core::node::{NodeKind, NodeUIState};
+    use crate::types::data_type::DataValue::*;
+
+    fn key(time: f64, value: DataValue, interpolation: Interpolation) -> Keyframe {
+        Keyframe {
+            time,
+            value,
+            interpolation,
+        }
+    }
+
+    fn number(track: &Track, time: f64) -> f64 {
+        match track.value_at(time) {
+            Some(Number(x)) => (x * 1000.0).round() / 1000.0,
+            _ => panic!("Expected Number"),
+        }
+    }
+
+    #[test]
+    fn track_interpolates_keyframes() {
+        let ease = Interpolation::Bezier {
+            x1: 0.42,
+            y1: 0.0,
+            x2: 0.58,
+            y2: 1.0,
+        };
+        let mut track = Track::new(0);
+        assert_eq!(None, track.value_at(1.0));
+
+        track.set_keyframe(key(2.0, Number(1.0), Interpolation::Step));
+        track.set_keyframe(key(0.0, Number(0.0), Interpolation::Linear));
+        track.set_keyframe(key(3.0, Number(0.0), ease));
+        track.set_keyframe(key(5.0, Number(1.0), Interpolation::Linear));
+        track.set_keyframe(key(4.0, Number(0.5), Interpolation::Step));
+        assert!(track.remove_keyframe(4.0));
+        assert_eq!(4, track.keyframes.len());
+
+        assert_eq!(0.0, number(&track, -1.0));
+        assert_eq!(0.25, number(&track, 0.5));
+        assert_eq!(1.0, number(&track, 2.0));
+        assert_eq!(1.0, number(&track, 2.99));
+        assert_eq!(0.0, number(&track, 3.0));
+
+        // Ease in-out: symmetric, slow at the ends
+        assert_eq!(0.5, number(&track, 4.0));
+        assert!(number(&track, 3.2) < 0.05);
+        assert_eq!(1.0 - number(&track, 3.2), number(&track, 4.8));
+        assert_eq!(1.0, number(&track, 9.0));
+
+        // Booleans switch halfway
+        let mut track = Track::new(0);
+        track.set_keyframe(key(0.0, Boolean(false), Interpolation::Linear));
+        track.set_keyframe(key(1.0, Boolean(true), Interpolation::Linear));
+        assert_eq!(Some(Boolean(false)), track.value_at(0.49));
+        assert_eq!(Some(Boolean(true)), track.value_at(0.5));
+    }
+
+    #[test]
+    fn transport_plays_seeks_and_loops() {
+        let mut timeline = Timeline::default();
+        let track = timeline.track_mut(3);
+        track.set_keyframe(key(0.0, Number(0.0), Interpolation::Linear));
+        track.set_keyframe(key(4.0, Number(4.0), Interpolation::Linear));
+        assert_eq!(4.0, timeline.length());
+
+        // Paused, a seek applies once
+        assert!(timeline.advance(0.5).is_empty());
+        timeline.transport.seek(1.0);
+        assert_eq!(vec![(3, Number(1.0))], timeline.advance(0.5));
+        assert!(timeline.advance(0.5).is_empty());
+
+        timeline.transport.play();
+        assert_eq!(vec![(3, Number(1.5))], timeline.advance(0.5));
+
+        timeline.transport.set_loop(Some(LoopRegion {
+            start: 1.0,
+            end: 2.0,
+        }));
+        assert_eq!(vec![(3, Number(1.25))], timeline.advance(0.75));
+
+        timeline.transport.set_loop(Some(LoopRegion {
+            start: 2.0,
+            end: 2.0,
+        }));
+        assert_eq!(None, timeline.transport.loop_region);
+        timeline.transport.pause();
+        assert!(timeline.advance(1.0).is_empty());
+        assert_eq!(1.25, timeline.transport.position);
+    }
+
+    #[test]
+    fn graph_applies_timeline_before_processing() {
+        let mut graph = Graph::new(0);
+        let ui_state = NodeUIState {
+            position: (0.0, 0.0),
+        };
+        graph.add_node(NodeKind::ConstantNumber, ui_state.clone());
+        graph.add_node(NodeKind::ConstantNumber, ui_state);
+        // Number -> Number, the second input is driven and left alone
+        graph.connect(0, 1);
+
+        let timeline = graph.timeline_mut();
+        for input in [0, 1] {
+            let track = timeline.track_mut(input);
+            track.set_keyframe(key(0.0, Number(0.0), Interpolation::Linear));
+            track.set_keyframe(key(1.0, Number(10.0), Interpolation::Linear));
+        }
+        timeline.transport.play();
+
+        graph.tick(0.25);
+        // The output already has the value of this frame
+        assert_eq!(Number(2.5), graph.get_output_value(0));
+        assert_eq!(Number(2.5), graph.get_output_value(1));
+        graph.tick(0.25);
+        assert_eq!(Number(5.0), graph.get_input_value(0));
+        assert_eq!(Number(0.0), graph.get_input_value(1));
+    }
+
+    #[test]
+    fn graph_checks_keyframes() {
+        let mut graph = Graph::new(0);
+        graph.add_node(
+            NodeKind::ConstantNumber,
+            NodeUIState {
+                position: (0.0, 0.0),
+            },
+        );
+
+        let error = graph.set_keyframe(0, key(0.0, Boolean(true), Interpolation::Step));
+        assert_eq!(Err("Expected Number".to_string()), error);
+        let error = graph.set_keyframe(5, key(0.0, Number(1.0), Interpolation::Step));
+        assert_eq!(Err("Unknown input 5".to_string()), error);
+        assert!(graph.timeline().tracks.is_empty());
+
+        // Removing from a field without a track doesn't add one
+        assert!(!graph.remove_keyframe(0, 0.0));
+        assert!(graph.timeline().tracks.is_empty());
+
+        graph
+            .set_keyframe(0, key(1.0, Number(1.0), Interpolation::Step))
+            .unwrap();
+        assert!(graph.remove_keyframe(0, 1.0));
+        assert!(!graph.remove_keyframe(0, 1.0));
+    }
+}
@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+
+// Start and end in seconds, the playhead jumps back to start on reaching end
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LoopRegion {
+    pub start: f64,
+    pub end: f64,
+}
+
+// Playhead of a timeline, moved on by the graph clock while playing
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Transport {
+    pub playing: bool,
+    // Seconds from the start of the timeline
+    pub position: f64,
+    pub loop_region: Option<LoopRegion>,
+    // Set by seek, so a paused timeline still applies the new position once
+    #[serde(skip)]
+    moved: bool,
+}
+
+impl Transport {
+    pub fn play(&mut self) {
+        self.playing = true;
+    }
+
+    pub fn pause(&mut self) {
+        self.playing = false;
+    }
+
+    pub fn seek(&mut self, position: f64) {
+        self.position = position.max(0.0);
+        self.moved = true;
+    }
+
+    // None plays on, regions shorter than a frame are ignored
+    pub fn set_loop(&mut self, region: Option<LoopRegion>) {
+        self.loop_region = region.filter(|x| x.end > x.start);
+    }
+
+    // Moves the playhead by delta seconds while playing, true when the
+    // timeline should be applied
+    pub fn advance(&mut self, delta: f64) -> bool {
+        let moved = std::mem::take(&mut self.moved);
+        if !self.playing {
+            return moved;
+        }
+
+        self.position += delta;
+        if let Some(region) = self.loop_region {
+            if self.position >= region.end {
+                let length = region.end - region.start;
+                self.position = region.start + (self.position - region.end) % length;
+            }
+        }
+        true
+    }
+}
@@ -1,7 +1,9 @@
 use std::cmp::PartialEq;
 use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+
+use super::curve::to_u8;
 
 pub enum ColorValue {
     RGB(f64, f64, f64),
@@ -10,7 +12,7 @@ pub enum ColorValue {
     HEXA([char; 8]),
 }
 
-#[derive(Copy, Clone, Default, Serialize, Debug)]
+#[derive(Copy, Clone, Default, Serialize, Deserialize, Debug)]
 pub struct Color {
     e: [f64; 4],
 }
@@ -65,9 +67,9 @@ impl Color {
     pub fn hex(&self) -> String {
         format!(
             "{:02X}{:02X}{:02X}",
-            (self.r() * 255.0) as u8,
-            (self.g() * 255.0) as u8,
-            (self.b() * 255.0) as u8,
+            to_u8(self.r()),
+            to_u8(self.g()),
+            to_u8(self.b()),
         )
         .to_lowercase()
     }
@@ -75,14 +77,62 @@ impl Color {
     pub fn hexa(&self) -> String {
         format!(
             "{:02X}{:02X}{:02X}{:02X}",
-            (self.r() * 255.0) as u8,
-            (self.g() * 255.0) as u8,
-            (self.b() * 255.0) as u8,
-            (self.a() * 255.0) as u8,
+            to_u8(self.r()),
+            to_u8(self.g()),
+            to_u8(self.b()),
+            to_u8(self.a()),
         )
         .to_lowercase()
     }
 
+    // Mix towards other in OKLab, so a fade keeps its brightness and doesn't
+    // go through grey. Alpha is mixed linearly.
+    pub fn mix(&self, other: Color, t: f64) -> Color {
+        // Exact at the ends
+        if t <= 0.0 {
+            return *self;
+        } else if t >= 1.0 {
+            return other;
+        }
+        let (l0, a0, b0) = self.to_oklab();
+        let (l1, a1, b1) = other.to_oklab();
+        let lerp = |x: f64, y: f64| x + (y - x) * t;
+        Color::from_oklab(
+            lerp(l0, l1),
+            lerp(a0, a1),
+            lerp(b0, b1),
+            lerp(self.a(), other.a()),
+        )
+    }
+
+    pub fn to_oklab(&self) -> (f64, f64, f64) {
+        let (r, g, b) = (
+            to_linear(self.r()),
+            to_linear(self.g()),
+            to_linear(self.b()),
+        );
+        let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+        let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+        let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+        (
+            0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+            1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+            0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+        )
+    }
+
+    pub fn from_oklab(l: f64, a: f64, b: f64, alpha: f64) -> Color {
+        let l_ = (l + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+        let m_ = (l - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+        let s_ = (l - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+        Color::new(ColorValue::RGBA(
+            from_linear(4.0767416621 * l_ - 3.3077115913 * m_ + 0.2309699292 * s_),
+            from_linear(-1.2684380046 * l_ + 2.6097574011 * m_ - 0.3413193965 * s_),
+            from_linear(-0.0041960863 * l_ - 0.7034186147 * m_ + 1.7076147010 * s_),
+            alpha,
+        ))
+    }
+
     fn clamp(&self) -> Color {
         Color::new(ColorValue::RGBA(
             clamp_x(self.r()),
@@ -249,6 +299,27 @@ impl PartialEq for Color {
     }
 }
 
+// sRGB transfer, mirrored below 0 so out of range colours survive a round trip
+fn to_linear(x: f64) -> f64 {
+    let y = x.abs();
+    let y = if y <= 0.04045 {
+        y / 12.92
+    } else {
+        ((y + 0.055) / 1.055).powf(2.4)
+    };
+    y.copysign(x)
+}
+
+fn from_linear(x: f64) -> f64 {
+    let y = x.abs();
+    let y = if y <= 0.0031308 {
+        y * 12.92
+    } else {
+        1.055 * y.powf(1.0 / 2.4) - 0.055
+    };
+    y.copysign(x)
+}
+
 fn clamp_x(x: f64) -> f64 {
     x.min(1.0).max(0.0)
 }
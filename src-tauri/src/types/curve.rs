@@ -0,0 +1,95 @@
+// Response curves for dimmers, and conversion of 0..1 values to DMX resolutions.
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Curve<'a> {
+    Linear,
+    Square,
+    SCurve,
+    Gamma(f64),
+    // Evenly spaced output values over 0..1, linearly interpolated between.
+    // Borrowed, e.g. from the node cache, so nothing is copied per frame.
+    Table(&'a [f64]),
+}
+
+impl<'a> Curve<'a> {
+    // Index used by the DimmerCurve node's "Curve" input, 4 is the table
+    // parsed from its text
+    pub fn from_index(index: f64, gamma: f64, table: &'a [f64]) -> Curve<'a> {
+        match index.round() as i64 {
+            1 => Curve::Square,
+            2 => Curve::SCurve,
+            3 => Curve::Gamma(gamma),
+            4 => Curve::Table(table),
+            _ => Curve::Linear,
+        }
+    }
+
+    // Output values between 0 and 1, separated by commas or spaces,
+    // e.g. "0, 0.1, 0.3, 1"
+    pub fn parse_table(text: &str) -> Result<Vec<f64>, String> {
+        text.split(|x: char| x == ',' || x.is_whitespace())
+            .filter(|x| !x.is_empty())
+            .map(|x| match x.parse::<f64>() {
+                Ok(value) if (0.0..=1.0).contains(&value) => Ok(value),
+                _ => Err(format!("'{}' isn't a level between 0 and 1", x)),
+            })
+            .collect()
+    }
+
+    pub fn apply(&self, x: f64) -> f64 {
+        let x = clamp_unit(x);
+
+        match self {
+            Curve::Linear => x,
+            Curve::Square => x * x,
+            Curve::SCurve => x * x * (3.0 - 2.0 * x),
+            Curve::Gamma(gamma) => x.powf(*gamma),
+            Curve::Table(table) => lookup(table, x),
+        }
+    }
+}
+
+fn lookup(table: &[f64], x: f64) -> f64 {
+    match table.len() {
+        0 => x,
+        1 => table[0],
+        len => {
+            let position = x * (len - 1) as f64;
+            let index = (position.floor() as usize).min(len - 2);
+            let t = position - index as f64;
+
+            table[index] + (table[index + 1] - table[index]) * t
+        }
+    }
+}
+
+pub fn clamp_unit(x: f64) -> f64 {
+    if x.is_nan() {
+        return 0.0;
+    }
+    x.clamp(0.0, 1.0)
+}
+
+// 0..1 -> 0..255, rounded to nearest
+pub fn to_u8(x: f64) -> u8 {
+    (clamp_unit(x) * 255.0).round() as u8
+}
+
+// 0..1 -> 0..65535, rounded to nearest
+pub fn to_u16(x: f64) -> u16 {
+    (clamp_unit(x) * 65535.0).round() as u16
+}
+
+// 0..1 -> (coarse, fine) channel pair for 16 bit attributes
+pub fn to_coarse_fine(x: f64) -> (u8, u8) {
+    let value = to_u16(x);
+    ((value >> 8) as u8, (value & 0xFF) as u8)
+}
+
+pub fn from_u8(value: u8) -> f64 {
+    value as f64 / 255.0
+}
+
+pub fn from_coarse_fine(coarse: u8, fine: u8) -> f64 {
+    ((coarse as u16) << 8 | fine as u16) as f64 / 65535.0
+}
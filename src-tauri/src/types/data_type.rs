@@ -1,22 +1,41 @@
 use super::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::cmp::PartialEq;
+use std::fmt::{Display, Formatter};
 use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
 #[derive(Copy, Clone, Serialize)]
 pub enum DataType {
     Number,
     Boolean,
+    // Momentary event, true for exactly one frame (see Graph::clear_triggers)
+    Trigger,
     Color,
     Vector3,
     Point3,
+    // Accepts any value, e.g. the inputs of a Select node
+    Any,
 }
 
-#[derive(Copy, Clone, Serialize, Debug)]
+impl DataType {
+    // Values of the type, every value for Any
+    pub fn accepts(&self, value: &DataValue) -> bool {
+        matches!(self, DataType::Any) || DataValue::default(*self).type_name() == value.type_name()
+    }
+
+    // Whether an output of the type can feed a field of the other, Any on
+    // either side can
+    pub fn connects_to(&self, field: DataType) -> bool {
+        matches!(self, DataType::Any) || field.accepts(&DataValue::default(*self))
+    }
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize, Debug)]
 #[serde(tag = "type", content = "value")]
 pub enum DataValue {
     Number(f64),
     Boolean(bool),
+    Trigger(bool),
     Color(color::Color),
     Vector3(vector::Vec3),
     Point3(vector::Point3),
@@ -28,6 +47,7 @@ impl DataValue {
         match self {
             DataValue::Number(_) => "Number",
             DataValue::Boolean(_) => "Boolean",
+            DataValue::Trigger(_) => "Trigger",
             DataValue::Color(_) => "Color",
             DataValue::Vector3(_) => "Vector3",
             DataValue::Point3(_) => "Point3",
@@ -38,14 +58,109 @@ impl DataValue {
         match data_type {
             DataType::Number => DataValue::Number(0.0),
             DataType::Boolean => DataValue::Boolean(false),
+            DataType::Trigger => DataValue::Trigger(false),
             DataType::Color => DataValue::Color(color::Color::default()),
             DataType::Vector3 => DataValue::Vector3(vector::Vec3::default()),
             DataType::Point3 => DataValue::Point3(vector::Point3::default()),
+            DataType::Any => DataValue::Number(0.0),
         }
     }
+
+    // Triggers fire on their frame, Booleans while they are true
+    pub fn is_active(&self) -> bool {
+        match self {
+            DataValue::Trigger(b) | DataValue::Boolean(b) => *b,
+            _ => false,
+        }
+    }
+
+    // From self at t = 0 to other at t = 1. Colours mix perceptually, anything
+    // that can't be mixed switches halfway.
+    pub fn interpolate(&self, other: &DataValue, t: f64) -> DataValue {
+        let t = t.clamp(0.0, 1.0);
+        match (self, other) {
+            (DataValue::Number(a), DataValue::Number(b)) => DataValue::Number(a + (b - a) * t),
+            (DataValue::Color(a), DataValue::Color(b)) => DataValue::Color(a.mix(*b, t)),
+            (DataValue::Vector3(a), DataValue::Vector3(b)) => {
+                DataValue::Vector3(*a + (*b - *a) * t)
+            }
+            (DataValue::Point3(a), DataValue::Point3(b)) => DataValue::Point3(*a + (*b - *a) * t),
+            _ if t < 0.5 => *self,
+            _ => *other,
+        }
+    }
+
+    // Components of the numeric types, Color includes alpha
+    pub fn components(&self) -> Option<Vec<f64>> {
+        match self {
+            DataValue::Number(n) => Some(vec![*n]),
+            DataValue::Vector3(v) | DataValue::Point3(v) => Some(vec![v.x(), v.y(), v.z()]),
+            DataValue::Color(c) => Some(vec![c.r(), c.g(), c.b(), c.a()]),
+            _ => None,
+        }
+    }
+
+    // Same type as self, built from components()
+    fn with_components(&self, c: &[f64]) -> DataValue {
+        match self {
+            DataValue::Vector3(_) => DataValue::Vector3(vector::Vec3::new(c[0], c[1], c[2])),
+            DataValue::Point3(_) => DataValue::Point3(vector::Point3::new(c[0], c[1], c[2])),
+            DataValue::Color(_) => DataValue::Color(color::Color::new(color::ColorValue::RGBA(
+                c[0], c[1], c[2], c[3],
+            ))),
+            _ => DataValue::Number(c[0]),
+        }
+    }
+
+    // Applies f to each component of the values. The result takes the type of the first non Number value,
+    // Numbers are used for every component, e.g. lerp(Color, Color, Number).
+    pub fn map_components(
+        op: &'static str,
+        values: &[DataValue],
+        f: impl Fn(&[f64]) -> f64,
+    ) -> Result<DataValue, EvalError> {
+        let shape = values
+            .iter()
+            .find(|x| !matches!(x, DataValue::Number(_)))
+            .copied()
+            .unwrap_or(DataValue::Number(0.0));
+
+        let shape_components = match shape.components() {
+            Some(c) => c.len(),
+            None => {
+                return Err(EvalError::TypeError {
+                    op,
+                    lhs: shape.type_name(),
+                    rhs: shape.type_name(),
+                })
+            }
+        };
+
+        let mut columns = Vec::with_capacity(values.len());
+        for value in values {
+            match (value, value.components()) {
+                (DataValue::Number(n), _) => columns.push(vec![*n; shape_components]),
+                (_, Some(c)) if value.type_name() == shape.type_name() => columns.push(c),
+                _ => {
+                    return Err(EvalError::TypeError {
+                        op,
+                        lhs: shape.type_name(),
+                        rhs: value.type_name(),
+                    })
+                }
+            }
+        }
+
+        let result: Vec<f64> = (0..shape_components)
+            .map(|i| f(&columns.iter().map(|c| c[i]).collect::<Vec<f64>>()))
+            .collect();
+
+        Ok(shape.with_components(&result))
+    }
 }
 
 // Error type for DataValue ops
+#[derive(Debug)]
 pub enum EvalError {
     TypeError {
         op: &'static str,
@@ -59,6 +174,23 @@ pub enum EvalError {
     },
 }
 
+impl Display for EvalError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            EvalError::TypeError { op, lhs, rhs } => {
+                write!(
+                    f,
+                    "Type error: cannot apply '{}' to {} and {}",
+                    op, lhs, rhs
+                )
+            }
+            EvalError::UnsupportedOp { op, lhs, rhs } => {
+                write!(f, "Unsupported: '{}' between {} and {}", op, lhs, rhs)
+            }
+        }
+    }
+}
+
 impl Add for DataValue {
     type Output = Result<DataValue, EvalError>;
 
@@ -73,11 +205,13 @@ impl Add for DataValue {
             (Vector3(a), Vector3(b)) => Ok(Vector3(a + b)),
             (Point3(a), Point3(b)) => Ok(Point3(a + b)),
 
-            (Boolean(_), _) | (_, Boolean(_)) => Err(EvalError::TypeError {
-                op: "+",
-                lhs: self.type_name(),
-                rhs: rhs.type_name(),
-            }),
+            (Boolean(_), _) | (_, Boolean(_)) | (Trigger(_), _) | (_, Trigger(_)) => {
+                Err(EvalError::TypeError {
+                    op: "+",
+                    lhs: self.type_name(),
+                    rhs: rhs.type_name(),
+                })
+            }
 
             _ => Err(EvalError::UnsupportedOp {
                 op: "+",
@@ -102,11 +236,13 @@ impl Sub for DataValue {
             (Vector3(a), Vector3(b)) => Ok(Vector3(a - b)),
             (Point3(a), Point3(b)) => Ok(Point3(a - b)),
 
-            (Boolean(_), _) | (_, Boolean(_)) => Err(EvalError::TypeError {
-                op: "-",
-                lhs: self.type_name(),
-                rhs: rhs.type_name(),
-            }),
+            (Boolean(_), _) | (_, Boolean(_)) | (Trigger(_), _) | (_, Trigger(_)) => {
+                Err(EvalError::TypeError {
+                    op: "-",
+                    lhs: self.type_name(),
+                    rhs: rhs.type_name(),
+                })
+            }
 
             _ => Err(EvalError::UnsupportedOp {
                 op: "-",
@@ -131,11 +267,13 @@ impl Mul for DataValue {
             (Vector3(a), Vector3(b)) => Ok(Vector3(a * b)),
             (Point3(a), Point3(b)) => Ok(Point3(a * b)),
 
-            (Boolean(_), _) | (_, Boolean(_)) => Err(EvalError::TypeError {
-                op: "+",
-                lhs: self.type_name(),
-                rhs: rhs.type_name(),
-            }),
+            (Boolean(_), _) | (_, Boolean(_)) | (Trigger(_), _) | (_, Trigger(_)) => {
+                Err(EvalError::TypeError {
+                    op: "+",
+                    lhs: self.type_name(),
+                    rhs: rhs.type_name(),
+                })
+            }
 
             _ => Err(EvalError::UnsupportedOp {
                 op: "+",
@@ -160,11 +298,13 @@ impl Div for DataValue {
             (Vector3(a), Vector3(b)) => Ok(Vector3(a / b)),
             (Point3(a), Point3(b)) => Ok(Point3(a / b)),
 
-            (Boolean(_), _) | (_, Boolean(_)) => Err(EvalError::TypeError {
-                op: "+",
-                lhs: self.type_name(),
-                rhs: rhs.type_name(),
-            }),
+            (Boolean(_), _) | (_, Boolean(_)) | (Trigger(_), _) | (_, Trigger(_)) => {
+                Err(EvalError::TypeError {
+                    op: "+",
+                    lhs: self.type_name(),
+                    rhs: rhs.type_name(),
+                })
+            }
 
             _ => Err(EvalError::UnsupportedOp {
                 op: "+",
@@ -180,6 +320,7 @@ impl PartialEq for DataValue {
         match (self, other) {
             (Self::Number(l0), Self::Number(r0)) => l0 == r0,
             (Self::Boolean(l0), Self::Boolean(r0)) => l0 == r0,
+            (Self::Trigger(l0), Self::Trigger(r0)) => l0 == r0,
             (Self::Color(l0), Self::Color(r0)) => l0 == r0,
             (Self::Vector3(l0), Self::Vector3(r0)) => l0 == r0,
             (Self::Point3(l0), Self::Point3(r0)) => l0 == r0,
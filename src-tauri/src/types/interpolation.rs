@@ -0,0 +1,140 @@
+use std::f64::consts::PI;
+
+pub fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + (b - a) * t
+}
+
+pub fn map_range(x: f64, in_min: f64, in_max: f64, out_min: f64, out_max: f64, clamp: bool) -> f64 {
+    let mut t = if in_max == in_min {
+        0.0
+    } else {
+        (x - in_min) / (in_max - in_min)
+    };
+
+    if clamp {
+        t = t.clamp(0.0, 1.0);
+    }
+
+    lerp(out_min, out_max, t)
+}
+
+pub fn smoothstep(edge0: f64, edge1: f64, x: f64) -> f64 {
+    if edge0 == edge1 {
+        return if x < edge0 { 0.0 } else { 1.0 };
+    }
+
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+// Min and max may be given in either order
+pub fn clamp(x: f64, min: f64, max: f64) -> f64 {
+    if min <= max {
+        x.clamp(min, max)
+    } else {
+        x.clamp(max, min)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Easing {
+    Linear,
+    Quad,
+    Cubic,
+    Sine,
+    Expo,
+    Elastic,
+    Bounce,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum EaseMode {
+    In,
+    Out,
+    InOut,
+}
+
+impl Easing {
+    // Index used by the Ease node's "Family" input
+    pub fn from_index(index: f64) -> Easing {
+        match index.round() as i64 {
+            1 => Easing::Quad,
+            2 => Easing::Cubic,
+            3 => Easing::Sine,
+            4 => Easing::Expo,
+            5 => Easing::Elastic,
+            6 => Easing::Bounce,
+            _ => Easing::Linear,
+        }
+    }
+
+    pub fn apply(&self, mode: EaseMode, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+
+        // Out and InOut are built from the In curve
+        match mode {
+            EaseMode::In => self.ease_in(t),
+            EaseMode::Out => 1.0 - self.ease_in(1.0 - t),
+            EaseMode::InOut => {
+                if t < 0.5 {
+                    self.ease_in(2.0 * t) / 2.0
+                } else {
+                    1.0 - self.ease_in(2.0 - 2.0 * t) / 2.0
+                }
+            }
+        }
+    }
+
+    fn ease_in(&self, t: f64) -> f64 {
+        match self {
+            Easing::Linear => t,
+            Easing::Quad => t * t,
+            Easing::Cubic => t * t * t,
+            Easing::Sine => 1.0 - (t * PI / 2.0).cos(),
+            Easing::Expo => {
+                if t == 0.0 {
+                    0.0
+                } else {
+                    2f64.powf(10.0 * t - 10.0)
+                }
+            }
+            Easing::Elastic => {
+                if t == 0.0 || t == 1.0 {
+                    t
+                } else {
+                    -(2f64.powf(10.0 * t - 10.0)) * ((10.0 * t - 10.75) * (2.0 * PI / 3.0)).sin()
+                }
+            }
+            Easing::Bounce => 1.0 - bounce_out(1.0 - t),
+        }
+    }
+}
+
+impl EaseMode {
+    // Index used by the Ease node's "Mode" input
+    pub fn from_index(index: f64) -> EaseMode {
+        match index.round() as i64 {
+            1 => EaseMode::Out,
+            2 => EaseMode::InOut,
+            _ => EaseMode::In,
+        }
+    }
+}
+
+fn bounce_out(t: f64) -> f64 {
+    const N: f64 = 7.5625;
+    const D: f64 = 2.75;
+
+    if t < 1.0 / D {
+        N * t * t
+    } else if t < 2.0 / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984375
+    }
+}
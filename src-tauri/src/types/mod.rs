@@ -1,5 +1,7 @@
 pub mod color;
+pub mod curve;
 pub mod data_type;
+pub mod interpolation;
 pub mod vector;
 
 use data_type::DataValue;
@@ -8,6 +10,9 @@ use data_type::DataValue;
 #[cfg(test)]
 pub mod tests {
     use super::color::*;
+    use super::curve::*;
+    use super::data_type::DataValue;
+    use super::interpolation::*;
     use super::vector::*;
 
     #[test]
@@ -74,4 +79,137 @@ pub mod tests {
         div_vec /= 2.0;
         assert_eq!(Vec3::new(0.05, 0.125, 0.25), div_vec);
     }
+
+    #[test]
+    fn color_hex_rounds() {
+        let col = Color::new(ColorValue::RGB(0.999, 0.5, 0.0));
+        assert_eq!("ff8000", col.hex());
+    }
+
+    #[test]
+    fn color_mixes_in_oklab() {
+        let red = Color::new(ColorValue::RGB(1.0, 0.0, 0.0));
+        let black = Color::new(ColorValue::RGBA(0.0, 0.0, 0.0, 0.0));
+        let white = Color::new(ColorValue::RGB(1.0, 1.0, 1.0));
+
+        // Half the lightness of white
+        let grey = black.mix(white, 0.5);
+        assert!((grey.r() - 0.3886).abs() < 0.001);
+        assert!((grey.r() - grey.b()).abs() < 0.000001);
+        assert_eq!(0.5, grey.a());
+
+        let (l, a, b) = red.to_oklab();
+        assert!((0.628 - l).abs() < 0.001);
+        let round_trip = Color::from_oklab(l, a, b, 1.0);
+        assert!((round_trip.r() - 1.0).abs() < 0.000001 && round_trip.g().abs() < 0.000001);
+        assert_eq!(white, red.mix(white, 1.0));
+    }
+
+    #[test]
+    fn curves() {
+        assert_eq!(0.25, Curve::Linear.apply(0.25));
+        assert_eq!(0.0625, Curve::Square.apply(0.25));
+        assert_eq!(0.5, Curve::SCurve.apply(0.5));
+        assert_eq!(0.15625, Curve::SCurve.apply(0.25));
+        assert_eq!(0.25, Curve::Gamma(2.0).apply(0.5));
+        assert_eq!(1.0, Curve::Gamma(2.2).apply(2.0));
+        assert_eq!(0.0, Curve::Linear.apply(f64::NAN));
+
+        let table = Curve::Table(&[0.0, 0.5, 0.6, 1.0]);
+        assert_eq!(0.0, table.apply(0.0));
+        assert_eq!(0.25, table.apply(1.0 / 6.0));
+        assert_eq!(0.6, table.apply(2.0 / 3.0));
+        assert_eq!(1.0, table.apply(1.0));
+
+        assert_eq!(Ok(vec![0.0, 0.5, 1.0]), Curve::parse_table("0, 0.5 1"));
+        assert_eq!(Ok(vec![]), Curve::parse_table(" "));
+        assert!(Curve::parse_table("0, 2").is_err());
+        assert!(Curve::parse_table("0, x").is_err());
+        assert_eq!(Curve::Table(&[0.5]), Curve::from_index(4.0, 2.2, &[0.5]));
+    }
+
+    #[test]
+    fn dmx_conversions() {
+        assert_eq!(0, to_u8(-1.0));
+        assert_eq!(128, to_u8(0.5));
+        assert_eq!(255, to_u8(0.999));
+        assert_eq!(255, to_u8(1.5));
+
+        assert_eq!(32768, to_u16(0.5));
+        assert_eq!(65535, to_u16(1.0));
+        assert_eq!((0x80, 0x00), to_coarse_fine(0.5));
+        assert_eq!((0x00, 0x01), to_coarse_fine(1.0 / 65535.0));
+        assert_eq!((0xFF, 0xFF), to_coarse_fine(1.0));
+
+        assert_eq!(1.0, from_u8(255));
+        assert_eq!(0.0, from_coarse_fine(0x00, 0x00));
+        assert_eq!(1.0, from_coarse_fine(0xFF, 0xFF));
+    }
+
+    #[test]
+    fn interpolation() {
+        assert_eq!(0.25, lerp(0.0, 1.0, 0.25));
+        assert_eq!(0.5, map_range(63.5, 0.0, 127.0, 0.0, 1.0, true));
+        assert_eq!(-90.0, map_range(0.0, 0.0, 127.0, -90.0, 90.0, true));
+        assert_eq!(1.0, map_range(254.0, 0.0, 127.0, 0.0, 1.0, true));
+        assert_eq!(2.0, map_range(254.0, 0.0, 127.0, 0.0, 1.0, false));
+        assert_eq!(0.5, clamp(0.5, 1.0, 0.0));
+        assert_eq!(0.0, smoothstep(0.2, 0.4, 0.1));
+        assert_eq!(0.5, smoothstep(0.0, 0.5, 0.25));
+        assert_eq!(1.0, smoothstep(0.2, 0.4, 0.5));
+    }
+
+    #[test]
+    fn easing() {
+        let families = [
+            Easing::Linear,
+            Easing::Quad,
+            Easing::Cubic,
+            Easing::Sine,
+            Easing::Expo,
+            Easing::Elastic,
+            Easing::Bounce,
+        ];
+
+        // Every curve starts at 0 and ends at 1
+        for family in families {
+            for mode in [EaseMode::In, EaseMode::Out, EaseMode::InOut] {
+                assert!(family.apply(mode, 0.0).abs() < 0.001);
+                assert!((family.apply(mode, 1.0) - 1.0).abs() < 0.001);
+            }
+            assert!((family.apply(EaseMode::InOut, 0.5) - 0.5).abs() < 0.00001);
+        }
+
+        assert_eq!(0.25, Easing::Quad.apply(EaseMode::In, 0.5));
+        assert_eq!(0.75, Easing::Quad.apply(EaseMode::Out, 0.5));
+        assert_eq!(0.0625, Easing::Cubic.apply(EaseMode::InOut, 0.25));
+        assert!((Easing::Bounce.apply(EaseMode::Out, 0.5 / 2.75) - 0.25).abs() < 0.00001);
+        assert_eq!(Easing::Elastic, Easing::from_index(5.0));
+        assert_eq!(EaseMode::InOut, EaseMode::from_index(2.0));
+    }
+
+    #[test]
+    fn component_wise() {
+        let a = DataValue::Vector3(Vec3::new(0.0, 1.0, 2.0));
+        let b = DataValue::Vector3(Vec3::new(2.0, 3.0, 4.0));
+        let half = DataValue::Number(0.5);
+
+        let lerped = DataValue::map_components("lerp", &[a, b, half], |x| lerp(x[0], x[1], x[2]));
+        assert_eq!(
+            DataValue::Vector3(Vec3::new(1.0, 2.0, 3.0)),
+            lerped.unwrap()
+        );
+
+        let red = DataValue::Color(Color::new(ColorValue::RGBA(1.0, 0.0, 0.0, 1.0)));
+        let faded = DataValue::map_components("lerp", &[red, DataValue::Number(0.0), half], |x| {
+            lerp(x[0], x[1], x[2])
+        });
+        assert_eq!(
+            DataValue::Color(Color::new(ColorValue::RGBA(0.5, 0.0, 0.0, 0.5))),
+            faded.unwrap()
+        );
+
+        assert!(DataValue::map_components("lerp", &[a, red, half], |x| x[0]).is_err());
+        assert!(DataValue::map_components("lerp", &[DataValue::Boolean(true)], |x| x[0]).is_err());
+    }
 }